@@ -0,0 +1,346 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Half-aggregation of ed25519 signatures, in the construction of Chalkias,
+//! Garillot, Kondi, and Nikolaenko ("Non-interactive half-aggregation of
+//! EdDSA and variants of Schnorr signatures", CT-RSA 2021).
+//!
+//! An `AggregateSignature` on `n` messages is `32·(n + 1)` bytes, rather than
+//! the `64·n` bytes of the individual signatures.  It keeps every `R`, since
+//! each is hashed into its own signature's challenge, but combines the `s`
+//! scalars into one, as `s = z_1·s_1 + … + z_n·s_n`.  The `z_i` are derived
+//! by hashing every `R`, public key, and message together, so that the
+//! aggregate cannot be rearranged.  Simply summing the `s` scalars, with
+//! every `z_i = 1`, would let anyone move value between them, so that an
+//! aggregate could be valid although the signatures in it were not.
+//!
+//! Aggregates are verified with the same, cofactorless, equation as
+//! `PublicKey::verify()`, but the individual signatures cannot be recovered
+//! from them.
+//!
+//! Public keys may also be aggregated, by adding their points, with
+//! `PublicKey::aggregate_with()` and `PublicKey::aggregate_many()`.
+
+use std::vec::Vec;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::Identity;
+use curve25519_dalek::edwards::vartime;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// The domain separation string for the aggregation coefficients.
+const AGGREGATE_DOMAIN: &[u8] = b"ed25519-dalek half-aggregation";
+
+/// Reduce the output of the hash function `D` to a scalar.
+fn reduce<D>(h: D) -> Scalar where D: Digest<OutputSize = U64> + Default {
+    let digest = h.fixed_result();
+
+    Scalar::reduce(array_ref!(digest, 0, 64))
+}
+
+/// Derive the coefficient `z_i` for each signature.
+fn coefficients<D>(rs: &[CompressedEdwardsY], messages: &[&[u8]], public_keys: &[PublicKey])
+        -> Vec<Scalar> where D: Digest<OutputSize = U64> + Default {
+
+    let mut h: D = D::default();
+
+    h.input(AGGREGATE_DOMAIN);
+    h.input(&(rs.len() as u64).to_le_bytes());
+    for i in 0..rs.len() {
+        h.input(rs[i].as_bytes());
+        h.input(public_keys[i].as_bytes());
+        h.input(&(messages[i].len() as u64).to_le_bytes());
+        h.input(messages[i]);
+    }
+    let transcript = h.fixed_result();
+
+    (0..rs.len()).map(|i| {
+        let mut h: D = D::default();
+
+        h.input(&transcript);
+        h.input(&(i as u64).to_le_bytes());
+        reduce(h)
+    }).collect()
+}
+
+/// Check that there is one message and public key for each of `n`
+/// signatures, and at least one signature.
+fn check_lengths(n: usize, messages: &[&[u8]], public_keys: &[PublicKey])
+        -> Result<(), SignatureError> {
+
+    if n == 0 || messages.len() != n || public_keys.len() != n {
+        return Err(SignatureError::InvalidInput);
+    }
+    Ok(())
+}
+
+/// Several signatures, on messages which may differ and under public keys
+/// which may differ, aggregated into one.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AggregateSignature {
+    rs: Vec<CompressedEdwardsY>,
+    s: Scalar,
+}
+
+impl AggregateSignature {
+    /// Convert this aggregate to bytes: the `R` of each signature, in order,
+    /// followed by the aggregated `s`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(32 * (self.rs.len() + 1));
+
+        for r in self.rs.iter() {
+            bytes.extend_from_slice(r.as_bytes());
+        }
+        bytes.extend_from_slice(self.s.as_bytes());
+        bytes
+    }
+
+    /// Construct an aggregate from the bytes produced by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `AggregateSignature`, or whose
+    /// error value is `SignatureError::InvalidEncoding` if the length of the
+    /// bytes is not a multiple of 32 of at least 64.
+    pub fn from_bytes(bytes: &[u8]) -> Result<AggregateSignature, SignatureError> {
+        if bytes.len() < 64 || !bytes.len().is_multiple_of(32) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let (rs, s) = bytes.split_at(bytes.len() - 32);
+
+        Ok(AggregateSignature{
+            rs: rs.chunks(32).map(|chunk| CompressedEdwardsY(*array_ref!(chunk, 0, 32))).collect(),
+            s: Scalar(*array_ref!(s, 0, 32)),
+        })
+    }
+
+    /// The number of signatures in this aggregate.
+    pub fn len(&self) -> usize {
+        self.rs.len()
+    }
+
+    /// Whether this aggregate holds no signatures, which is never the case
+    /// for one from `aggregate_signatures()` or `from_bytes()`.
+    pub fn is_empty(&self) -> bool {
+        self.rs.is_empty()
+    }
+}
+
+impl PublicKey {
+    /// Aggregate this public key with an `other`, by adding their points.
+    ///
+    /// # Warning
+    ///
+    /// This is the raw group operation, with no protection against rogue-key
+    /// attacks: anyone may publish the key `A - B` for some `A` they control,
+    /// so that its aggregate with `B` is `A`.  Before using an aggregate as an
+    /// n-of-n key, check a proof that the holder of each key knows its secret
+    /// key, such as a signature over the key itself.
+    ///
+    /// # Returns
+    ///
+    /// As for `PublicKey::aggregate_many()`.
+    pub fn aggregate_with(&self, other: &PublicKey) -> Option<PublicKey> {
+        PublicKey::aggregate_many(&[*self, *other])
+    }
+
+    /// Aggregate the `public_keys`, by adding their points.
+    ///
+    /// The warning for `PublicKey::aggregate_with()` applies.
+    ///
+    /// # Returns
+    ///
+    /// The aggregate `PublicKey`, or `None` if there are no `public_keys` or
+    /// one of them is not a point on the curve.
+    pub fn aggregate_many(public_keys: &[PublicKey]) -> Option<PublicKey> {
+        if public_keys.is_empty() {
+            return None;
+        }
+        let mut sum: ExtendedPoint = ExtendedPoint::identity();
+
+        for public_key in public_keys {
+            sum = &sum + &public_key.0.decompress()?;
+        }
+        Some(PublicKey(sum.compress()))
+    }
+}
+
+/// Sign each of the `messages` with the `keypair`.
+pub fn sign_multiple<D>(keypair: &Keypair, messages: &[&[u8]]) -> Vec<Signature>
+        where D: Digest<OutputSize = U64> + Default {
+    messages.iter().map(|message| keypair.sign::<D>(message)).collect()
+}
+
+/// Aggregate the `signatures`, where each is on the message and under the
+/// public key at the same position in `messages` and `public_keys`.
+///
+/// The signatures are not checked: an aggregate with an invalid signature
+/// in it will fail to verify.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the `AggregateSignature`, or whose error
+/// value is `SignatureError::InvalidInput` if there are no signatures, or the
+/// number of messages or public keys is not the number of signatures.
+pub fn aggregate_signatures<D>(signatures: &[Signature], messages: &[&[u8]],
+                               public_keys: &[PublicKey])
+        -> Result<AggregateSignature, SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    check_lengths(signatures.len(), messages, public_keys)?;
+
+    let rs: Vec<CompressedEdwardsY> = signatures.iter().map(|signature| signature.r).collect();
+    let zs: Vec<Scalar> = coefficients::<D>(&rs, messages, public_keys);
+    let s: Scalar = signatures.iter().zip(zs.iter())
+        .fold(Scalar::zero(), |s, (signature, z)| Scalar::multiply_add(z, &signature.s, &s));
+
+    Ok(AggregateSignature{ rs, s })
+}
+
+/// Verify an `AggregateSignature` on the `messages`, under the `public_keys`,
+/// in the order in which they were aggregated.
+///
+/// # Returns
+///
+/// * `Ok(())` if the aggregate is valid,
+/// * `Err(SignatureError::InvalidInput)` if the number of messages or public
+///   keys is not the number of signatures in the aggregate, or
+/// * `Err(SignatureError::InvalidSignature)` otherwise.
+pub fn verify_aggregate<D>(aggregate: &AggregateSignature, messages: &[&[u8]],
+                           public_keys: &[PublicKey]) -> Result<(), SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    check_lengths(aggregate.rs.len(), messages, public_keys)?;
+
+    if aggregate.s[31] & 224 != 0 {
+        return Err(SignatureError::InvalidSignature);
+    }
+    let zs: Vec<Scalar> = coefficients::<D>(&aggregate.rs, messages, public_keys);
+    let mut scalars: Vec<Scalar> = Vec::with_capacity(2 * zs.len());
+    let mut points: Vec<ExtendedPoint> = Vec::with_capacity(2 * zs.len());
+
+    for i in 0..zs.len() {
+        let r: ExtendedPoint = aggregate.rs[i].decompress().ok_or(SignatureError::InvalidSignature)?;
+        let a: ExtendedPoint = public_keys[i].0.decompress().ok_or(SignatureError::InvalidSignature)?;
+        let mut h: D = D::default();
+
+        h.input(aggregate.rs[i].as_bytes());
+        h.input(public_keys[i].as_bytes());
+        h.input(messages[i]);
+
+        scalars.push(zs[i]);
+        points.push(r);
+        scalars.push(&zs[i] * &reduce(h));
+        points.push(a);
+    }
+    let expected: ExtendedPoint = vartime::multiscalar_mult(&scalars, &points);
+    let actual: ExtendedPoint = &aggregate.s * &constants::ED25519_BASEPOINT_TABLE;
+
+    if actual.compress() == expected.compress() {
+        Ok(())
+    } else {
+        Err(SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{ExpandedSecretKey, keypair_for_testing};
+    use super::*;
+
+    fn signed_for_testing() -> (Vec<Signature>, Vec<&'static [u8]>, Vec<PublicKey>) {
+        let alice: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+        let messages: Vec<&'static [u8]> = vec![b"first", b"second", b"third"];
+        let mut signatures: Vec<Signature> = sign_multiple::<Sha512>(&alice, &messages[..2]);
+
+        signatures.push(bob.sign::<Sha512>(messages[2]));
+        (signatures, messages, vec![alice.public, alice.public, bob.public])
+    }
+
+    #[test]
+    fn public_keys_aggregate_to_the_sum_of_secret_keys() {
+        let alice: ExpandedSecretKey = keypair_for_testing("alice").secret.expand::<Sha512>();
+        let bob: ExpandedSecretKey = keypair_for_testing("bob").secret.expand::<Sha512>();
+        let carol: ExpandedSecretKey = keypair_for_testing("carol").secret.expand::<Sha512>();
+        let public = |key: &Scalar| PublicKey((key * &constants::ED25519_BASEPOINT_TABLE).compress());
+        let both: PublicKey = public(&alice.key).aggregate_with(&public(&bob.key)).unwrap();
+        let all: PublicKey = PublicKey::aggregate_many(
+            &[public(&alice.key), public(&bob.key), public(&carol.key)]).unwrap();
+
+        assert_eq!(both, public(&(&alice.key + &bob.key)));
+        assert_eq!(all, both.aggregate_with(&public(&carol.key)).unwrap());
+        assert_eq!(all, public(&(&(&alice.key + &bob.key) + &carol.key)));
+    }
+
+    #[test]
+    fn aggregate_many_rejects_bad_input() {
+        let alice: PublicKey = keypair_for_testing("alice").public;
+        let not_a_point: PublicKey = (0u8..).map(|y| PublicKey(CompressedEdwardsY([y; 32])))
+            .find(|public| public.0.decompress().is_none()).unwrap();
+
+        assert_eq!(PublicKey::aggregate_many(&[]), None);
+        assert_eq!(PublicKey::aggregate_many(&[alice]), Some(alice));
+        assert_eq!(PublicKey::aggregate_many(&[alice, not_a_point]), None);
+        assert_eq!(alice.aggregate_with(&not_a_point), None);
+    }
+
+    #[test]
+    fn aggregate_signature_verifies() {
+        let (signatures, messages, keys) = signed_for_testing();
+        let aggregate: AggregateSignature =
+            aggregate_signatures::<Sha512>(&signatures, &messages, &keys).unwrap();
+
+        assert_eq!(aggregate.len(), 3);
+        assert_eq!(aggregate.to_bytes().len(), 128);
+        assert_eq!(verify_aggregate::<Sha512>(&aggregate, &messages, &keys), Ok(()));
+        assert_eq!(AggregateSignature::from_bytes(&aggregate.to_bytes()), Ok(aggregate));
+    }
+
+    #[test]
+    fn aggregate_signature_is_bound_to_messages_and_order() {
+        let (signatures, mut messages, mut keys) = signed_for_testing();
+        let aggregate: AggregateSignature =
+            aggregate_signatures::<Sha512>(&signatures, &messages, &keys).unwrap();
+
+        messages.swap(0, 1);
+        assert_eq!(verify_aggregate::<Sha512>(&aggregate, &messages, &keys),
+                   Err(SignatureError::InvalidSignature));
+        messages.swap(0, 1);
+        keys[2] = keys[0];
+        assert_eq!(verify_aggregate::<Sha512>(&aggregate, &messages, &keys),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(verify_aggregate::<Sha512>(&aggregate, &messages[..2], &keys[..2]),
+                   Err(SignatureError::InvalidInput));
+    }
+
+    #[test]
+    fn aggregate_with_invalid_signature_fails() {
+        let (mut signatures, messages, keys) = signed_for_testing();
+
+        signatures[1] = signatures[0];
+        let aggregate: AggregateSignature =
+            aggregate_signatures::<Sha512>(&signatures, &messages, &keys).unwrap();
+
+        assert_eq!(verify_aggregate::<Sha512>(&aggregate, &messages, &keys),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(aggregate_signatures::<Sha512>(&[], &[], &[]).unwrap_err(),
+                   SignatureError::InvalidInput);
+    }
+}
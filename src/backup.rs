@@ -0,0 +1,155 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Splitting of secret keys into three backup shares, any two of which
+//! suffice to recover the key.
+
+use rand::Rng;
+
+use ed25519::{Keypair, SecretKey};
+use ed25519::SECRET_KEY_LENGTH;
+use errors::SignatureError;
+
+/// Multiply `a` and `b` in GF(2⁸) modulo x⁸ + x⁴ + x³ + x + 1.
+pub(crate) fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product: u8 = 0;
+
+    for _ in 0..8 {
+        product ^= a & (0u8.wrapping_sub(b & 1));
+        a = (a << 1) ^ (0x1b & 0u8.wrapping_sub(a >> 7));
+        b >>= 1;
+    }
+    product
+}
+
+/// The inverses in GF(2⁸) of the differences of share coordinates, which are
+/// `1 ⊕ 2 = 3`, `1 ⊕ 3 = 2`, and `2 ⊕ 3 = 1`, indexed by the difference.
+const INVERSES: [u8; 4] = [0x00, 0x01, 0x8d, 0xf6];
+
+impl Keypair {
+    /// Split the secret key of this keypair into three shares, any two of
+    /// which recover it with `SecretKey::from_2of3_shares()`, using
+    /// randomness from the `csprng`.
+    ///
+    /// The position of each share in the returned array is its index, which
+    /// must be kept alongside it.
+    ///
+    /// # Construction
+    ///
+    /// This is Shamir's scheme fixed to a threshold of two and three shares,
+    /// applied to each byte of the secret key independently, over GF(2⁸)
+    /// (with the AES polynomial).  For each byte `k` of the key a random byte
+    /// `r` is chosen, and share `i` (for `i` in 0, 1, 2) holds `k + (i + 1)·r`,
+    /// i.e. the value at `x = i + 1` of the line through `k` with slope `r`.
+    /// Any two points determine the line, and so `k`, while any single share
+    /// is uniformly random, and reveals nothing.
+    ///
+    /// Simply XORing random pads into the key would not do: with `a` and `b`
+    /// random and `c = k ⊕ a ⊕ b`, all three of `a`, `b`, and `c` are needed
+    /// to recover `k`, and no two of them suffice.
+    ///
+    /// # Security
+    ///
+    /// Only the 2-of-3 case is supported; this is not a general threshold
+    /// scheme.  Shares carry no integrity protection, so a corrupted share
+    /// yields the wrong key; check the recovered key against the known public
+    /// key.
+    pub fn split_secret_2of3(&self, csprng: &mut dyn Rng) -> [[u8; SECRET_KEY_LENGTH]; 3] {
+        let mut slope: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+        let mut shares: [[u8; SECRET_KEY_LENGTH]; 3] = [[0u8; SECRET_KEY_LENGTH]; 3];
+
+        csprng.fill_bytes(&mut slope);
+
+        for (i, share) in shares.iter_mut().enumerate() {
+            for j in 0..SECRET_KEY_LENGTH {
+                share[j] = self.secret.0[j] ^ gf_mul(i as u8 + 1, slope[j]);
+            }
+        }
+        shares
+    }
+}
+
+impl SecretKey {
+    /// Recover a secret key from two of the three shares produced by
+    /// `Keypair::split_secret_2of3()`, each given with its index.
+    ///
+    /// A corrupted share is not detected, and yields the wrong key, so the
+    /// public key derived from the result should be checked against the known
+    /// one.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `SecretKey`, or whose error value is
+    /// `SignatureError::InvalidInput` if either index is not 0, 1, or 2, or
+    /// if the two indices are the same.
+    pub fn from_2of3_shares(first: (usize, &[u8; SECRET_KEY_LENGTH]),
+                            second: (usize, &[u8; SECRET_KEY_LENGTH]))
+            -> Result<SecretKey, SignatureError> {
+
+        let (i, first_share) = first;
+        let (j, second_share) = second;
+
+        if i > 2 || j > 2 || i == j {
+            return Err(SignatureError::InvalidInput);
+        }
+        let x: u8 = i as u8 + 1;
+        let inverse: u8 = INVERSES[(x ^ (j as u8 + 1)) as usize];
+        let mut secret: SecretKey = SecretKey([0u8; SECRET_KEY_LENGTH]);
+
+        for k in 0..SECRET_KEY_LENGTH {
+            let slope: u8 = gf_mul(first_share[k] ^ second_share[k], inverse);
+
+            secret.0[k] = first_share[k] ^ gf_mul(x, slope);
+        }
+        Ok(secret)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn gf_inverses_are_inverses() {
+        for (d, inverse) in INVERSES.iter().enumerate().skip(1) {
+            assert_eq!(gf_mul(d as u8, *inverse), 1);
+        }
+    }
+
+    #[test]
+    fn any_two_shares_recover_the_secret() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let keypair: Keypair = keypair_for_testing("backup");
+        let shares: [[u8; 32]; 3] = keypair.split_secret_2of3(&mut csprng);
+
+        for i in 0..3 {
+            assert!(shares[i] != keypair.secret.to_bytes());
+            for j in 0..3 {
+                if i == j {
+                    continue;
+                }
+                let secret: SecretKey = SecretKey::from_2of3_shares((i, &shares[i]),
+                                                                    (j, &shares[j])).unwrap();
+                assert_eq!(secret.to_bytes(), keypair.secret.to_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn bad_share_indices_are_rejected() {
+        let share: [u8; 32] = [0u8; 32];
+
+        assert_eq!(SecretKey::from_2of3_shares((1, &share), (1, &share)).unwrap_err(),
+                   SignatureError::InvalidInput);
+        assert_eq!(SecretKey::from_2of3_shares((0, &share), (3, &share)).unwrap_err(),
+                   SignatureError::InvalidInput);
+    }
+}
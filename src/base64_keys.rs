@@ -0,0 +1,119 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding of secret keys as base64, as commonly found in configuration
+//! files.
+
+use std::string::String;
+use std::vec::Vec;
+
+use base64;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use ed25519::SECRET_KEY_LENGTH;
+use errors::SignatureError;
+
+impl Keypair {
+    /// Encode the secret key of this `Keypair` as standard, padded base64
+    /// (RFC 4648 §4).
+    ///
+    /// # Warning
+    ///
+    /// The output is the secret key, in the clear.  Anyone who can read it
+    /// can sign as this keypair, so it must be kept at least as private as
+    /// the configuration file it is stored in.
+    ///
+    /// # Returns
+    ///
+    /// A 44-character `String`.
+    pub fn secret_key_to_base64(&self) -> String {
+        base64::encode_config(self.secret.as_bytes(), base64::STANDARD)
+    }
+
+    /// Construct a `Keypair` from a secret key encoded as standard, padded
+    /// base64, such as one produced by `secret_key_to_base64()`.
+    ///
+    /// The public half of the returned `Keypair` is derived from the secret
+    /// key using SHA-512.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    /// `SignatureError::InvalidEncoding` if the string is not the canonical,
+    /// padded base64 encoding of 32 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "base64-keys")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// let encoded: &str = "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=";
+    /// let keypair: Keypair = Keypair::from_base64_secret_key(encoded).unwrap();
+    ///
+    /// assert_eq!(keypair.secret_key_to_base64(), encoded);
+    /// # }
+    /// # #[cfg(not(feature = "base64-keys"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_base64_secret_key(encoded: &str) -> Result<Keypair, SignatureError> {
+        let bytes: Vec<u8> = base64::decode_config(encoded, base64::STANDARD)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if bytes.len() != SECRET_KEY_LENGTH {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let secret: SecretKey = SecretKey::from_bytes(&bytes)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+        let keypair: Keypair = Keypair{ secret, public };
+
+        // Reject unpadded encodings, and those whose unused trailing bits are
+        // set, so that each key has exactly one encoding.
+        if keypair.secret_key_to_base64() != encoded {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        Ok(keypair)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use super::*;
+
+    // The secret key of TEST 1 from RFC8032 §7.1.
+    static ENCODED: &str = "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=";
+
+    #[test]
+    fn base64_secret_key_roundtrip() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let keypair: Keypair = Keypair::from_base64_secret_key(ENCODED).unwrap();
+
+        assert_eq!(&keypair.public.to_bytes()[..], &public_bytes[..]);
+        assert_eq!(keypair.secret_key_to_base64(), ENCODED);
+    }
+
+    #[test]
+    fn base64_secret_key_noncanonical_is_rejected() {
+        // Unpadded, a trailing bit set ('A' to 'B'), URL-safe, and too short.
+        for encoded in ["nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+                        "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2B=",
+                        "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A=",
+                        "nWGxne/9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyu"].iter() {
+            assert_eq!(Keypair::from_base64_secret_key(encoded).unwrap_err(),
+                       SignatureError::InvalidEncoding);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Batch verification of signatures.
+//!
+//! Rather than checking `s_i·B = R_i + k_i·A_i` for each signature in turn,
+//! a batch is checked with a single multiscalar multiplication, of
+//!
+//! ```text
+//! -(Σ z_i·s_i)·B + Σ z_i·R_i + Σ (z_i·k_i)·A_i = 0
+//! ```
+//!
+//! where the `z_i` are random 128-bit weights, chosen afresh for each batch.
+//! Without the weights, a forger could submit two invalid signatures whose
+//! errors cancel out.  With them, a batch containing an invalid signature
+//! passes with probability at most 2⁻¹²⁸.
+//!
+//! Like `PublicKey::verify()`, this uses the cofactorless equation, which
+//! would give a probabilistic result for signatures whose `R` or public key
+//! has a component of small order.  So each signature is first put through
+//! the checks of `PublicKey::verify_strict()`, and a batch passes exactly when
+//! every signature in it passes `verify_strict()`, except with probability at
+//! most 2⁻¹²⁸.
+
+use std::vec::Vec;
+
+use rand::OsRng;
+use rand::Rng;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::Identity;
+use curve25519_dalek::edwards::vartime;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{PublicKey, Signature};
+use errors::SignatureError;
+
+/// Verify a batch of `signatures`, where each is on the message and under the
+/// public key at the same position in `messages` and `public_keys`, using
+/// the hash function `D`.
+///
+/// # Returns
+///
+/// * `Ok(())` if every signature is valid (an empty batch is),
+/// * `Err(SignatureError::InvalidInput)` if the three slices are not all of
+///   the same length,
+/// * `Err(SignatureError::EntropyError)` if the operating system's random
+///   number generator could not be used, or
+/// * `Err(SignatureError::InvalidSignature)` if any signature is invalid, or
+///   would be rejected by `PublicKey::verify_strict()`.  Which one is not
+///   reported.
+pub fn try_verify_batch<D>(messages: &[&[u8]], signatures: &[Signature],
+                           public_keys: &[PublicKey]) -> Result<(), SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    if messages.len() != signatures.len() || public_keys.len() != signatures.len() {
+        return Err(SignatureError::InvalidInput);
+    }
+    let mut csprng: OsRng = OsRng::new().or(Err(SignatureError::EntropyError))?;
+    let n: usize = signatures.len();
+    let mut scalars: Vec<Scalar> = Vec::with_capacity(2 * n + 1);
+    let mut points: Vec<ExtendedPoint> = Vec::with_capacity(2 * n + 1);
+    let mut s_sum: Scalar = Scalar::zero();
+
+    for i in 0..n {
+        let signature: &Signature = &signatures[i];
+
+        let (r, a): (ExtendedPoint, ExtendedPoint) = public_keys[i].strict_points(signature)
+            .or(Err(SignatureError::InvalidSignature))?;
+        let mut weight: Scalar = Scalar::zero();
+        let mut h: D = D::default();
+
+        csprng.fill_bytes(&mut weight.0[..16]);
+
+        h.input(signature.r.as_bytes());
+        h.input(public_keys[i].as_bytes());
+        h.input(messages[i]);
+        let digest = h.fixed_result();
+        let k: Scalar = Scalar::reduce(array_ref!(digest, 0, 64));
+
+        s_sum = Scalar::multiply_add(&weight, &signature.s, &s_sum);
+        scalars.push(weight);
+        points.push(r);
+        scalars.push(&weight * &k);
+        points.push(a);
+    }
+    scalars.push(-&s_sum);
+    points.push(constants::ED25519_BASEPOINT_TABLE.basepoint());
+
+    let sum: ExtendedPoint = vartime::multiscalar_mult(&scalars, &points);
+
+    if sum.compress() == ExtendedPoint::identity().compress() {
+        Ok(())
+    } else {
+        Err(SignatureError::InvalidSignature)
+    }
+}
+
+/// Verify a batch of `signatures`, as for `try_verify_batch()`.
+///
+/// # Returns
+///
+/// `true` if the slices are of the same length and every signature is valid,
+/// and `false` otherwise.
+pub fn verify_batch<D>(messages: &[&[u8]], signatures: &[Signature],
+                       public_keys: &[PublicKey]) -> bool
+        where D: Digest<OutputSize = U64> + Default {
+    try_verify_batch::<D>(messages, signatures, public_keys).is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    fn batch_for_testing() -> (Vec<&'static [u8]>, Vec<Signature>, Vec<PublicKey>) {
+        let messages: Vec<&'static [u8]> = vec![b"one", b"two", b"three", b"four"];
+        let keypairs: Vec<Keypair> = ["a", "b", "c", "d"].iter()
+            .map(|label| keypair_for_testing(label)).collect();
+        let signatures: Vec<Signature> = keypairs.iter().zip(messages.iter())
+            .map(|(keypair, message)| keypair.sign::<Sha512>(message)).collect();
+
+        (messages, signatures, keypairs.iter().map(|keypair| keypair.public).collect())
+    }
+
+    #[test]
+    fn valid_batch_verifies() {
+        let (messages, signatures, keys) = batch_for_testing();
+
+        assert!(verify_batch::<Sha512>(&messages, &signatures, &keys));
+        assert!(verify_batch::<Sha512>(&messages[..1], &signatures[..1], &keys[..1]));
+        assert!(verify_batch::<Sha512>(&[], &[], &[]));
+    }
+
+    #[test]
+    fn invalid_batch_fails() {
+        let (messages, mut signatures, keys) = batch_for_testing();
+
+        signatures.swap(1, 2);
+        assert_eq!(try_verify_batch::<Sha512>(&messages, &signatures, &keys),
+                   Err(SignatureError::InvalidSignature));
+        assert!(!verify_batch::<Sha512>(&messages[1..2], &signatures[1..2], &keys[1..2]));
+    }
+
+    #[test]
+    fn mismatched_lengths_are_rejected() {
+        let (messages, signatures, keys) = batch_for_testing();
+
+        assert_eq!(try_verify_batch::<Sha512>(&messages[..3], &signatures, &keys),
+                   Err(SignatureError::InvalidInput));
+        assert!(!verify_batch::<Sha512>(&messages, &signatures, &keys[..3]));
+    }
+}
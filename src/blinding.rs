@@ -0,0 +1,171 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Additive blinding of keypairs.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{ExpandedSecretKey, PublicKey, Signature};
+use errors::SignatureError;
+
+/// The domain separation string for the nonces of blinded secret keys.
+const BLINDING_NONCE_DOMAIN: &[u8] = b"ed25519-dalek blinded nonce";
+
+/// Interpret a blinding factor as a scalar, reducing it modulo ℓ.
+fn blinding_scalar(blinding: &[u8; 32]) -> Scalar {
+    let mut wide: [u8; 64] = [0u8; 64];
+
+    wide[..32].copy_from_slice(blinding);
+    Scalar::reduce(&wide)
+}
+
+/// A public key which has been blinded with `KeyBlindingScheme`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BlindedPublicKey(PublicKey);
+
+impl BlindedPublicKey {
+    /// The blinded public key, for verifying signatures made with the
+    /// blinded secret key.
+    pub fn public_key(&self) -> PublicKey {
+        self.0
+    }
+}
+
+/// Additive blinding of public and secret keys, for protocols in which a
+/// holder of a keypair must authenticate under a public key which cannot be
+/// linked to its usual one.
+///
+/// The blinded public key is `A' = A + b·B`, where `b` is the blinding
+/// scalar, and the blinded secret scalar is `a' = a + b`.  Without the
+/// blinding factor, `A'` cannot be linked to `A`.
+///
+/// # Warning
+///
+/// A signature made with the original keypair cannot be turned into one
+/// which verifies under the blinded public key: the public key is hashed into
+/// the challenge of every ed25519 signature, so the challenge for `A'` is
+/// unrelated to the one for `A`, and no adjustment of `s` alone can account
+/// for it.  Instead, sign with the secret key returned by
+/// `KeyBlindingScheme::blind_secret_key()`.
+///
+/// Anyone who knows the blinding factor and the original public key can
+/// link the two, and anyone who knows the blinding factor and the blinded
+/// secret key can recover the original secret scalar.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct KeyBlindingScheme;
+
+impl KeyBlindingScheme {
+    /// Blind a `public_key`, by adding the point corresponding to the
+    /// `blinding` factor (interpreted as a little-endian scalar, modulo ℓ).
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `BlindedPublicKey`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the `public_key` is not
+    /// a valid point.
+    pub fn blind_public_key(public_key: &PublicKey, blinding: &[u8; 32])
+            -> Result<BlindedPublicKey, SignatureError> {
+
+        let a = public_key.0.decompress().ok_or(SignatureError::InvalidEncoding)?;
+        let blinded = &a + &(&blinding_scalar(blinding) * &constants::ED25519_BASEPOINT_TABLE);
+
+        Ok(BlindedPublicKey(PublicKey(blinded.compress())))
+    }
+
+    /// Blind an `expanded_secret_key` with the same `blinding` factor, giving
+    /// the secret key for the public key returned by `blind_public_key()`.
+    ///
+    /// The nonce of the blinded key is derived from the original nonce and
+    /// the blinding factor, using the hash function `D`.  Reusing the
+    /// original nonce would reveal the secret key as soon as the same message
+    /// was signed with both keys.
+    pub fn blind_secret_key<D>(expanded_secret_key: &ExpandedSecretKey, blinding: &[u8; 32])
+            -> ExpandedSecretKey
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut h: D = D::default();
+        let mut nonce: [u8; 32] = [0u8; 32];
+
+        h.input(BLINDING_NONCE_DOMAIN);
+        h.input(&expanded_secret_key.nonce);
+        h.input(blinding);
+        nonce.copy_from_slice(&h.fixed_result()[..32]);
+
+        ExpandedSecretKey{
+            key: &expanded_secret_key.key + &blinding_scalar(blinding),
+            nonce,
+        }
+    }
+
+    /// Sign a `message` with the blinded form of `expanded_secret_key`, for
+    /// verification under the `blinded_public_key`.
+    pub fn sign_blinded<D>(expanded_secret_key: &ExpandedSecretKey, blinding: &[u8; 32],
+                           blinded_public_key: &BlindedPublicKey, message: &[u8]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+        KeyBlindingScheme::blind_secret_key::<D>(expanded_secret_key, blinding)
+            .sign::<D>(message, &blinded_public_key.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn blinded_signature_verifies_under_blinded_key() {
+        let keypair: Keypair = keypair_for_testing("blinding");
+        let expanded: ExpandedSecretKey = keypair.secret.expand::<Sha512>();
+        let blinding: [u8; 32] = keypair_for_testing("factor").public.to_bytes();
+        let blinded: BlindedPublicKey =
+            KeyBlindingScheme::blind_public_key(&keypair.public, &blinding).unwrap();
+        let signature: Signature = KeyBlindingScheme::sign_blinded::<Sha512>(
+            &expanded, &blinding, &blinded, b"test message");
+
+        assert!(blinded.public_key() != keypair.public);
+        assert!(blinded.public_key().verify::<Sha512>(b"test message", &signature).is_ok());
+        assert!(keypair.public.verify::<Sha512>(b"test message", &signature).is_err());
+
+        // A signature by the original keypair does not verify under the
+        // blinded public key.
+        let original: Signature = keypair.sign::<Sha512>(b"test message");
+
+        assert!(blinded.public_key().verify::<Sha512>(b"test message", &original).is_err());
+    }
+
+    #[test]
+    fn blinded_secret_key_matches_blinded_public_key() {
+        let keypair: Keypair = keypair_for_testing("blinding");
+        let blinding: [u8; 32] = [7u8; 32];
+        let blinded: ExpandedSecretKey =
+            KeyBlindingScheme::blind_secret_key::<Sha512>(&keypair.secret.expand::<Sha512>(),
+                                                          &blinding);
+        let public: BlindedPublicKey =
+            KeyBlindingScheme::blind_public_key(&keypair.public, &blinding).unwrap();
+
+        assert_eq!((&blinded.key * &constants::ED25519_BASEPOINT_TABLE).compress(),
+                   public.public_key().0);
+        assert!(blinded.nonce[..] != keypair.secret.expand::<Sha512>().nonce[..]);
+    }
+
+    #[test]
+    fn zero_blinding_is_the_identity() {
+        let keypair: Keypair = keypair_for_testing("blinding");
+        let blinded: BlindedPublicKey =
+            KeyBlindingScheme::blind_public_key(&keypair.public, &[0u8; 32]).unwrap();
+
+        assert_eq!(blinded.public_key(), keypair.public);
+    }
+}
@@ -0,0 +1,285 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Minimal certificates, binding a name to a public key for a period of time,
+//! for use between services which would otherwise need X.509.
+//!
+//! # Format
+//!
+//! A `Certificate` is encoded as
+//!
+//! ```text
+//! name length (4 bytes, big-endian) || name (UTF-8) || public key (32 bytes)
+//!     || not before (8 bytes, big-endian) || not after (8 bytes, big-endian)
+//!     || signature (64 bytes)
+//! ```
+//!
+//! where the times are in seconds since the UNIX epoch.  The issuer's
+//! signature is over the string `"ed25519-dalek certificate v1"` followed by
+//! all of the preceding fields.
+
+use std::string::String;
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde")]
+use serde::{Serializer, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de::Error as SerdeError;
+#[cfg(feature = "serde")]
+use serde::de::Visitor;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use errors::SignatureError;
+
+/// The domain separation string prepended to the signed fields.
+const CERTIFICATE_DOMAIN: &[u8] = b"ed25519-dalek certificate v1";
+
+/// The current time, in seconds since the UNIX epoch.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// A certificate, made by an issuer's `Keypair`, binding a subject's name to
+/// its `PublicKey` for a period of validity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Certificate {
+    /// The name of the subject.
+    pub subject_name: String,
+    /// The public key of the subject.
+    pub subject_public_key: PublicKey,
+    /// The time at which the certificate becomes valid, in seconds since the
+    /// UNIX epoch.
+    pub not_before: u64,
+    /// The time after which the certificate is no longer valid, in seconds
+    /// since the UNIX epoch.
+    pub not_after: u64,
+    /// The issuer's signature over the other fields.
+    pub signature: Signature,
+}
+
+impl Certificate {
+    /// Encode the fields of this certificate, without its signature.
+    fn fields(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(52 + self.subject_name.len());
+
+        bytes.extend_from_slice(&(self.subject_name.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(self.subject_name.as_bytes());
+        bytes.extend_from_slice(self.subject_public_key.as_bytes());
+        bytes.extend_from_slice(&self.not_before.to_be_bytes());
+        bytes.extend_from_slice(&self.not_after.to_be_bytes());
+        bytes
+    }
+
+    /// The message signed by the issuer.
+    fn signed_message(&self) -> Vec<u8> {
+        let mut message: Vec<u8> = CERTIFICATE_DOMAIN.to_vec();
+
+        message.extend_from_slice(&self.fields());
+        message
+    }
+
+    /// Convert this certificate to bytes, in the format described in the
+    /// documentation for this module.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.fields();
+
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+
+    /// Construct a certificate from the bytes produced by `to_bytes()`.
+    ///
+    /// The signature is not checked; use `verify()` for that.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Certificate`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Certificate, SignatureError> {
+        if bytes.len() < 4 {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let name_length: usize = u32::from_be_bytes(*array_ref!(bytes, 0, 4)) as usize;
+
+        if name_length.checked_add(PUBLIC_KEY_LENGTH + 16 + SIGNATURE_LENGTH) != Some(bytes.len() - 4) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let (name, rest) = bytes[4..].split_at(name_length);
+        let subject_name: String = String::from_utf8(name.to_vec())
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let subject_public_key: PublicKey = PublicKey::from_bytes(&rest[..PUBLIC_KEY_LENGTH])
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let signature: Signature = Signature::from_bytes(&rest[PUBLIC_KEY_LENGTH + 16..])
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        Ok(Certificate{
+            subject_name,
+            subject_public_key,
+            not_before: u64::from_be_bytes(*array_ref!(rest, PUBLIC_KEY_LENGTH, 8)),
+            not_after: u64::from_be_bytes(*array_ref!(rest, PUBLIC_KEY_LENGTH + 8, 8)),
+            signature,
+        })
+    }
+
+    /// Check that this certificate was issued by the holder of
+    /// `ca_public_key`, and that it is valid now.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the certificate is valid,
+    /// * an error as for `PublicKey::verify()` if its signature is invalid,
+    /// * `Err(SignatureError::OutsideValidityPeriod)` if its signature is valid,
+    ///   but the current time is before `not_before` or after `not_after`.
+    pub fn verify<D>(&self, ca_public_key: &PublicKey) -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+        self.verify_at::<D>(ca_public_key, now())
+    }
+
+    /// Check that this certificate was issued by the holder of
+    /// `ca_public_key`, and that it is valid at the given `time`, in seconds
+    /// since the UNIX epoch.
+    ///
+    /// # Returns
+    ///
+    /// As for `verify()`.
+    pub fn verify_at<D>(&self, ca_public_key: &PublicKey, time: u64)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        ca_public_key.verify::<D>(&self.signed_message(), &self.signature)?;
+
+        if time < self.not_before || time > self.not_after {
+            return Err(SignatureError::OutsideValidityPeriod);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Certificate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_bytes(&self.to_bytes())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'d> Deserialize<'d> for Certificate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'d> {
+        struct CertificateVisitor;
+
+        impl<'d> Visitor<'d> for CertificateVisitor {
+            type Value = Certificate;
+
+            fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                formatter.write_str("An ed25519-dalek certificate.")
+            }
+
+            fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Certificate, E> where E: SerdeError {
+                Certificate::from_bytes(bytes).or(Err(SerdeError::invalid_length(bytes.len(), &self)))
+            }
+        }
+        deserializer.deserialize_bytes(CertificateVisitor)
+    }
+}
+
+impl Keypair {
+    /// Issue a `Certificate`, signed with this keypair, binding the
+    /// `subject_name` to the `subject_public_key` from now until
+    /// `duration_secs` seconds from now.
+    pub fn certify<D>(&self, subject_name: &str, subject_public_key: &PublicKey,
+                      duration_secs: u64) -> Certificate
+            where D: Digest<OutputSize = U64> + Default {
+
+        let not_before: u64 = now();
+        let mut certificate: Certificate = Certificate{
+            subject_name: String::from(subject_name),
+            subject_public_key: *subject_public_key,
+            not_before,
+            not_after: not_before.saturating_add(duration_secs),
+            signature: Signature::from_bytes(&[0u8; SIGNATURE_LENGTH]).unwrap(),
+        };
+
+        certificate.signature = self.sign::<D>(&certificate.signed_message());
+        certificate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn certify_and_verify() {
+        let ca: Keypair = keypair_for_testing("ca");
+        let subject: PublicKey = keypair_for_testing("service").public;
+        let certificate: Certificate = ca.certify::<Sha512>("service.internal", &subject, 3600);
+
+        assert_eq!(certificate.verify::<Sha512>(&ca.public), Ok(()));
+        assert_eq!(certificate.verify::<Sha512>(&subject), Err(SignatureError::InvalidSignature));
+        assert_eq!(certificate.verify_at::<Sha512>(&ca.public, certificate.not_after + 1),
+                   Err(SignatureError::OutsideValidityPeriod));
+        assert_eq!(certificate.verify_at::<Sha512>(&ca.public, certificate.not_before - 1),
+                   Err(SignatureError::OutsideValidityPeriod));
+    }
+
+    #[test]
+    fn certificate_is_tamper_evident() {
+        let ca: Keypair = keypair_for_testing("ca");
+        let subject: PublicKey = keypair_for_testing("service").public;
+        let mut certificate: Certificate = ca.certify::<Sha512>("service.internal", &subject, 3600);
+
+        certificate.not_after += 1;
+        assert_eq!(certificate.verify::<Sha512>(&ca.public), Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn certificate_bytes_roundtrip() {
+        let ca: Keypair = keypair_for_testing("ca");
+        let subject: PublicKey = keypair_for_testing("service").public;
+        let certificate: Certificate = ca.certify::<Sha512>("service.internal", &subject, 3600);
+        let bytes: Vec<u8> = certificate.to_bytes();
+
+        assert_eq!(Certificate::from_bytes(&bytes), Ok(certificate));
+        assert_eq!(Certificate::from_bytes(&bytes[..bytes.len() - 1]),
+                   Err(SignatureError::InvalidEncoding));
+    }
+
+    #[test]
+    fn huge_name_length_is_rejected() {
+        let mut bytes: Vec<u8> = vec![0xff; 4];
+
+        // On a 32-bit target, 0xffffffff + 128 would wrap around to 127.
+        bytes.extend_from_slice(&[0u8; PUBLIC_KEY_LENGTH + 16 + SIGNATURE_LENGTH - 1]);
+        assert_eq!(Certificate::from_bytes(&bytes), Err(SignatureError::InvalidEncoding));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_deserialize_certificate() {
+        use bincode::{serialize, deserialize, Infinite};
+
+        let ca: Keypair = keypair_for_testing("ca");
+        let subject: PublicKey = keypair_for_testing("service").public;
+        let certificate: Certificate = ca.certify::<Sha512>("service.internal", &subject, 3600);
+        let encoded: Vec<u8> = serialize(&certificate, Infinite).unwrap();
+        let decoded: Certificate = deserialize(&encoded).unwrap();
+
+        assert_eq!(decoded, certificate);
+    }
+}
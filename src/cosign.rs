@@ -0,0 +1,194 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Import of keypairs stored by Sigstore's `cosign` tool.
+
+use std::vec::Vec;
+
+use base64;
+
+use crypto_secretbox::XSalsa20Poly1305;
+use crypto_secretbox::aead::{Aead, KeyInit};
+
+use scrypt::{scrypt, Params};
+
+use serde_json;
+use serde_json::Value;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use errors::SignatureError;
+use pem::pem_block;
+use pkcs8::secret_key_from_pkcs8;
+
+/// The PEM labels of encrypted keys written by `cosign`.
+const ENCRYPTED_LABELS: [&str; 2] = ["ENCRYPTED SIGSTORE PRIVATE KEY",
+                                     "ENCRYPTED COSIGN PRIVATE KEY"];
+
+/// The PEM label of an unencrypted PKCS#8 private key.
+const PKCS8_LABEL: &str = "PRIVATE KEY";
+
+/// Decode the base64 string at `value`.
+fn base64_field(value: &Value) -> Result<Vec<u8>, SignatureError> {
+    let encoded: &str = value.as_str().ok_or(SignatureError::InvalidEncoding)?;
+
+    base64::decode_config(encoded, base64::STANDARD).or(Err(SignatureError::InvalidEncoding))
+}
+
+/// Read the integer at `value` as a `u32`.
+fn u32_field(value: &Value) -> Result<u32, SignatureError> {
+    value.as_u64().filter(|&n| n <= u32::MAX as u64).map(|n| n as u32)
+        .ok_or(SignatureError::InvalidEncoding)
+}
+
+/// Decrypt the PKCS#8 private key within the JSON `contents` of an encrypted
+/// `cosign` key.
+fn decrypt(contents: &[u8], password: &str) -> Result<Vec<u8>, SignatureError> {
+    let object: Value = serde_json::from_slice(contents).or(Err(SignatureError::InvalidEncoding))?;
+    let kdf: &Value = &object["kdf"];
+    let cipher: &Value = &object["cipher"];
+
+    if kdf["name"] != "scrypt" || cipher["name"] != "nacl/secretbox" {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let n: u32 = u32_field(&kdf["params"]["N"])?;
+
+    if n < 2 || !n.is_power_of_two() {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let params: Params = Params::new(n.trailing_zeros() as u8,
+                                     u32_field(&kdf["params"]["r"])?,
+                                     u32_field(&kdf["params"]["p"])?, 32)
+        .or(Err(SignatureError::InvalidEncoding))?;
+    let salt: Vec<u8> = base64_field(&kdf["salt"])?;
+    let nonce: Vec<u8> = base64_field(&cipher["nonce"])?;
+    let ciphertext: Vec<u8> = base64_field(&object["ciphertext"])?;
+
+    if nonce.len() != 24 {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let mut key: [u8; 32] = [0u8; 32];
+
+    scrypt(password.as_bytes(), &salt, &params, &mut key).or(Err(SignatureError::InvalidEncoding))?;
+
+    XSalsa20Poly1305::new(&key.into())
+        .decrypt(nonce[..].into(), &ciphertext[..])
+        .or(Err(SignatureError::DecryptionFailed))
+}
+
+impl Keypair {
+    /// Import a `Keypair` from a secret key written by `cosign`.
+    ///
+    /// `cosign generate-key-pair` and `cosign import-key-pair` write secret
+    /// keys as a PEM block labelled `ENCRYPTED SIGSTORE PRIVATE KEY` (or, in
+    /// older versions, `ENCRYPTED COSIGN PRIVATE KEY`), whose contents are the
+    /// JSON object
+    ///
+    /// ```text
+    /// {"kdf":{"name":"scrypt","params":{"N":…,"r":…,"p":…},"salt":"…"},
+    ///  "cipher":{"name":"nacl/secretbox","nonce":"…"},
+    ///  "ciphertext":"…"}
+    /// ```
+    ///
+    /// with the byte strings in standard base64.  The ciphertext is an
+    /// RFC8410 PKCS#8 private key, sealed with NaCl's `secretbox`
+    /// (XSalsa20-Poly1305) under a key derived from the password with scrypt.
+    ///
+    /// Unencrypted PKCS#8 keys, in a PEM block labelled `PRIVATE KEY`, are
+    /// also accepted.
+    ///
+    /// The `password` is used only for encrypted keys; `cosign` permits an
+    /// empty password, which `None` stands for.  The public key is derived
+    /// using SHA-512.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    ///
+    /// * `SignatureError::DecryptionFailed` if the `password` is wrong,
+    /// * `SignatureError::InvalidKeyLength` if the key is not an Ed25519 key
+    ///   (`cosign` generates ECDSA P-256 keys by default), or
+    /// * `SignatureError::InvalidEncoding` if the PEM is malformed, or uses a
+    ///   key derivation function or cipher other than those above.
+    pub fn from_cosign_pem(pem: &str, password: Option<&str>) -> Result<Keypair, SignatureError> {
+        let (label, contents) = pem_block(pem).ok_or(SignatureError::InvalidEncoding)?;
+
+        let secret: SecretKey = if label == PKCS8_LABEL {
+            secret_key_from_pkcs8(&contents)?
+        } else if ENCRYPTED_LABELS.contains(&&label[..]) {
+            secret_key_from_pkcs8(&decrypt(&contents, password.unwrap_or(""))?)?
+        } else {
+            return Err(SignatureError::InvalidEncoding);
+        };
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Ok(Keypair{ secret, public })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Read;
+    use std::string::String;
+    use hex::FromHex;
+    use super::*;
+
+    fn read_key(filename: &str) -> String {
+        let mut pem: String = String::new();
+
+        File::open(filename).unwrap().read_to_string(&mut pem).unwrap();
+        pem
+    }
+
+    fn expected_public() -> Vec<u8> {
+        FromHex::from_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap()
+    }
+
+    // The key holds the secret key of the first test from RFC8032 §7.1,
+    // encrypted with the password "ed25519-dalek" exactly as cosign does it,
+    // but with scrypt's N lowered from cosign's 32768 to 1024, to keep the
+    // test fast.
+    #[test]
+    fn cosign_encrypted_key() {
+        let pem: String = read_key("res/ed25519-cosign.key");
+        let keypair: Keypair = Keypair::from_cosign_pem(&pem, Some("ed25519-dalek")).unwrap();
+
+        assert_eq!(&keypair.public.to_bytes()[..], &expected_public()[..]);
+    }
+
+    #[test]
+    fn cosign_wrong_password_is_rejected() {
+        let pem: String = read_key("res/ed25519-cosign.key");
+
+        assert_eq!(Keypair::from_cosign_pem(&pem, Some("hunter2")).unwrap_err(),
+                   SignatureError::DecryptionFailed);
+        assert_eq!(Keypair::from_cosign_pem(&pem, None).unwrap_err(),
+                   SignatureError::DecryptionFailed);
+    }
+
+    #[test]
+    fn cosign_unencrypted_key() {
+        let pem: String = read_key("res/ed25519-pkcs8.pem");
+        let keypair: Keypair = Keypair::from_cosign_pem(&pem, None).unwrap();
+
+        assert_eq!(&keypair.public.to_bytes()[..], &expected_public()[..]);
+    }
+
+    #[test]
+    fn cosign_other_pem_is_rejected() {
+        let pem: String = read_key("res/ed25519-pkcs8.pem").replace("PRIVATE KEY", "PUBLIC KEY");
+
+        assert_eq!(Keypair::from_cosign_pem(&pem, None).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_cosign_pem("", None).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+}
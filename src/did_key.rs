@@ -0,0 +1,109 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding of public keys as W3C `did:key` Decentralized Identifiers.
+//!
+//! A `did:key` identifier for an ed25519 key is `"did:key:z"` followed by the
+//! base58btc encoding of the key's multicodec encoding, from
+//! `PublicKey::to_multicodec_bytes()`.  These identifiers always begin with
+//! `"did:key:z6Mk"`.
+
+use std::string::String;
+use std::vec::Vec;
+
+use bs58;
+
+use ed25519::PublicKey;
+use errors::SignatureError;
+
+/// The prefix of every `did:key` identifier encoded in base58btc.
+const DID_KEY_PREFIX: &str = "did:key:z";
+
+impl PublicKey {
+    /// Encode this `PublicKey` as a `did:key` identifier.
+    pub fn to_did_key(&self) -> String {
+        let mut did: String = String::from(DID_KEY_PREFIX);
+
+        did.push_str(&bs58::encode(self.to_multicodec_bytes()).into_string());
+        did
+    }
+
+    /// Construct a `PublicKey` from a `did:key` identifier.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `PublicKey`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the identifier is for a key of
+    ///   some other type, or
+    /// * `SignatureError::InvalidEncoding` if the identifier does not begin
+    ///   with `"did:key:z"`, or the rest of it is not valid base58btc.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "did-key")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let did: &str = "did:key:z6MktwupdmLXVVqTzCw4i46r4uGyosGXRnR3XjN4Zq7oMMsw";
+    /// let public: PublicKey = PublicKey::from_did_key(did).unwrap();
+    ///
+    /// assert_eq!(public.to_did_key(), did);
+    /// # }
+    /// # #[cfg(not(feature = "did-key"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_did_key(did: &str) -> Result<PublicKey, SignatureError> {
+        if !did.starts_with(DID_KEY_PREFIX) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let bytes: Vec<u8> = bs58::decode(&did[DID_KEY_PREFIX.len()..]).into_vec()
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        PublicKey::from_multicodec_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use super::*;
+
+    // The public key of the first test from RFC8032 §7.1.
+    static DID: &str = "did:key:z6MktwupdmLXVVqTzCw4i46r4uGyosGXRnR3XjN4Zq7oMMsw";
+
+    #[test]
+    fn did_key_roundtrip() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let public: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+
+        assert_eq!(public.to_did_key(), DID);
+        assert_eq!(PublicKey::from_did_key(DID), Ok(public));
+    }
+
+    #[test]
+    fn did_key_other_key_types_are_rejected() {
+        // The same bytes, with the multicodec prefix for an X25519 key.
+        let x25519: &str = "did:key:z6LSrApwZptxFR4jy6U8Z8exYPwTqSXniWLqihApE1oK9WsK";
+
+        assert_eq!(PublicKey::from_did_key(x25519), Err(SignatureError::InvalidKeyLength));
+    }
+
+    #[test]
+    fn did_key_malformed_is_rejected() {
+        assert_eq!(PublicKey::from_did_key(&DID[1..]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_did_key("did:key:z0OIl"), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_did_key(&DID[..DID.len() - 1]),
+                   Err(SignatureError::InvalidKeyLength));
+    }
+}
@@ -0,0 +1,386 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Distributed generation of a shared key, in the protocol of Pedersen ("A
+//! Threshold Cryptosystem without a Trusted Party", EUROCRYPT 1991).
+
+use std::vec::Vec;
+
+use rand::Rng;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::PublicKey;
+use errors::SignatureError;
+
+/// Choose a uniformly random scalar using the `csprng`.
+fn random_scalar(csprng: &mut dyn Rng) -> Scalar {
+    let mut bytes: [u8; 64] = [0u8; 64];
+
+    csprng.fill_bytes(&mut bytes);
+    Scalar::reduce(&bytes)
+}
+
+/// Interpret `bytes` as a scalar, if it is the canonical encoding of one.
+fn canonical_scalar(bytes: &[u8; 32]) -> Option<Scalar> {
+    let mut wide: [u8; 64] = [0u8; 64];
+
+    wide[..32].copy_from_slice(bytes);
+    let scalar: Scalar = Scalar::reduce(&wide);
+
+    if scalar.as_bytes() == bytes { Some(scalar) } else { None }
+}
+
+/// A message from one participant to another in the first round of the
+/// protocol of `DkgParticipant`.
+///
+/// Its commitments are public, but its share is secret; see the warning for
+/// `DkgParticipant` on how each must be sent.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DkgBroadcast {
+    sender: u8,
+    recipient: u8,
+    commitments: Vec<CompressedEdwardsY>,
+    share: Scalar,
+}
+
+impl DkgBroadcast {
+    /// The index of the participant who made this message.
+    pub fn sender(&self) -> u8 {
+        self.sender
+    }
+
+    /// The index of the participant to whom this message must be sent.
+    pub fn recipient(&self) -> u8 {
+        self.recipient
+    }
+
+    /// Convert this message to bytes: the sender's and recipient's indices,
+    /// the 32-byte share, and then each 32-byte commitment, starting with the
+    /// commitment to the constant term.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(34 + 32 * self.commitments.len());
+
+        bytes.push(self.sender);
+        bytes.push(self.recipient);
+        bytes.extend_from_slice(self.share.as_bytes());
+        for commitment in self.commitments.iter() {
+            bytes.extend_from_slice(commitment.as_bytes());
+        }
+        bytes
+    }
+
+    /// Construct a message from the bytes produced by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `DkgBroadcast`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the bytes are not of length
+    /// 34 plus a non-zero multiple of 32, or the share is not a canonical
+    /// scalar.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DkgBroadcast, SignatureError> {
+        if bytes.len() < 66 || !(bytes.len() - 34).is_multiple_of(32) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let share: Scalar = canonical_scalar(array_ref!(bytes, 2, 32))
+            .ok_or(SignatureError::InvalidEncoding)?;
+
+        Ok(DkgBroadcast{
+            sender: bytes[0],
+            recipient: bytes[1],
+            commitments: bytes[34..].chunks(32)
+                .map(|chunk| CompressedEdwardsY(*array_ref!(chunk, 0, 32))).collect(),
+            share,
+        })
+    }
+}
+
+/// One participant's share of the secret key made by the protocol of
+/// `DkgParticipant`.
+///
+/// The shares are for threshold protocols which are not in this crate; they
+/// cannot be used to make ed25519 signatures on their own.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SecretKeyShare {
+    index: u8,
+    share: Scalar,
+}
+
+impl SecretKeyShare {
+    /// The index of the participant holding this share.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// The public key corresponding to this share alone, with which the
+    /// other participants can check this participant's contributions to a
+    /// threshold protocol.
+    pub fn public_share(&self) -> PublicKey {
+        PublicKey((&self.share * &constants::ED25519_BASEPOINT_TABLE).compress())
+    }
+
+    /// Convert this share to bytes: its index followed by the 32-byte share.
+    pub fn to_bytes(&self) -> [u8; 33] {
+        let mut bytes: [u8; 33] = [0u8; 33];
+
+        bytes[0] = self.index;
+        bytes[1..].copy_from_slice(self.share.as_bytes());
+        bytes
+    }
+
+    /// Construct a share from the bytes produced by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `SecretKeyShare`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the bytes are not of
+    /// length 33, the index is zero, or the share is not a canonical scalar.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretKeyShare, SignatureError> {
+        if bytes.len() != 33 || bytes[0] == 0 {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let share: Scalar = canonical_scalar(array_ref!(bytes, 1, 32))
+            .ok_or(SignatureError::InvalidEncoding)?;
+
+        Ok(SecretKeyShare{ index: bytes[0], share })
+    }
+}
+
+/// A participant in the distributed key generation protocol of Pedersen,
+/// between its first and second rounds.
+///
+/// Each of the `n` participants, numbered from 1 to `n`, chooses a random
+/// polynomial of degree `t - 1` and, in the first round, sends every
+/// participant (itself included) a `DkgBroadcast` holding Feldman
+/// commitments to the coefficients of its polynomial and the polynomial's
+/// value at the recipient's index.  In the second round, each participant
+/// checks the shares it was sent against their commitments, and sums them
+/// to produce its `SecretKeyShare`.  The group's `PublicKey` is the sum of
+/// the commitments to the constant terms.  The secret key is the sum of the
+/// constant terms, which no participant learns, and can be reconstructed
+/// from any `t` of the shares.
+///
+/// # Warning
+///
+/// This is the simplified protocol, without the complaint round:
+///
+/// * The commitments in a `DkgBroadcast` are public, but its share is
+///   secret, and must be sent only to its recipient, over an authenticated
+///   and encrypted channel.
+/// * A participant who sends different commitments to different recipients
+///   is not detected, so the commitments must be sent over a broadcast
+///   channel, or compared among the participants afterwards.
+/// * A participant who sends a bad share causes `finalize()` to fail for
+///   its recipient, and the protocol must be restarted without it.
+/// * As Gennaro et al. showed, a malicious participant can bias the
+///   distribution of the group's public key.
+#[derive(Clone, Debug)]
+pub struct DkgParticipant {
+    index: u8,
+    threshold: u8,
+    n_parties: u8,
+}
+
+impl DkgParticipant {
+    /// Begin the protocol as the participant with the given `index`, among
+    /// `n_parties`, any `threshold` of whom will be able to reconstruct the
+    /// secret key, using randomness from the `csprng`.
+    ///
+    /// # Returns
+    ///
+    /// The participant, and one `DkgBroadcast` for each participant, in order
+    /// of their indices, to be sent as described for `DkgParticipant`.
+    ///
+    /// # Panics
+    ///
+    /// If the `index` is not between 1 and `n_parties`, or the `threshold` is
+    /// not between 1 and `n_parties`.
+    pub fn new(index: u8, threshold: u8, n_parties: u8, csprng: &mut dyn Rng)
+            -> (DkgParticipant, Vec<DkgBroadcast>) {
+
+        assert!(index >= 1 && index <= n_parties, "participant index out of range");
+        assert!(threshold >= 1 && threshold <= n_parties, "threshold out of range");
+
+        let coefficients: Vec<Scalar> = (0..threshold).map(|_| random_scalar(csprng)).collect();
+        let commitments: Vec<CompressedEdwardsY> = coefficients.iter()
+            .map(|a| (a * &constants::ED25519_BASEPOINT_TABLE).compress()).collect();
+
+        let broadcasts: Vec<DkgBroadcast> = (1..n_parties as u16 + 1).map(|j| {
+            let x: Scalar = Scalar::from_u64(j as u64);
+            let share: Scalar = coefficients.iter().rev()
+                .fold(Scalar::zero(), |acc, a| Scalar::multiply_add(&acc, &x, a));
+
+            DkgBroadcast{
+                sender: index,
+                recipient: j as u8,
+                commitments: commitments.clone(),
+                share,
+            }
+        }).collect();
+
+        (DkgParticipant{ index, threshold, n_parties }, broadcasts)
+    }
+
+    /// Complete the protocol, given the `broadcasts` sent to this participant
+    /// by every participant (itself included), in any order.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is this participant's `SecretKeyShare` and
+    /// the group's `PublicKey`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidEncoding` if a commitment is not a valid
+    ///   point, or
+    /// * `SignatureError::InvalidInput` if there is not exactly one broadcast
+    ///   from each participant, a broadcast is for another recipient or has
+    ///   the wrong number of commitments, or a share does not match its
+    ///   sender's commitments.
+    pub fn finalize(&self, broadcasts: &[DkgBroadcast])
+            -> Result<(SecretKeyShare, PublicKey), SignatureError> {
+
+        if broadcasts.len() != self.n_parties as usize {
+            return Err(SignatureError::InvalidInput);
+        }
+        let x: Scalar = Scalar::from_u64(self.index as u64);
+        let mut seen: Vec<bool> = vec![false; self.n_parties as usize + 1];
+        let mut share: Scalar = Scalar::zero();
+        let mut group_key: Option<ExtendedPoint> = None;
+
+        for broadcast in broadcasts.iter() {
+            let sender: usize = broadcast.sender as usize;
+
+            if sender == 0 || sender > self.n_parties as usize || seen[sender] ||
+                broadcast.recipient != self.index ||
+                broadcast.commitments.len() != self.threshold as usize {
+                return Err(SignatureError::InvalidInput);
+            }
+            seen[sender] = true;
+
+            let commitments: Vec<ExtendedPoint> = broadcast.commitments.iter()
+                .map(|c| c.decompress()).collect::<Option<Vec<_>>>()
+                .ok_or(SignatureError::InvalidEncoding)?;
+
+            // Feldman's check: the share must be the committed polynomial
+            // evaluated at our index, "in the exponent".
+            let mut expected: ExtendedPoint = commitments[commitments.len() - 1];
+
+            for commitment in commitments.iter().rev().skip(1) {
+                expected = &(&expected * &x) + commitment;
+            }
+            let actual: ExtendedPoint = &broadcast.share * &constants::ED25519_BASEPOINT_TABLE;
+
+            if actual.compress() != expected.compress() {
+                return Err(SignatureError::InvalidInput);
+            }
+            share += &broadcast.share;
+            group_key = Some(match group_key {
+                Some(sum) => &sum + &commitments[0],
+                None => commitments[0],
+            });
+        }
+
+        Ok((SecretKeyShare{ index: self.index, share },
+            PublicKey(group_key.unwrap().compress())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use super::*;
+
+    /// Run the protocol among `n` participants, returning each one's result.
+    fn run(threshold: u8, n: u8) -> Vec<(SecretKeyShare, PublicKey)> {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let (participants, broadcasts): (Vec<DkgParticipant>, Vec<Vec<DkgBroadcast>>) =
+            (1..n + 1).map(|i| DkgParticipant::new(i, threshold, n, &mut csprng)).unzip();
+
+        participants.iter().map(|participant| {
+            let received: Vec<DkgBroadcast> = broadcasts.iter()
+                .map(|from| from[participant.index as usize - 1].clone()).collect();
+
+            participant.finalize(&received).unwrap()
+        }).collect()
+    }
+
+    /// Reconstruct the secret scalar from `shares` by Lagrange interpolation.
+    fn interpolate(shares: &[&SecretKeyShare]) -> Scalar {
+        shares.iter().fold(Scalar::zero(), |acc, share_i| {
+            let x_i: Scalar = Scalar::from_u64(share_i.index as u64);
+            let coefficient: Scalar = shares.iter()
+                .filter(|share_j| share_j.index != share_i.index)
+                .fold(Scalar::one(), |c, share_j| {
+                    let x_j: Scalar = Scalar::from_u64(share_j.index as u64);
+
+                    &(&c * &x_j) * &(&x_j - &x_i).invert()
+                });
+
+            Scalar::multiply_add(&coefficient, &share_i.share, &acc)
+        })
+    }
+
+    #[test]
+    fn dkg_participants_agree_on_group_key() {
+        let results: Vec<(SecretKeyShare, PublicKey)> = run(2, 3);
+
+        for (share, group_key) in results.iter() {
+            assert_eq!(*group_key, results[0].1);
+            assert!(share.public_share() != *group_key);
+        }
+    }
+
+    #[test]
+    fn dkg_any_threshold_shares_reconstruct_secret() {
+        let results: Vec<(SecretKeyShare, PublicKey)> = run(3, 5);
+        let group_key: PublicKey = results[0].1;
+        let shares: Vec<&SecretKeyShare> = results.iter().map(|r| &r.0).collect();
+
+        for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4]].iter() {
+            let chosen: Vec<&SecretKeyShare> = subset.iter().map(|&i| shares[i]).collect();
+            let secret: Scalar = interpolate(&chosen);
+
+            assert_eq!((&secret * &constants::ED25519_BASEPOINT_TABLE).compress(), group_key.0);
+        }
+        let secret: Scalar = interpolate(&shares[..2]);
+
+        assert!((&secret * &constants::ED25519_BASEPOINT_TABLE).compress() != group_key.0);
+    }
+
+    #[test]
+    fn dkg_bad_share_is_rejected() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let (first, mut from_first) = DkgParticipant::new(1, 2, 2, &mut csprng);
+        let (_, from_second) = DkgParticipant::new(2, 2, 2, &mut csprng);
+        let mut received: Vec<DkgBroadcast> = vec![from_first[0].clone(), from_second[0].clone()];
+
+        assert!(first.finalize(&received).is_ok());
+
+        received[1].share += &Scalar::one();
+        assert_eq!(first.finalize(&received).unwrap_err(), SignatureError::InvalidInput);
+
+        from_first.truncate(1);
+        assert_eq!(first.finalize(&from_first).unwrap_err(), SignatureError::InvalidInput);
+        assert_eq!(first.finalize(&[from_first[0].clone(), from_first[0].clone()]).unwrap_err(),
+                   SignatureError::InvalidInput);
+    }
+
+    #[test]
+    fn dkg_messages_roundtrip() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let (participant, broadcasts) = DkgParticipant::new(1, 1, 1, &mut csprng);
+        let broadcast: DkgBroadcast = DkgBroadcast::from_bytes(&broadcasts[0].to_bytes()).unwrap();
+        let (share, _) = participant.finalize(core::slice::from_ref(&broadcast)).unwrap();
+
+        assert_eq!(broadcast, broadcasts[0]);
+        assert_eq!(SecretKeyShare::from_bytes(&share.to_bytes()), Ok(share));
+    }
+}
@@ -0,0 +1,250 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing combined with hybrid encryption to an X25519 public key.
+
+use std::vec::Vec;
+
+use rand::OsRng;
+use rand::Rng;
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::Key;
+use aes_gcm::Nonce;
+use aes_gcm::aead::{Aead, KeyInit};
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::montgomery::CompressedMontgomeryU;
+use curve25519_dalek::constants;
+use curve25519_dalek::scalar::Scalar;
+
+use subtle::slices_equal;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use ed25519::SIGNATURE_LENGTH;
+use errors::SignatureError;
+use hmac;
+
+/// The length of an AES-GCM nonce, in bytes.
+const NONCE_LENGTH: usize = 12;
+
+/// The length of an AES-GCM authentication tag, in bytes.
+const TAG_LENGTH: usize = 16;
+
+/// The HKDF info string used to derive the AES-256-GCM key.
+const KDF_INFO: &[u8] = b"ed25519-dalek sign_and_encrypt";
+
+/// Compute the X25519 function of RFC7748 on the scalar `k` and the
+/// u-coordinate `u`.
+fn x25519(k: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let mut scalar: Scalar = Scalar(*k);
+
+    scalar[0]  &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+
+    (&CompressedMontgomeryU(*u).decompress() * &scalar).compress().to_bytes()
+}
+
+/// Derive the AES-256-GCM key for a message from the ephemeral public key,
+/// the recipient's public key, and their shared secret.
+fn derive_key<D>(ephemeral_public: &[u8; 32], recipient_public: &[u8; 32],
+                 shared_secret: &[u8; 32]) -> [u8; 32]
+        where D: Digest<OutputSize = U64> + Default {
+
+    let mut salt: [u8; 64] = [0u8; 64];
+    let mut key: [u8; 32] = [0u8; 32];
+
+    salt[..32].copy_from_slice(ephemeral_public);
+    salt[32..].copy_from_slice(recipient_public);
+
+    let prk = hmac::hkdf_extract::<D>(&salt, shared_secret);
+    hmac::hkdf_expand::<D>(&prk, KDF_INFO, &mut key);
+    key
+}
+
+impl Keypair {
+    /// Sign a `message`, and encrypt the signature and message to the owner
+    /// of an X25519 public key.
+    ///
+    /// The message is signed, then an ephemeral X25519 key is generated and
+    /// used to compute a shared secret with the recipient, from which an
+    /// AES-256-GCM key is derived with HKDF-`D`.  The output is the ephemeral
+    /// public key, then a random nonce, then the encryption of the signature
+    /// followed by the message.
+    ///
+    /// # Warning
+    ///
+    /// The signature covers the message, but not the recipient, so the
+    /// recipient could decrypt it and re-encrypt it to somebody else, who
+    /// would believe that it was sent to them.  Include the recipient in the
+    /// message if that matters.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the ciphertext, or whose error value is
+    ///
+    /// * `SignatureError::EntropyError` if the operating system's random
+    ///   number generator is unavailable, or
+    /// * `SignatureError::InvalidInput` if the recipient's public key is of
+    ///   low order, so that the shared secret would be zero, and the key
+    ///   could be computed by anyone.
+    pub fn sign_and_encrypt<D>(&self, message: &[u8], recipient_x25519_public: &[u8; 32])
+            -> Result<Vec<u8>, SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut csprng: OsRng = OsRng::new().or(Err(SignatureError::EntropyError))?;
+        let mut ephemeral_secret: [u8; 32] = [0u8; 32];
+        let mut nonce: [u8; NONCE_LENGTH] = [0u8; NONCE_LENGTH];
+
+        csprng.fill_bytes(&mut ephemeral_secret);
+        csprng.fill_bytes(&mut nonce);
+
+        let ephemeral_public: [u8; 32] = x25519(&ephemeral_secret,
+                                                constants::BASE_COMPRESSED_MONTGOMERY.as_bytes());
+        let shared_secret: [u8; 32] = x25519(&ephemeral_secret, recipient_x25519_public);
+
+        // A low-order recipient key yields an all-zero shared secret.
+        if slices_equal(&shared_secret, &[0u8; 32]) == 1 {
+            return Err(SignatureError::InvalidInput);
+        }
+        let key: [u8; 32] = derive_key::<D>(&ephemeral_public, recipient_x25519_public,
+                                            &shared_secret);
+
+        let mut plaintext: Vec<u8> = Vec::with_capacity(SIGNATURE_LENGTH + message.len());
+        plaintext.extend_from_slice(&self.sign::<D>(message).to_bytes());
+        plaintext.extend_from_slice(message);
+
+        let cipher: Aes256Gcm = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+        let ciphertext: Vec<u8> = cipher.encrypt(Nonce::from_slice(&nonce), &plaintext[..])
+            .or(Err(SignatureError::DecryptionFailed))?;
+
+        let mut output: Vec<u8> = Vec::with_capacity(32 + NONCE_LENGTH + ciphertext.len());
+        output.extend_from_slice(&ephemeral_public);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+}
+
+/// Decrypt a ciphertext produced by `Keypair::sign_and_encrypt()`, and verify
+/// that the message within it was signed by `sender`.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the message, or whose error value is
+///
+/// * `SignatureError::DecryptionFailed` if the ciphertext is malformed, or
+///   was not encrypted to `recipient_x25519_secret`, or
+/// * `SignatureError::InvalidSignature` if it decrypts, but the message was
+///   not signed by `sender`.
+pub fn decrypt_and_verify<D>(recipient_x25519_secret: &[u8; 32], sender: &PublicKey,
+                             ciphertext: &[u8]) -> Result<Vec<u8>, SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    if ciphertext.len() < 32 + NONCE_LENGTH + TAG_LENGTH + SIGNATURE_LENGTH {
+        return Err(SignatureError::DecryptionFailed);
+    }
+    let ephemeral_public: &[u8; 32] = array_ref!(ciphertext, 0, 32);
+    let nonce: &[u8] = &ciphertext[32..32 + NONCE_LENGTH];
+
+    let recipient_public: [u8; 32] = x25519(recipient_x25519_secret,
+                                            constants::BASE_COMPRESSED_MONTGOMERY.as_bytes());
+    let shared_secret: [u8; 32] = x25519(recipient_x25519_secret, ephemeral_public);
+
+    // A low-order ephemeral key yields an all-zero shared secret.
+    if slices_equal(&shared_secret, &[0u8; 32]) == 1 {
+        return Err(SignatureError::DecryptionFailed);
+    }
+    let key: [u8; 32] = derive_key::<D>(ephemeral_public, &recipient_public, &shared_secret);
+
+    let cipher: Aes256Gcm = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let mut plaintext: Vec<u8> = cipher.decrypt(Nonce::from_slice(nonce),
+                                                &ciphertext[32 + NONCE_LENGTH..])
+        .or(Err(SignatureError::DecryptionFailed))?;
+
+    let message: Vec<u8> = plaintext.split_off(SIGNATURE_LENGTH);
+    let signature: Signature = Signature::from_bytes(&plaintext)
+        .or(Err(SignatureError::DecryptionFailed))?;
+
+    sender.verify::<D>(&message, &signature)?;
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    // Bob's keys from RFC7748 §6.1.
+    fn bob() -> ([u8; 32], [u8; 32]) {
+        let secret: Vec<u8> = FromHex::from_hex(
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb").unwrap();
+        let public: Vec<u8> = FromHex::from_hex(
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f").unwrap();
+
+        (*array_ref!(secret, 0, 32), *array_ref!(public, 0, 32))
+    }
+
+    #[test]
+    fn x25519_rfc7748_public_key() {
+        let (secret, public) = bob();
+
+        assert_eq!(x25519(&secret, constants::BASE_COMPRESSED_MONTGOMERY.as_bytes()), public);
+    }
+
+    #[test]
+    fn sign_and_encrypt_roundtrip() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let (bob_secret, bob_public) = bob();
+
+        let ciphertext: Vec<u8> = alice.sign_and_encrypt::<Sha512>(b"test message", &bob_public)
+            .unwrap();
+        let message: Vec<u8> = decrypt_and_verify::<Sha512>(&bob_secret, &alice.public,
+                                                            &ciphertext).unwrap();
+
+        assert_eq!(ciphertext.len(), 32 + NONCE_LENGTH + TAG_LENGTH + SIGNATURE_LENGTH + 12);
+        assert_eq!(&message[..], b"test message");
+    }
+
+    #[test]
+    fn decrypt_and_verify_rejects_tampering() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let mallory: Keypair = keypair_for_testing("mallory");
+        let (bob_secret, bob_public) = bob();
+
+        let mut ciphertext: Vec<u8> = alice.sign_and_encrypt::<Sha512>(b"test message",
+                                                                       &bob_public).unwrap();
+
+        assert_eq!(decrypt_and_verify::<Sha512>(&bob_secret, &mallory.public, &ciphertext),
+                   Err(SignatureError::InvalidSignature));
+
+        let last: usize = ciphertext.len() - 1;
+        ciphertext[last] ^= 1;
+        assert_eq!(decrypt_and_verify::<Sha512>(&bob_secret, &alice.public, &ciphertext),
+                   Err(SignatureError::DecryptionFailed));
+        assert_eq!(decrypt_and_verify::<Sha512>(&bob_secret, &alice.public, &ciphertext[..100]),
+                   Err(SignatureError::DecryptionFailed));
+    }
+
+    #[test]
+    fn sign_and_encrypt_rejects_low_order_recipient() {
+        let alice: Keypair = keypair_for_testing("alice");
+
+        // u = 0 is the point of order two, for which the shared secret is zero.
+        assert_eq!(alice.sign_and_encrypt::<Sha512>(b"test message", &[0u8; 32]),
+                   Err(SignatureError::InvalidInput));
+    }
+}
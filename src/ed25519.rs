@@ -10,11 +10,19 @@
 //! A Rust implementation of ed25519 EdDSA key generation, signing, and
 //! verification.
 
+use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::fmt::Debug;
+use core::hash::{Hash, Hasher};
 
 #[cfg(feature = "std")]
 use rand::Rng;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::time::Duration;
+
 #[cfg(feature = "serde")]
 use serde::{Serialize, Deserialize};
 #[cfg(feature = "serde")]
@@ -24,6 +32,8 @@ use serde::de::Error as SerdeError;
 #[cfg(feature = "serde")]
 use serde::de::Visitor;
 
+#[cfg(feature = "sha2")]
+use sha2::Sha256;
 #[cfg(feature = "sha2")]
 use sha2::Sha512;
 
@@ -41,6 +51,16 @@ use curve25519_dalek::scalar::Scalar;
 
 use subtle::slices_equal;
 
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[cfg(feature = "hkdf")]
+use hmac;
+
+use errors::SignatureError;
+#[cfg(feature = "sha2")]
+use errors::SignatureMismatchError;
+
 /// The length of an ed25519 EdDSA `Signature`, in bytes.
 pub const SIGNATURE_LENGTH: usize = 64;
 
@@ -53,6 +73,33 @@ pub const PUBLIC_KEY_LENGTH: usize = 32;
 /// The length of an ed25519 EdDSA `Keypair`, in bytes.
 pub const KEYPAIR_LENGTH: usize = SECRET_KEY_LENGTH + PUBLIC_KEY_LENGTH;
 
+/// The length of an ed25519 EdDSA `ExpandedSecretKey`, in bytes.
+pub const EXPANDED_SECRET_KEY_LENGTH: usize = 64;
+
+/// The maximum length of an Ed25519ctx or Ed25519ph context string, in bytes.
+pub const MAX_CONTEXT_LENGTH: usize = 255;
+
+/// The length of the key identifier of a `PublicKey`, in bytes.
+pub const KEY_ID_LENGTH: usize = 8;
+
+/// The fixed prefix of `dom2(flag, context)`, as specified in RFC8032.
+const DOM2_PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
+
+/// Hash `dom2(flag, context)` into `h`, if `dom2` is `Some((flag, context))`.
+///
+/// # Panics
+///
+/// If the `context` is longer than `MAX_CONTEXT_LENGTH`.
+fn input_dom2<D>(h: &mut D, dom2: Option<(u8, &[u8])>) where D: Digest {
+    if let Some((flag, context)) = dom2 {
+        assert!(context.len() <= MAX_CONTEXT_LENGTH, "context is longer than 255 bytes");
+
+        h.input(DOM2_PREFIX);
+        h.input(&[flag, context.len() as u8]);
+        h.input(context);
+    }
+}
+
 /// An EdDSA signature.
 ///
 /// # Note
@@ -92,21 +139,53 @@ impl Clone for Signature {
 
 impl Debug for Signature {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-        write!(f, "Signature( r: {:?}, s: {:?} )", &self.r, &self.s)
+        write!(f, "Signature(\"{}\")", self)
     }
 }
 
 impl Eq for Signature {}
 
+/// Signatures are compared in constant time, so that comparing a forgery
+/// with a valid signature does not reveal how many of their bytes match.
+/// Verification does not use this; it makes its own constant-time comparison
+/// of the recomputed `R`.
 impl PartialEq for Signature {
     fn eq(&self, other: &Signature) -> bool {
-        let mut equal: u8 = 0;
+        self.ct_eq_bytes(&other.to_bytes())
+    }
+}
 
-        for i in 0..32 {
-            equal |= self.r.0[i] ^ other.r.0[i];
-            equal |= self.s[i]   ^ other.s[i];
+impl PartialEq<[u8; SIGNATURE_LENGTH]> for Signature {
+    fn eq(&self, other: &[u8; SIGNATURE_LENGTH]) -> bool {
+        self.ct_eq_bytes(other)
+    }
+}
+
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for Signature {
+    type Error = &'static str;
+
+    /// Construct a `Signature` from a slice of exactly `SIGNATURE_LENGTH` bytes.
+    fn try_from(bytes: &'a [u8]) -> Result<Signature, &'static str> {
+        if bytes.len() != SIGNATURE_LENGTH {
+            return Err("Wrong length of bytes for signature! Need 64 bytes.");
         }
-        equal == 0
+        Ok(Signature::from(*array_ref!(bytes, 0, SIGNATURE_LENGTH)))
+    }
+}
+
+impl From<[u8; SIGNATURE_LENGTH]> for Signature {
+    /// Construct a `Signature` from the bytes `R || s`.
+    fn from(bytes: [u8; SIGNATURE_LENGTH]) -> Signature {
+        let lower: &[u8; 32] = array_ref!(bytes,  0, 32);
+        let upper: &[u8; 32] = array_ref!(bytes, 32, 32);
+
+        Signature{ r: CompressedEdwardsY(*lower), s: Scalar(*upper) }
     }
 }
 
@@ -121,18 +200,149 @@ impl Signature {
         signature_bytes
     }
 
+    /// Convert this `Signature` to a `Vec` of its 64 bytes, as `R || s`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.to_bytes().to_vec()
+    }
+
+    /// Convert this `Signature` to a byte array, as `R || s`.
+    ///
+    /// This is the same as `to_bytes()`.
+    #[inline]
+    pub fn to_raw_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        self.to_bytes()
+    }
+
     /// Construct a `Signature` from a slice of bytes.
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Result<Signature, &'static str> {
-        if bytes.len() != SIGNATURE_LENGTH {
-            return Err("Wrong length of bytes for signature! Need 64 bytes.")
+        Signature::try_from(bytes)
+    }
+
+    /// Mask this `Signature` by XORing its bytes with a `pad`.
+    ///
+    /// # Warning
+    ///
+    /// This is a one-time pad, nothing more.  It provides no hiding whatsoever
+    /// unless the `pad` is uniformly random, secret, and never reused: XORing
+    /// two masked signatures made with the same pad cancels the pad, and anyone
+    /// who knows the signed message and public key can recognise an unmasked
+    /// signature.  There is no integrity protection either, since flipping a
+    /// bit of the masked bytes flips the same bit of the recovered signature.
+    ///
+    /// # Returns
+    ///
+    /// The masked signature bytes, which may be unmasked again with
+    /// `Signature::from_xored()` and the same `pad`.
+    pub fn xor_with(&self, pad: &[u8; SIGNATURE_LENGTH]) -> [u8; SIGNATURE_LENGTH] {
+        let mut masked: [u8; SIGNATURE_LENGTH] = self.to_bytes();
+
+        for i in 0..SIGNATURE_LENGTH {
+            masked[i] ^= pad[i];
         }
+        masked
+    }
 
-        let lower: &[u8; 32] = array_ref!(bytes,  0, 32);
-        let upper: &[u8; 32] = array_ref!(bytes, 32, 32);
+    /// Unmask a `Signature` which was masked with `Signature::xor_with()`
+    /// using the same `pad`.
+    ///
+    /// # Warning
+    ///
+    /// See `Signature::xor_with()`.  The result is not checked in any way, so
+    /// unmasking with the wrong pad yields garbage which will simply fail to
+    /// verify.
+    pub fn from_xored(masked: &[u8; SIGNATURE_LENGTH], pad: &[u8; SIGNATURE_LENGTH]) -> Signature {
+        let mut bytes: [u8; SIGNATURE_LENGTH] = *masked;
+
+        for i in 0..SIGNATURE_LENGTH {
+            bytes[i] ^= pad[i];
+        }
+        Signature{ r: CompressedEdwardsY(*array_ref!(bytes,  0, 32)),
+                   s: Scalar(*array_ref!(bytes, 32, 32)) }
+    }
+
+    /// Get the compressed nonce commitment `R`, i.e. the first 32 bytes, of
+    /// this `Signature`.
+    ///
+    /// In a commit-reveal protocol, the signer may reveal `R` first and the
+    /// full signature later.  Since `s` is determined by `R`, the public key,
+    /// and the message, revealing `R` before the message is fixed commits the
+    /// signer to a particular nonce.
+    ///
+    /// # Warning
+    ///
+    /// A signer who reveals the same `R` for two different messages has
+    /// reused a nonce, and anyone holding both full signatures can recover
+    /// the secret key.  This cannot happen with the deterministic nonces of
+    /// `ExpandedSecretKey::sign()`, since `R` depends upon the message.
+    pub fn r_commitment(&self) -> [u8; 32] {
+        self.r.to_bytes()
+    }
 
-        Ok(Signature{ r: CompressedEdwardsY(*lower), s: Scalar(*upper) })
+    /// Check, in constant time, that the `R` of this `Signature` matches a
+    /// previously revealed `commitment`.
+    pub fn verify_r_commitment(&self, commitment: &[u8; 32]) -> bool {
+        slices_equal(self.r.as_bytes(), commitment) == 1
     }
+
+    /// Determine, in constant time, whether this signature has exactly the
+    /// same bytes as `other`.
+    ///
+    /// This is the same comparison as `==`, but explicit.  Note that two
+    /// different encodings of the same signature, e.g. with `s` and `s + l`,
+    /// are not identical; see `ReplayFilter` for a comparison which is
+    /// robust against this.
+    pub fn is_identical_to(&self, other: &Signature) -> bool {
+        slices_equal(&self.to_bytes(), &other.to_bytes()) == 1
+    }
+
+    /// Determine, in constant time, whether this signature is encoded as
+    /// exactly the given `bytes`, without parsing them as a `Signature`.
+    ///
+    /// This is the comparison used by `==` between a `Signature` and an
+    /// array of bytes.
+    pub fn ct_eq_bytes(&self, bytes: &[u8; SIGNATURE_LENGTH]) -> bool {
+        slices_equal(&self.to_bytes(), bytes) == 1
+    }
+
+    /// Determine whether the `s` of this `Signature` is canonical, i.e. fully
+    /// reduced modulo ℓ, as RFC8032 requires.
+    ///
+    /// `PublicKey::verify()` accepts any `s` of at most 253 bits, so `s + ℓ`
+    /// is also accepted whenever it fits; protocols which need a signature to
+    /// have a single encoding should check this as well.
+    pub fn has_canonical_s(&self) -> bool {
+        let mut wide_s: [u8; 64] = [0u8; 64];
+
+        wide_s[..32].copy_from_slice(self.s.as_bytes());
+        Scalar::reduce(&wide_s) == self.s
+    }
+}
+
+/// Check that the `s` half of the encoded signature `signature_bytes` is less
+/// than ℓ, without parsing the rest of it.
+///
+/// This is much cheaper than decompressing `R`, so it can be used to discard
+/// malformed signatures early.  It is equivalent to
+/// `Signature::has_canonical_s()`.
+///
+/// # Returns
+///
+/// `Ok(())` if `s` is in range, or `Err(SignatureError::ScalarFormatError)`.
+pub fn validate_signature_s(signature_bytes: &[u8; SIGNATURE_LENGTH]) -> Result<(), SignatureError> {
+    let s: &[u8] = &signature_bytes[32..];
+
+    // Compare s and ℓ as little-endian integers, from the top byte down.
+    for i in (0..32).rev() {
+        if s[i] < constants::l.0[i] {
+            return Ok(());
+        }
+        if s[i] > constants::l.0[i] {
+            return Err(SignatureError::ScalarFormatError);
+        }
+    }
+    Err(SignatureError::ScalarFormatError)
 }
 
 #[cfg(feature = "serde")]
@@ -172,6 +382,45 @@ impl Debug for SecretKey {
     }
 }
 
+/// Overwrite this `SecretKey` with zeroes.  With the "zeroize" feature, this
+/// happens automatically when it is dropped, including as part of a
+/// `Keypair`; call it directly to clear the key sooner.
+#[cfg(feature = "zeroize")]
+impl Zeroize for SecretKey {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for SecretKey {}
+
+impl<'a> TryFrom<&'a [u8]> for SecretKey {
+    type Error = &'static str;
+
+    /// Construct a `SecretKey` from a slice of exactly `SECRET_KEY_LENGTH` bytes.
+    fn try_from(bytes: &'a [u8]) -> Result<SecretKey, &'static str> {
+        if bytes.len() != SECRET_KEY_LENGTH {
+            return Err("Wrong length of bytes for creating secret key!");
+        }
+        Ok(SecretKey::from(*array_ref!(bytes, 0, SECRET_KEY_LENGTH)))
+    }
+}
+
+impl From<[u8; SECRET_KEY_LENGTH]> for SecretKey {
+    /// Construct a `SecretKey` from its bytes.
+    fn from(bytes: [u8; SECRET_KEY_LENGTH]) -> SecretKey {
+        SecretKey(bytes)
+    }
+}
+
 impl SecretKey {
     /// Expand this `SecretKey` into an `ExpandedSecretKey`.
     pub fn expand<D>(&self) -> ExpandedSecretKey where D: Digest<OutputSize = U64> + Default {
@@ -190,6 +439,12 @@ impl SecretKey {
         &self.0
     }
 
+    /// Derive the `PublicKey` corresponding to this secret key, using the
+    /// hash function `D`.  This is `PublicKey::from_secret::<D>(self)`.
+    pub fn to_public<D>(&self) -> PublicKey where D: Digest<OutputSize = U64> + Default {
+        PublicKey::from_secret::<D>(self)
+    }
+
     /// Construct a `SecretKey` from a slice of bytes.
     ///
     /// # Example
@@ -224,10 +479,7 @@ impl SecretKey {
     /// is an `&'static str` describing the error that occurred.
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey, &'static str> {
-        if bytes.len() != SECRET_KEY_LENGTH {
-            return Err("Wrong length of bytes for creating secret key!");
-        }
-        Ok(SecretKey(*array_ref!(bytes, 0, SECRET_KEY_LENGTH)))
+        SecretKey::try_from(bytes)
     }
 
     /// Generate a `SecretKey` from a `csprng`.
@@ -296,6 +548,62 @@ impl SecretKey {
 
         sk
     }
+
+    /// Construct a `SecretKey` from a 32-byte `seed`, as in RFC8032 §5.1.5,
+    /// where the seed is the secret key itself.
+    ///
+    /// This needs no random number generator, so the seed may come from
+    /// anywhere, e.g. a key derivation function, but it must be secret and
+    /// uniformly random.
+    pub fn from_seed(seed: &[u8; SECRET_KEY_LENGTH]) -> SecretKey {
+        SecretKey(*seed)
+    }
+
+    /// Derive the child `SecretKey`s at each of the indices `start..end`.
+    ///
+    /// Each child key is the 32-byte output of HKDF (using the hash function
+    /// `D`), with this secret key as the input keying material, no salt, and
+    /// the child's index encoded as four little-endian bytes as the `info`.
+    /// Children at different indices are therefore independent keys.
+    ///
+    /// # Returns
+    ///
+    /// A `Vec` of `end - start` child keys, in order of their indices, which
+    /// is empty if `start >= end`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate sha2;
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(all(feature = "hkdf", feature = "std"))]
+    /// # fn main() {
+    /// use sha2::Sha512;
+    /// use ed25519_dalek::SecretKey;
+    ///
+    /// let root: SecretKey = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    /// let children: Vec<SecretKey> = root.derive_range::<Sha512>(0, 10);
+    ///
+    /// assert_eq!(children.len(), 10);
+    /// # }
+    /// # #[cfg(not(all(feature = "hkdf", feature = "std")))]
+    /// # fn main() { }
+    /// ```
+    #[cfg(all(feature = "hkdf", feature = "std"))]
+    pub fn derive_range<D>(&self, start: u32, end: u32) -> Vec<SecretKey> where D: Digest {
+        let prk = hmac::hkdf_extract::<D>(&[], self.as_bytes());
+
+        (start..end).map(|index| {
+            let info: [u8; 4] = [ index        as u8,
+                                 (index >>  8) as u8,
+                                 (index >> 16) as u8,
+                                 (index >> 24) as u8 ];
+            let mut child: SecretKey = SecretKey([0u8; SECRET_KEY_LENGTH]);
+
+            hmac::hkdf_expand::<D>(&prk, &info, &mut child.0);
+            child
+        }).collect()
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -366,6 +674,26 @@ pub struct ExpandedSecretKey {
     pub (crate) nonce: [u8; 32],
 }
 
+/// Overwrite both halves of this `ExpandedSecretKey` with zeroes.  With the
+/// "zeroize" feature, this happens automatically when it is dropped.
+#[cfg(feature = "zeroize")]
+impl Zeroize for ExpandedSecretKey {
+    fn zeroize(&mut self) {
+        self.key.0.zeroize();
+        self.nonce.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ExpandedSecretKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl ZeroizeOnDrop for ExpandedSecretKey {}
+
 #[cfg(feature = "sha2")]
 impl<'a> From<&'a SecretKey> for ExpandedSecretKey {
     /// Construct an `ExpandedSecretKey` from a `SecretKey`.
@@ -393,6 +721,26 @@ impl<'a> From<&'a SecretKey> for ExpandedSecretKey {
     }
 }
 
+impl<'a> TryFrom<&'a [u8]> for ExpandedSecretKey {
+    type Error = &'static str;
+
+    /// Construct an `ExpandedSecretKey` from a slice of exactly `EXPANDED_SECRET_KEY_LENGTH` bytes.
+    fn try_from(bytes: &'a [u8]) -> Result<ExpandedSecretKey, &'static str> {
+        if bytes.len() != EXPANDED_SECRET_KEY_LENGTH {
+            return Err("Wrong length of bytes for creating expanded secret key!");
+        }
+        Ok(ExpandedSecretKey::from(*array_ref!(bytes, 0, EXPANDED_SECRET_KEY_LENGTH)))
+    }
+}
+
+impl From<[u8; EXPANDED_SECRET_KEY_LENGTH]> for ExpandedSecretKey {
+    /// Construct an `ExpandedSecretKey` from the bytes `key || nonce`.
+    fn from(bytes: [u8; EXPANDED_SECRET_KEY_LENGTH]) -> ExpandedSecretKey {
+        ExpandedSecretKey{ key: Scalar(*array_ref!(bytes, 0, 32)),
+                           nonce:      *array_ref!(bytes, 32, 32), }
+    }
+}
+
 impl ExpandedSecretKey {
     /// Convert this `ExpandedSecretKey` into an array of 64 bytes.
     ///
@@ -436,7 +784,9 @@ impl ExpandedSecretKey {
         bytes
     }
 
-    /// Construct an `ExpandedSecretKey` from a slice of bytes.
+    /// Construct an `ExpandedSecretKey` from its 64 bytes, `key || nonce`, as
+    /// obtained from `to_bytes()`.  For a slice of bytes, use
+    /// `ExpandedSecretKey::try_from()`.
     ///
     /// # Returns
     ///
@@ -475,12 +825,9 @@ impl ExpandedSecretKey {
     /// # fn main() {}
     /// ```
     #[inline]
-    pub fn from_bytes(bytes: &[u8]) -> Result<ExpandedSecretKey, &'static str> {
-        if bytes.len() != 64 {
-            return Err("Wrong length of bytes for creating expanded secret key!");
-        }
-        Ok(ExpandedSecretKey{ key: Scalar(*array_ref!(bytes, 0, 32)),
-                              nonce:      *array_ref!(bytes, 32, 32), })
+    pub fn from_bytes(bytes: &[u8; EXPANDED_SECRET_KEY_LENGTH])
+            -> Result<ExpandedSecretKey, &'static str> {
+        Ok(ExpandedSecretKey::from(*bytes))
     }
 
     /// Construct an `ExpandedSecretKey` from a `SecretKey`, using hash function `D`.
@@ -526,6 +873,27 @@ impl ExpandedSecretKey {
     /// Sign a message with this `ExpandedSecretKey`.
     pub fn sign<D>(&self, message: &[u8], public_key: &PublicKey) -> Signature
             where D: Digest<OutputSize = U64> + Default {
+        self.sign_with_dom2::<D>(None, &[message], public_key)
+    }
+
+    /// Sign a message with this `ExpandedSecretKey`, prefixing both hashes
+    /// with `dom2(flag, context)` if `dom2` is `Some((flag, context))`.  The
+    /// message signed is the concatenation of each of the `message` parts.
+    ///
+    /// With `None` this is pure Ed25519, with a `flag` of 0 it is Ed25519ctx,
+    /// and with a `flag` of 1 and a prehashed `message` it is Ed25519ph.
+    pub(crate) fn sign_with_dom2<D>(&self, dom2: Option<(u8, &[u8])>, message: &[&[u8]],
+                                    public_key: &PublicKey) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+        self.sign_with_dom2_and_noise::<D>(dom2, &[], message, public_key)
+    }
+
+    /// As for `sign_with_dom2()`, but with `noise` hashed into the nonce,
+    /// between the secret nonce prefix and the message.  With empty `noise`,
+    /// this is exactly `sign_with_dom2()`.
+    pub(crate) fn sign_with_dom2_and_noise<D>(&self, dom2: Option<(u8, &[u8])>, noise: &[u8],
+                                              message: &[&[u8]], public_key: &PublicKey)
+            -> Signature where D: Digest<OutputSize = U64> + Default {
 
         let mut h: D = D::default();
         let mut hash: [u8; 64] = [0u8; 64];
@@ -534,8 +902,12 @@ impl ExpandedSecretKey {
         let r: ExtendedPoint;
         let s: Scalar;
 
+        input_dom2(&mut h, dom2);
         h.input(&self.nonce);
-        h.input(&message);
+        h.input(noise);
+        for part in message {
+            h.input(part);
+        }
         hash.copy_from_slice(h.fixed_result().as_slice());
 
         mesg_digest = Scalar::reduce(&hash);
@@ -543,9 +915,12 @@ impl ExpandedSecretKey {
         r = &mesg_digest * &constants::ED25519_BASEPOINT_TABLE;
 
         h = D::default();
+        input_dom2(&mut h, dom2);
         h.input(r.compress().as_bytes());
         h.input(public_key.as_bytes());
-        h.input(&message);
+        for part in message {
+            h.input(part);
+        }
         hash.copy_from_slice(h.fixed_result().as_slice());
 
         hram_digest = Scalar::reduce(&hash);
@@ -576,7 +951,7 @@ impl<'d> Deserialize<'d> for ExpandedSecretKey {
             }
 
             fn visit_bytes<E>(self, bytes: &[u8]) -> Result<ExpandedSecretKey, E> where E: SerdeError {
-                ExpandedSecretKey::from_bytes(bytes).or(Err(SerdeError::invalid_length(bytes.len(), &self)))
+                ExpandedSecretKey::try_from(bytes).or(Err(SerdeError::invalid_length(bytes.len(), &self)))
             }
         }
         deserializer.deserialize_bytes(ExpandedSecretKeyVisitor)
@@ -590,7 +965,62 @@ pub struct PublicKey(pub (crate) CompressedEdwardsY);
 
 impl Debug for PublicKey {
     fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
-        write!(f, "PublicKey( CompressedEdwardsY( {:?} ))", self.0)
+        write!(f, "PublicKey(\"{}\")", self)
+    }
+}
+
+#[cfg(feature = "sha2")]
+impl<'a> From<&'a SecretKey> for PublicKey {
+    /// Derive the `PublicKey` corresponding to a `SecretKey`, using SHA-512.
+    fn from(secret_key: &'a SecretKey) -> PublicKey {
+        PublicKey::from_secret::<Sha512>(secret_key)
+    }
+}
+
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+/// Public keys are ordered lexicographically by their compressed encodings.
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &PublicKey) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PublicKey {
+    fn cmp(&self, other: &PublicKey) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for PublicKey {
+    type Error = &'static str;
+
+    /// Construct a `PublicKey` from a slice of exactly `PUBLIC_KEY_LENGTH` bytes,
+    /// rejecting points of small order, as for `PublicKey::from_bytes()`.
+    fn try_from(bytes: &'a [u8]) -> Result<PublicKey, &'static str> {
+        if bytes.len() != PUBLIC_KEY_LENGTH {
+            return Err("Wrong length of bytes for creating public key!");
+        }
+        PublicKey::try_from(*array_ref!(bytes, 0, PUBLIC_KEY_LENGTH))
+    }
+}
+
+impl TryFrom<[u8; PUBLIC_KEY_LENGTH]> for PublicKey {
+    type Error = &'static str;
+
+    /// Construct a `PublicKey` from its compressed Edwards-Y bytes, rejecting
+    /// points of small order, as for `PublicKey::from_bytes()`.
+    fn try_from(bytes: [u8; PUBLIC_KEY_LENGTH]) -> Result<PublicKey, &'static str> {
+        let public_key: PublicKey = PublicKey(CompressedEdwardsY(bytes));
+
+        if public_key.is_weak() {
+            return Err("Public key is a point of small order!");
+        }
+        Ok(public_key)
     }
 }
 
@@ -601,14 +1031,45 @@ impl PublicKey {
         self.0.to_bytes()
     }
 
+    /// Convert this public key to a byte array.
+    ///
+    /// This is the same as `to_bytes()`.
+    #[inline]
+    pub fn to_raw_bytes(&self) -> [u8; PUBLIC_KEY_LENGTH] {
+        self.to_bytes()
+    }
+
+    /// Convert this public key to a `Vec` of its 32 bytes.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
     /// View this public key as a byte array.
     #[inline]
     pub fn as_bytes<'a>(&'a self) -> &'a [u8; PUBLIC_KEY_LENGTH] {
         &(self.0).0
     }
 
+    /// The key identifier of this public key, the first `KEY_ID_LENGTH`
+    /// bytes of the SHA-256 digest of its compressed encoding.
+    ///
+    /// Key identifiers are short, so an attacker can find a public key with
+    /// any given identifier with about 2^64 work.  They are meant to tell keys
+    /// apart, not to authenticate them.
+    #[cfg(feature = "sha2")]
+    pub fn key_id(&self) -> [u8; KEY_ID_LENGTH] {
+        let mut key_id: [u8; KEY_ID_LENGTH] = [0u8; KEY_ID_LENGTH];
+
+        key_id.copy_from_slice(&Sha256::digest(self.as_bytes())[..KEY_ID_LENGTH]);
+        key_id
+    }
+
     /// Construct a `PublicKey` from a slice of bytes.
     ///
+    /// Points of small order, which would accept a signature on any message,
+    /// are rejected; see `PublicKey::is_weak()`.
+    ///
     /// # Warning
     ///
     /// The caller is responsible for ensuring that the bytes passed into this
@@ -644,10 +1105,7 @@ impl PublicKey {
     /// is an `&'static str` describing the error that occurred.
     #[inline]
     pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey, &'static str> {
-        if bytes.len() != PUBLIC_KEY_LENGTH {
-            return Err("Wrong length of bytes for creating public key!");
-        }
-        Ok(PublicKey(CompressedEdwardsY(*array_ref!(bytes, 0, 32))))
+        PublicKey::try_from(bytes)
     }
 
     /// Convert this public key to its underlying extended twisted Edwards coordinate.
@@ -656,8 +1114,75 @@ impl PublicKey {
         self.0.decompress()
     }
 
+    /// Determine whether this public key is a point of small order, i.e. one
+    /// of the eight points in the torsion subgroup.
+    ///
+    /// For such a key `A`, the verification equation `R = s·B - k·A` depends
+    /// on the challenge `k` only modulo 8, so one signature verifies for about
+    /// one message in eight, or, if `A` is the identity, for every message.
+    /// `PublicKey::from_bytes()` rejects these keys.
+    ///
+    /// # Returns
+    ///
+    /// `true` if this public key decompresses to a point of small order, and
+    /// `false` otherwise, including if it fails to decompress.
+    pub fn is_weak(&self) -> bool {
+        match self.decompress() {
+            Some(a) => a.is_small_order(),
+            None    => false,
+        }
+    }
+
+    /// Determine whether this public key lies in the prime-order subgroup,
+    /// i.e. whether `l * A` is the identity, where `l` is the group order.
+    ///
+    /// The curve has cofactor 8, so a compressed point may decode to a point
+    /// with a torsion component.  Honestly generated public keys never have
+    /// one, but protocols which rely upon prime-order points should reject
+    /// keys which do.
+    ///
+    /// # Returns
+    ///
+    /// `true` if this public key decompresses to a point in the prime-order
+    /// subgroup, and `false` otherwise, including if it fails to decompress.
+    pub fn is_torsion_free(&self) -> bool {
+        use curve25519_dalek::edwards::IsIdentity;
+
+        match self.decompress() {
+            Some(a) => (&a * &constants::l).is_identity(),
+            None    => false,
+        }
+    }
+
+    /// Compute the additive inverse, `-A`, of this public key.
+    ///
+    /// Negation on the Edwards curve negates the x-coordinate, so this flips
+    /// the sign bit of the compressed point, except for the points whose
+    /// x-coordinate is zero, which are their own inverses.  Keys which fail
+    /// to decompress simply have their sign bit flipped.
+    ///
+    /// # Warning
+    ///
+    /// The negated key is for use in multi-party computation protocols which
+    /// need `-A` as a group element.  Nobody holds a `SecretKey` for it, so it
+    /// cannot sign anything, and nothing should be verified with it.
+    pub fn negate(&self) -> PublicKey {
+        match self.decompress() {
+            Some(a) => PublicKey((-(&a)).compress()),
+            None    => {
+                let mut bytes: [u8; PUBLIC_KEY_LENGTH] = self.to_bytes();
+
+                bytes[31] ^= 0x80;
+                PublicKey(CompressedEdwardsY(bytes))
+            },
+        }
+    }
+
     /// Derive this public key from its corresponding `SecretKey`.
-    #[cfg(feature = "std")]
+    ///
+    /// This is the only derivation of public keys from secret keys in this
+    /// crate; `SecretKey::to_public()`, `Keypair::generate()` and the rest all
+    /// call it.
     #[allow(unused_assignments)]
     pub fn from_secret<D>(secret_key: &SecretKey) -> PublicKey
             where D: Digest<OutputSize = U64> + Default {
@@ -680,49 +1205,401 @@ impl PublicKey {
         PublicKey(CompressedEdwardsY(pk))
     }
 
-    /// Verify a signature on a message with this keypair's public key.
+    /// Verify a signature on a message with this public key.
     ///
     /// # Return
     ///
-    /// Returns true if the signature was successfully verified, and
-    /// false otherwise.
-    pub fn verify<D>(&self, message: &[u8], signature: &Signature) -> bool
+    /// Returns `Ok(())` if the signature was successfully verified, and
+    /// otherwise
+    ///
+    /// * `Err(SignatureError::ScalarFormatError)` if the `s` of the signature
+    ///   is out of range,
+    /// * `Err(SignatureError::PointDecompressionError)` if this public key is
+    ///   not a point on the curve, or
+    /// * `Err(SignatureError::InvalidSignature)` if the verification equation
+    ///   was not satisfied.
+    pub fn verify<D>(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError>
             where D: Digest<OutputSize = U64> + Default {
+        self.verify_with_dom2::<D>(None, &[message], signature)
+    }
 
-        use curve25519_dalek::edwards::vartime;
+    /// Verify a signature on a message with this public key, as for
+    /// `verify()`, but also rejecting every signature which is malleable or
+    /// non-canonically encoded, or which uses points outside the prime-order
+    /// subgroup.
+    ///
+    /// Any signature made honestly by `Keypair::sign()` verifies with both,
+    /// but `verify()` also accepts some which no honest signer makes, such as
+    /// those whose `s` is not fully reduced modulo ℓ.  This is equivalent to
+    /// `verify()` after checking that:
+    ///
+    /// * `s` is less than ℓ,
+    /// * `R` and this public key are canonical encodings of points, and
+    /// * both points lie in the prime-order subgroup, and neither is the
+    ///   identity.
+    ///
+    /// The subgroup checks each cost a scalar multiplication, so this is
+    /// around three times as slow as `verify()`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified, and
+    /// otherwise
+    ///
+    /// * `Err(SignatureError::ScalarFormatError)` if the `s` of the signature
+    ///   is not less than ℓ,
+    /// * `Err(SignatureError::PointDecompressionError)` if this public key is
+    ///   not a point on the curve,
+    /// * `Err(SignatureError::InvalidEncoding)` if `R` or this public key is
+    ///   not encoded canonically, or
+    /// * `Err(SignatureError::InvalidSignature)` if `R` is not a point on the
+    ///   curve, if either point is outside the prime-order subgroup, or if the
+    ///   verification equation was not satisfied.
+    pub fn verify_strict<D>(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
 
-        let mut h: D = D::default();
-        let mut a: ExtendedPoint;
-        let ao:  Option<ExtendedPoint>;
-        let r: ExtendedPoint;
-        let digest: [u8; 64];
-        let digest_reduced: Scalar;
+        self.strict_points(signature)?;
+        self.verify::<D>(message, signature)
+    }
 
-        if signature.s[31] & 224 != 0 {
-            return false;
+    /// Verify a signature on a message with this public key, as for
+    /// `verify()`, but on failure report which key was used, to help
+    /// diagnose signatures made with the wrong key.
+    ///
+    /// The key is identified in the error by its `key_id()`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified, and
+    /// otherwise a `SignatureMismatchError` holding the key identifier, the
+    /// signature, and the error `verify()` would have returned.
+    #[cfg(feature = "sha2")]
+    pub fn verify_debug<D>(&self, message: &[u8], signature: &Signature)
+            -> Result<(), SignatureMismatchError> where D: Digest<OutputSize = U64> + Default {
+
+        self.verify::<D>(message, signature).map_err(|reason| {
+            SignatureMismatchError{
+                expected_key_id: self.key_id(),
+                signature_bytes: signature.to_bytes(),
+                reason,
+            }
+        })
+    }
+
+    /// Perform the checks of `verify_strict()` which precede the verification
+    /// equation.
+    ///
+    /// # Returns
+    ///
+    /// The decompressed `R` and public key, or an error as for
+    /// `verify_strict()`.
+    pub(crate) fn strict_points(&self, signature: &Signature)
+            -> Result<(ExtendedPoint, ExtendedPoint), SignatureError> {
+
+        use curve25519_dalek::edwards::IsIdentity;
+
+        if !signature.has_canonical_s() {
+            return Err(SignatureError::ScalarFormatError);
         }
-        ao = self.decompress();
+        let a: ExtendedPoint = self.decompress().ok_or(SignatureError::PointDecompressionError)?;
+        let r: ExtendedPoint = signature.r.decompress().ok_or(SignatureError::InvalidSignature)?;
 
-        if ao.is_some() {
-            a = ao.unwrap();
-        } else {
-            return false;
+        if a.compress() != self.0 || r.compress() != signature.r {
+            return Err(SignatureError::InvalidEncoding);
         }
-        a = -(&a);
+        for point in [&r, &a].iter() {
+            if point.is_small_order() || !(*point * &constants::l).is_identity() {
+                return Err(SignatureError::InvalidSignature);
+            }
+        }
+        Ok((r, a))
+    }
 
+    /// Verify a signature on a message with this public key, as for
+    /// `verify()`, for callers which need only to know whether it is valid.
+    pub fn is_valid_signature<D>(&self, message: &[u8], signature: &Signature) -> bool
+            where D: Digest<OutputSize = U64> + Default {
+        self.verify::<D>(message, signature).is_ok()
+    }
+
+    /// Verify a signature on a message with this public key, prefixing the
+    /// hash with `dom2(flag, context)` if `dom2` is `Some((flag, context))`.
+    /// The message is the concatenation of each of the `message` parts.
+    ///
+    /// See `ExpandedSecretKey::sign_with_dom2()`, and `verify()` for the
+    /// errors returned.
+    pub(crate) fn verify_with_dom2<D>(&self, dom2: Option<(u8, &[u8])>, message: &[&[u8]],
+                                      signature: &Signature) -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut h: D = D::default();
+
+        input_dom2(&mut h, dom2);
         h.input(signature.r.as_bytes());
         h.input(self.as_bytes());
-        h.input(&message);
+        for part in message {
+            h.input(part);
+        }
+        self.verify_with_challenge_hash(h, signature)
+    }
+
+    /// Check the verification equation for a `signature` under this public
+    /// key, given the hash `h` into which everything which precedes the
+    /// message, and the message itself, have been input.
+    ///
+    /// See `verify()` for the errors returned.
+    pub(crate) fn verify_with_challenge_hash<D>(&self, h: D, signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        use curve25519_dalek::edwards::vartime;
+
+        let mut a: ExtendedPoint;
+        let ao:  Option<ExtendedPoint>;
+        let r: ExtendedPoint;
+        let digest: [u8; 64];
+        let digest_reduced: Scalar;
+
+        if signature.s[31] & 224 != 0 {
+            return Err(SignatureError::ScalarFormatError);
+        }
+        ao = self.decompress();
+
+        if ao.is_some() {
+            a = ao.unwrap();
+        } else {
+            return Err(SignatureError::PointDecompressionError);
+        }
+        a = -(&a);
 
         let digest_bytes = h.fixed_result();
         digest = *array_ref!(digest_bytes, 0, 64);
         digest_reduced = Scalar::reduce(&digest);
         r = vartime::double_scalar_mult_basepoint(&digest_reduced, &a, &signature.s);
 
-        slices_equal(signature.r.as_bytes(), r.compress().as_bytes()) == 1
+        if slices_equal(signature.r.as_bytes(), r.compress().as_bytes()) == 1 {
+            Ok(())
+        } else {
+            Err(SignatureError::InvalidSignature)
+        }
+    }
+
+    /// Verify a signature on a message with this public key, taking
+    /// approximately the same amount of wall-clock time whether or not the
+    /// signature is valid.
+    ///
+    /// Unlike `verify()`, this never returns early: the full verification
+    /// equation is computed even for signatures which could be rejected
+    /// cheaply, i.e. those whose `s` is out of range or whose public key does
+    /// not decompress.  Afterwards, the calling thread sleeps until
+    /// `CONSTANT_TIME_VERIFY_DURATION` has elapsed since the call began.
+    ///
+    /// # Tradeoffs
+    ///
+    /// * Every call takes at least `CONSTANT_TIME_VERIFY_DURATION`, which is
+    ///   several times longer than an ordinary verification on most hardware.
+    ///   Throughput suffers accordingly.
+    /// * If verification itself takes longer than the padding (e.g. in an
+    ///   unoptimised build, or on a heavily loaded or very slow machine), no
+    ///   padding is applied, and the timing of the underlying variable-time
+    ///   computation is observable.
+    /// * Sleeping is only as precise as the operating system's scheduler.
+    ///
+    /// Note that the inputs to verification are all public, so this is
+    /// chiefly useful to protocols which must not reveal *whether* a
+    /// signature verified, e.g. to an attacker probing an oracle.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified, and
+    /// `Err(SignatureError::InvalidSignature)` otherwise.
+    #[cfg(feature = "std")]
+    pub fn verify_constant_time<D>(&self, message: &[u8], signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        use std::thread;
+        use std::time::Instant;
+        use curve25519_dalek::edwards::Identity;
+        use curve25519_dalek::edwards::vartime;
+
+        let start: Instant = Instant::now();
+        let mut h: D = D::default();
+        let mut valid: bool = signature.s[31] & 224 == 0;
+        let a: ExtendedPoint = match self.decompress() {
+            Some(a) => -(&a),
+            None    => { valid = false; ExtendedPoint::identity() },
+        };
+
+        h.input(signature.r.as_bytes());
+        h.input(self.as_bytes());
+        h.input(message);
+
+        let digest_bytes = h.fixed_result();
+        let digest_reduced: Scalar = Scalar::reduce(array_ref!(digest_bytes, 0, 64));
+        let r: ExtendedPoint = vartime::double_scalar_mult_basepoint(&digest_reduced, &a,
+                                                                     &signature.s);
+
+        valid &= slices_equal(signature.r.as_bytes(), r.compress().as_bytes()) == 1;
+
+        let elapsed = start.elapsed();
+        if elapsed < CONSTANT_TIME_VERIFY_DURATION {
+            thread::sleep(CONSTANT_TIME_VERIFY_DURATION - elapsed);
+        }
+
+        if valid {
+            Ok(())
+        } else {
+            Err(SignatureError::InvalidSignature)
+        }
+    }
+
+    /// Verify a signature made by `Keypair::sign_for_epoch()` on a message
+    /// for the given `epoch`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was made on this `message` for this
+    /// `epoch`, and otherwise an error as for `verify()`.
+    pub fn verify_for_epoch<D>(&self, message: &[u8], signature: &Signature, epoch: u64)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        self.verify_with_dom2::<D>(None, &[&epoch.to_be_bytes(), message], signature)
+    }
+
+    /// Verify a signature made by `Keypair::sign_with_binding()` on a message
+    /// with the given `binding` data.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was made on this `message` with
+    /// exactly this `binding`, and otherwise an error as for `verify()`.
+    pub fn verify_with_binding<D>(&self, message: &[u8], binding: &[u8], signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let length: [u8; 8] = (binding.len() as u64).to_le_bytes();
+
+        self.verify_with_dom2::<D>(None, &[&length, binding, message], signature)
+    }
+
+    /// Verify a signature on a message with this public key, returning the
+    /// message if the signature is valid, for use with the `?` operator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate sha2;
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "testing")]
+    /// # fn main() {
+    /// use sha2::Sha512;
+    /// use ed25519_dalek::{PublicKey, Signature, SignatureError};
+    /// # use ed25519_dalek::keypair_for_testing;
+    ///
+    /// fn parse_command<'a>(public: &PublicKey, message: &'a [u8], signature: &Signature)
+    ///         -> Result<&'a [u8], SignatureError> {
+    ///     let message: &[u8] = public.verify_returning_message::<Sha512>(message, signature)?;
+    ///
+    ///     Ok(&message[..4])
+    /// }
+    /// # let keypair = keypair_for_testing("example");
+    /// # let signature = keypair.sign::<Sha512>(b"stop now");
+    /// # assert_eq!(parse_command(&keypair.public, b"stop now", &signature), Ok(&b"stop"[..]));
+    /// # }
+    /// # #[cfg(not(feature = "testing"))]
+    /// # fn main() { }
+    /// ```
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(message)` if the signature is valid, and otherwise an
+    /// error as for `verify()`.
+    pub fn verify_returning_message<'a, D>(&self, message: &'a [u8], signature: &Signature)
+            -> Result<&'a [u8], SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        self.verify_with_dom2::<D>(None, &[message], signature)?;
+        Ok(message)
+    }
+
+    /// Verify a signature on a message with this public key, returning the
+    /// message wrapped in a `VerifiedMessage` as proof that it was verified.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(VerifiedMessage)` if the signature is valid, and otherwise
+    /// an error as for `verify()`.
+    pub fn verify_and_extract<'a, D>(&self, message: &'a [u8], signature: &Signature)
+            -> Result<VerifiedMessage<'a>, SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+        self.verify_returning_message::<D>(message, signature).map(VerifiedMessage)
+    }
+
+    /// Verify an Ed25519ctx `signature`, as specified in RFC8032, on a
+    /// `message` with the given `context`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified,
+    /// `Err(SignatureError::InvalidInput)` if the `context` is longer than
+    /// `MAX_CONTEXT_LENGTH` bytes, and otherwise an error as for `verify()`.
+    pub fn verify_with_context<D>(&self, message: &[u8], context: &[u8], signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        if context.len() > MAX_CONTEXT_LENGTH {
+            return Err(SignatureError::InvalidInput);
+        }
+        self.verify_with_dom2::<D>(Some((0, context)), &[message], signature)
+    }
+
+    /// Verify an Ed25519ph `signature`, as specified in RFC8032, on the
+    /// message whose digest is `prehash`, with an optional `context` of at
+    /// most `MAX_CONTEXT_LENGTH` bytes.  `D` must be the hash function used
+    /// to make the `prehash`; for Ed25519ph proper it is SHA-512.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified,
+    /// `Err(SignatureError::InvalidInput)` if the `context` is longer than
+    /// `MAX_CONTEXT_LENGTH` bytes, and otherwise an error as for `verify()`.
+    pub fn verify_prehashed<D>(&self, prehash: D, context: Option<&[u8]>, signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let context: &[u8] = context.unwrap_or(&[]);
+
+        if context.len() > MAX_CONTEXT_LENGTH {
+            return Err(SignatureError::InvalidInput);
+        }
+        self.verify_with_dom2::<D>(Some((1, context)), &[&prehash.fixed_result()], signature)
     }
 }
 
+/// A message whose signature has been verified.
+///
+/// A `VerifiedMessage` can only be made by `PublicKey::verify_and_extract()`,
+/// so a function which takes one as an argument cannot be called with a
+/// message which has not been through verification.  It does not record
+/// *which* public key verified it; where that matters, keep the two
+/// together.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VerifiedMessage<'a>(&'a [u8]);
+
+impl<'a> VerifiedMessage<'a> {
+    /// The message.
+    pub fn message(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+/// The minimum wall-clock duration of a call to
+/// `PublicKey::verify_constant_time()`.
+#[cfg(feature = "std")]
+pub const CONSTANT_TIME_VERIFY_DURATION: Duration = Duration::from_millis(1);
+
 #[cfg(feature = "serde")]
 impl Serialize for PublicKey {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
@@ -760,6 +1637,34 @@ pub struct Keypair {
     pub public: PublicKey,
 }
 
+impl<'a> TryFrom<&'a [u8]> for Keypair {
+    type Error = &'static str;
+
+    /// Construct a `Keypair` from a slice of exactly `KEYPAIR_LENGTH` bytes.
+    fn try_from(bytes: &'a [u8]) -> Result<Keypair, &'static str> {
+        if bytes.len() != KEYPAIR_LENGTH {
+            return Err("Wrong length of bytes for creating keypair!");
+        }
+        Keypair::try_from(*array_ref!(bytes, 0, KEYPAIR_LENGTH))
+    }
+}
+
+impl TryFrom<[u8; KEYPAIR_LENGTH]> for Keypair {
+    type Error = &'static str;
+
+    /// Construct a `Keypair` from the bytes `secret || public`.
+    ///
+    /// As with `Keypair::from_bytes()`, the public half is rejected if it is
+    /// a point of small order, but the two halves are not checked against
+    /// each other.
+    fn try_from(bytes: [u8; KEYPAIR_LENGTH]) -> Result<Keypair, &'static str> {
+        let secret: SecretKey = SecretKey::from(*array_ref!(bytes, 0, SECRET_KEY_LENGTH));
+        let public: PublicKey = PublicKey::try_from(*array_ref!(bytes, SECRET_KEY_LENGTH, PUBLIC_KEY_LENGTH))?;
+
+        Ok(Keypair{ secret, public })
+    }
+}
+
 impl Keypair {
     /// Convert this keypair to bytes.
     ///
@@ -770,7 +1675,7 @@ impl Keypair {
     /// `PUBLIC_KEY_LENGTH` bytes is the `PublicKey` (the same as other
     /// libraries, such as [Adam Langley's ed25519 Golang
     /// implementation](https://github.com/agl/ed25519/)).
-    pub fn to_bytes(&self) -> [u8; KEYPAIR_LENGTH] {
+    pub fn to_raw_bytes(&self) -> [u8; KEYPAIR_LENGTH] {
         let mut bytes: [u8; KEYPAIR_LENGTH] = [0u8; KEYPAIR_LENGTH];
 
         bytes[..SECRET_KEY_LENGTH].copy_from_slice(self.secret.as_bytes());
@@ -778,33 +1683,47 @@ impl Keypair {
         bytes
     }
 
+    /// Convert this keypair to bytes, as `secret || public`.
+    ///
+    /// This is the same as `to_raw_bytes()`, whose name makes it clear that
+    /// both halves of the keypair are returned.
+    #[deprecated(since = "0.6.0", note = "use `Keypair::to_raw_bytes()`, which is the same")]
+    pub fn to_bytes(&self) -> [u8; KEYPAIR_LENGTH] {
+        self.to_raw_bytes()
+    }
+
     /// Construct a `Keypair` from the bytes of a `PublicKey` and `SecretKey`.
     ///
     /// # Inputs
     ///
     /// * `bytes`: an `&[u8]` representing the scalar for the secret key, and a
     ///   compressed Edwards-Y coordinate of a point on curve25519, both as bytes.
-    ///   (As obtained from `Keypair::to_bytes()`.)
+    ///   (As obtained from `Keypair::to_raw_bytes()`.)
     ///
     /// # Warning
     ///
-    /// Absolutely no validation is done on the key.  If you give this function
-    /// bytes which do not represent a valid point, or which do not represent
-    /// corresponding parts of the key, then your `Keypair` will be broken and
-    /// it will be your fault.
+    /// The public half is validated as by `PublicKey::from_bytes()`, so a
+    /// point of small order is rejected, but the halves are not checked
+    /// against each other.  If you give this function bytes which do not
+    /// represent corresponding parts of the key, then your `Keypair` will be
+    /// broken and it will be your fault; use
+    /// `Keypair::verify_own_public_key()` to check.
     ///
     /// # Returns
     ///
     /// A `Result` whose okay value is an EdDSA `Keypair` or whose error value
     /// is an `&'static str` describing the error that occurred.
     pub fn from_bytes<'a>(bytes: &'a [u8]) -> Result<Keypair, &'static str> {
-        if bytes.len() != KEYPAIR_LENGTH {
-            return Err("Wrong length of bytes for creating keypair!");
-        }
-        let secret = SecretKey::from_bytes(&bytes[..SECRET_KEY_LENGTH])?;
-        let public = PublicKey::from_bytes(&bytes[SECRET_KEY_LENGTH..])?;
+        Keypair::try_from(bytes)
+    }
 
-        Ok(Keypair{ secret: secret, public: public })
+    /// Construct a `Keypair` from the bytes `secret || public`, as obtained
+    /// from `Keypair::to_raw_bytes()`.
+    ///
+    /// This is the same as `from_bytes()`, and the same warning applies: the
+    /// two halves are not checked against each other.
+    pub fn from_raw_bytes(bytes: &[u8]) -> Result<Keypair, &'static str> {
+        Keypair::from_bytes(bytes)
     }
 
     /// Generate an ed25519 keypair.
@@ -849,22 +1768,417 @@ impl Keypair {
         Keypair{ public: pk, secret: sk }
     }
 
+    /// Derive an ed25519 keypair from `entropy`, such as the output of a
+    /// hardware random number generator, of any length of at least 32 bytes.
+    ///
+    /// The secret key is the first 32 bytes of the hash of the `entropy`
+    /// with `D`, which should be SHA-512, so that all of the `entropy` is
+    /// used, however long it is.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    /// `SignatureError::InvalidKeyLength` if there are fewer than 32 bytes of
+    /// `entropy`.
+    #[cfg(feature = "std")]
+    pub fn from_entropy<D>(entropy: &[u8]) -> Result<Keypair, SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        if entropy.len() < SECRET_KEY_LENGTH {
+            return Err(SignatureError::InvalidKeyLength);
+        }
+        let mut h: D = D::default();
+
+        h.input(entropy);
+
+        let hash = h.fixed_result();
+        let secret: SecretKey = SecretKey(*array_ref!(hash, 0, SECRET_KEY_LENGTH));
+        let public: PublicKey = PublicKey::from_secret::<D>(&secret);
+
+        Ok(Keypair{ secret, public })
+    }
+
+    /// Deterministically derive an ed25519 keypair from a 32-byte `seed`, as
+    /// in RFC8032 §5.1.5, using the hash function `D` to compute the public
+    /// key.  RFC8032 specifies SHA-512.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "sha2")]
+    /// # extern crate sha2;
+    /// # #[cfg(feature = "sha2")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    /// use sha2::Sha512;
+    ///
+    /// let seed: [u8; 32] = [0u8; 32];
+    /// let keypair: Keypair = Keypair::from_seed::<Sha512>(&seed);
+    ///
+    /// assert_eq!(keypair.secret.to_bytes(), seed);
+    /// # }
+    /// # #[cfg(not(feature = "sha2"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_seed<D>(seed: &[u8; SECRET_KEY_LENGTH]) -> Keypair
+            where D: Digest<OutputSize = U64> + Default {
+        let sk: SecretKey = SecretKey::from_seed(seed);
+        let pk: PublicKey = PublicKey::from_secret::<D>(&sk);
+
+        Keypair{ public: pk, secret: sk }
+    }
+
+    /// Generate a fresh ed25519 keypair from the operating system's random
+    /// number generator, using SHA-512.
+    ///
+    /// This is `Keypair::generate::<Sha512>()` with a `rand::OsRng`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(all(feature = "std", feature = "sha2"))]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// let keypair: Keypair = Keypair::from_os_entropy().unwrap();
+    /// # }
+    /// # #[cfg(not(all(feature = "std", feature = "sha2")))]
+    /// # fn main() { }
+    /// ```
+    ///
+    /// # Return
+    ///
+    /// Returns the `Keypair`, or `Err(SignatureError::EntropyError)` if the
+    /// operating system's random number generator could not be used.
+    #[cfg(all(feature = "std", feature = "sha2"))]
+    pub fn from_os_entropy() -> Result<Keypair, SignatureError> {
+        let mut csprng: ::rand::OsRng = ::rand::OsRng::new().or(Err(SignatureError::EntropyError))?;
+
+        Ok(Keypair::generate::<Sha512>(&mut csprng))
+    }
+
     /// Sign a message with this keypair's secret key.
     pub fn sign<D>(&self, message: &[u8]) -> Signature where D: Digest<OutputSize = U64> + Default {
         self.secret.expand::<D>().sign::<D>(&message, &self.public)
     }
 
-    /// Verify a signature on a message with this keypair's public key.
-    pub fn verify<D>(&self, message: &[u8], signature: &Signature) -> bool
+    /// Sign a fixed-length message, such as a 32-byte hash, with this
+    /// keypair's secret key.  This is the same as `sign()` on the slice.
+    pub fn sign_array<D, const N: usize>(&self, message: &[u8; N]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+        self.sign::<D>(&message[..])
+    }
+
+    /// Sign a message with this keypair's secret key, hedging the nonce with
+    /// 32 bytes of randomness from the `csprng`.
+    ///
+    /// `sign()` derives the nonce only from the secret key and the message,
+    /// so signing the same message twice repeats the same computation.  An
+    /// attacker who can induce a fault in one of those computations, and see
+    /// both signatures, can then recover the secret key.  Here the nonce is
+    /// instead derived from the secret nonce prefix, then the randomness, then
+    /// the message, so that each signature uses a fresh nonce.  The nonce stays
+    /// secret even if the `csprng` is weak, since it still depends on the
+    /// secret key, and with a fixed `csprng` signing is deterministic.
+    ///
+    /// The result is an ordinary signature, which verifies with
+    /// `PublicKey::verify()`.
+    #[cfg(feature = "std")]
+    pub fn sign_with_rng<D>(&self, message: &[u8], csprng: &mut dyn Rng) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut noise: [u8; 32] = [0u8; 32];
+
+        csprng.fill_bytes(&mut noise);
+        self.secret.expand::<D>().sign_with_dom2_and_noise::<D>(None, &noise, &[message], &self.public)
+    }
+
+    /// Sign a `message` with Ed25519ctx, as specified in RFC8032, mixing the
+    /// application-specific `context` into both hashes.
+    ///
+    /// A signature made in one context is not valid in any other, nor as a
+    /// plain Ed25519 signature.  Verify it with
+    /// `PublicKey::verify_with_context()`.
+    ///
+    /// # Return
+    ///
+    /// Returns the `Signature`, or `Err(SignatureError::InvalidInput)` if the
+    /// `context` is longer than `MAX_CONTEXT_LENGTH` bytes.
+    pub fn sign_with_context<D>(&self, message: &[u8], context: &[u8])
+            -> Result<Signature, SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        if context.len() > MAX_CONTEXT_LENGTH {
+            return Err(SignatureError::InvalidInput);
+        }
+        Ok(self.secret.expand::<D>().sign_with_dom2::<D>(Some((0, context)), &[message],
+                                                         &self.public))
+    }
+
+    /// Sign the message whose digest is `prehash` with Ed25519ph, as
+    /// specified in RFC8032, with an optional `context`.
+    ///
+    /// `D` is used both for the `prehash` and within the signature itself;
+    /// for Ed25519ph proper it is SHA-512.  The flag byte in `dom2` ensures
+    /// that the signature is not also a valid Ed25519 or Ed25519ctx signature
+    /// on the same bytes.  Verify it with `PublicKey::verify_prehashed()`.
+    ///
+    /// # Return
+    ///
+    /// Returns the `Signature`, or `Err(SignatureError::InvalidInput)` if the
+    /// `context` is longer than `MAX_CONTEXT_LENGTH` bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # extern crate sha2;
+    /// # extern crate rand;
+    /// # #[cfg(feature = "std")]
+    /// # fn main() {
+    /// use ed25519_dalek::{Keypair, Signature};
+    /// use rand::OsRng;
+    /// use sha2::{Digest, Sha512};
+    ///
+    /// let mut csprng: OsRng = OsRng::new().unwrap();
+    /// let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+    /// let mut prehash: Sha512 = Sha512::default();
+    ///
+    /// prehash.input(b"firmware image");
+    ///
+    /// let signature: Signature = keypair.sign_prehashed(prehash.clone(), Some(b"v1")).unwrap();
+    ///
+    /// assert!(keypair.public.verify_prehashed(prehash, Some(b"v1"), &signature).is_ok());
+    /// # }
+    /// # #[cfg(not(feature = "std"))]
+    /// # fn main() { }
+    /// ```
+    pub fn sign_prehashed<D>(&self, prehash: D, context: Option<&[u8]>)
+            -> Result<Signature, SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let context: &[u8] = context.unwrap_or(&[]);
+
+        if context.len() > MAX_CONTEXT_LENGTH {
+            return Err(SignatureError::InvalidInput);
+        }
+        Ok(self.secret.expand::<D>().sign_with_dom2::<D>(Some((1, context)),
+                                                         &[&prehash.fixed_result()], &self.public))
+    }
+
+    /// Verify a signature on a message with this keypair's public key, as
+    /// for `PublicKey::verify()`.
+    pub fn verify<D>(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError>
             where D: FixedOutput<OutputSize = U64> + BlockInput + Default + Input {
         self.public.verify::<D>(message, signature)
     }
+
+    /// Sign a message with this keypair's secret key, giving a signature in
+    /// its canonical representation, with `s` fully reduced modulo ℓ.
+    ///
+    /// Every signature made by this crate is already canonical, so this
+    /// returns exactly what `sign()` does; it exists so that code written
+    /// for protocols which demand canonical signatures can say so.
+    ///
+    /// Unlike ECDSA, ed25519 has no "low-S" form.  The only other encodings
+    /// of a valid signature are `(R, s + k·ℓ)`, which represent the same
+    /// value.  Replacing `s` with `ℓ - s` and `R` with `-R` does *not* give
+    /// another valid signature, since `R` is hashed into the challenge, so
+    /// no such adjustment is made.  See `Signature::has_canonical_s()`.
+    pub fn sign_canonical<D>(&self, message: &[u8]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let signature: Signature = self.sign::<D>(message);
+
+        debug_assert!(signature.has_canonical_s());
+        signature
+    }
+
+    /// Check that this keypair's public key is the one derived from its
+    /// secret key, using the hash function `D`, such as after loading the
+    /// keypair from storage.
+    ///
+    /// This costs a scalar multiplication, so call it once when the keypair
+    /// is loaded, not before every signature.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the public key matches, and
+    /// `Err(SignatureError::InconsistentKeypair)` otherwise.
+    pub fn verify_own_public_key<D>(&self) -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let expected: CompressedEdwardsY =
+            (&self.secret.expand::<D>().key * &constants::ED25519_BASEPOINT_TABLE).compress();
+
+        if slices_equal(expected.as_bytes(), self.public.as_bytes()) == 1 {
+            Ok(())
+        } else {
+            Err(SignatureError::InconsistentKeypair)
+        }
+    }
+
+    /// Sign a message which is only valid for the given `epoch`, such as a
+    /// voting round.
+    ///
+    /// The message actually signed is the `epoch`, as eight big-endian
+    /// bytes, followed by the `message`.  Verify it with
+    /// `PublicKey::verify_for_epoch()`.
+    pub fn sign_for_epoch<D>(&self, message: &[u8], epoch: u64) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+        self.secret.expand::<D>()
+            .sign_with_dom2::<D>(None, &[&epoch.to_be_bytes(), message], &self.public)
+    }
+
+    /// Sign a message together with some auxiliary `binding` data, such as a
+    /// TLS channel binding or a handshake transcript.
+    ///
+    /// The message actually signed is the length of the `binding`, as eight
+    /// little-endian bytes, followed by the `binding`, then the `message`.
+    /// The length prefix ensures that no part of the binding can be passed
+    /// off as part of the message, or vice versa.  Verify it with
+    /// `PublicKey::verify_with_binding()`.
+    pub fn sign_with_binding<D>(&self, message: &[u8], binding: &[u8]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let length: [u8; 8] = (binding.len() as u64).to_le_bytes();
+
+        self.secret.expand::<D>().sign_with_dom2::<D>(None, &[&length, binding, message],
+                                                      &self.public)
+    }
+
+    /// Derive a signing `Keypair` from an X25519 Diffie-Hellman secret key.
+    ///
+    /// The ed25519 secret key is the first 32 bytes of
+    /// `SHA-512("ed25519-dalek-from-x25519" || x25519_secret)`, and the public
+    /// key is derived from it as usual.
+    ///
+    /// # Warning
+    ///
+    /// **This ties two keys which ought to be independent to one another.**
+    /// Anyone who learns the X25519 secret key can also forge signatures, and
+    /// many protocols (and their security proofs) assume that encryption and
+    /// signing keys are separate.  Only use this if you are certain that your
+    /// protocol tolerates it; otherwise, generate a separate `Keypair`.
+    #[cfg(feature = "sha2")]
+    pub fn from_x25519_secret(x25519_secret: &[u8; 32]) -> Keypair {
+        let mut h: Sha512 = Sha512::default();
+        let mut hash: [u8; 64] = [0u8; 64];
+
+        h.input(b"ed25519-dalek-from-x25519");
+        h.input(x25519_secret);
+        hash.copy_from_slice(h.fixed_result().as_slice());
+
+        let secret: SecretKey = SecretKey(*array_ref!(&hash, 0, SECRET_KEY_LENGTH));
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Keypair{ secret, public }
+    }
+
+    /// Derive a child `Keypair` from a `root_keypair` and its `chain_code`,
+    /// following a BIP-44 style derivation `path`, such as `"m/44'/501'/0'"`,
+    /// using the ed25519 derivation of SLIP-0010.
+    ///
+    /// The path must begin with `m`, followed by any number of `/`-separated
+    /// indices, each less than 2³¹.  SLIP-0010 defines only hardened
+    /// derivation for ed25519, so every index must be marked as hardened
+    /// with a trailing `'`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the child `Keypair` and its chain code,
+    /// or whose error value is `SignatureError::InvalidInput` if the path is
+    /// malformed, or contains an index which is not hardened.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate sha2;
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(all(feature = "hkdf", feature = "sha2", feature = "std"))]
+    /// # fn main() {
+    /// use ed25519_dalek::{Keypair, SignatureError};
+    /// # use ed25519_dalek::{PublicKey, SecretKey};
+    /// # let secret = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+    /// # let public = PublicKey::from_secret::<sha2::Sha512>(&secret);
+    /// # let root = Keypair{ secret: secret, public: public };
+    /// # let chain_code = [0u8; 32];
+    ///
+    /// let (child, _chain_code) = Keypair::derive_from_path(&root, &chain_code,
+    ///                                                      "m/44'/501'/0'/0'").unwrap();
+    ///
+    /// assert_eq!(Keypair::derive_from_path(&root, &chain_code, "m/44'/501'/0'/0").unwrap_err(),
+    ///            SignatureError::InvalidInput);
+    /// # }
+    /// # #[cfg(not(all(feature = "hkdf", feature = "sha2", feature = "std")))]
+    /// # fn main() { }
+    /// ```
+    #[cfg(all(feature = "hkdf", feature = "sha2", feature = "std"))]
+    pub fn derive_from_path(root_keypair: &Keypair, chain_code: &[u8; 32], path: &str)
+            -> Result<(Keypair, [u8; 32]), SignatureError> {
+
+        let mut components = path.split('/');
+        let mut secret: SecretKey = SecretKey(root_keypair.secret.to_bytes());
+        let mut chain_code: [u8; 32] = *chain_code;
+
+        if components.next() != Some("m") {
+            return Err(SignatureError::InvalidInput);
+        }
+        for component in components {
+            if !component.ends_with('\'') {
+                return Err(SignatureError::InvalidInput);
+            }
+            let digits: &str = &component[..component.len() - 1];
+
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(SignatureError::InvalidInput);
+            }
+            let index: u32 = match digits.parse::<u32>() {
+                Ok(index) if index < 1 << 31 => index | 1 << 31,
+                _                            => return Err(SignatureError::InvalidInput),
+            };
+            let i = hmac::hmac::<Sha512>(&chain_code, &[&[0u8], secret.as_bytes(),
+                                                        &index.to_be_bytes()]);
+
+            secret.0.copy_from_slice(&i[..32]);
+            chain_code.copy_from_slice(&i[32..]);
+        }
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Ok((Keypair{ secret, public }, chain_code))
+    }
+
+    /// Derive the child `Keypair` labelled `info` from this keypair, using
+    /// HKDF with the hash function `D`.
+    ///
+    /// The child's seed is the 32-byte output of HKDF, with this keypair's
+    /// `ExpandedSecretKey` as the input keying material, no salt, and `info`
+    /// as the `info`, and the child is `Keypair::from_seed()` of that seed.
+    /// Children with different `info` are therefore independent keys, and
+    /// none of them reveals anything about this keypair or its siblings.
+    ///
+    /// # Warning
+    ///
+    /// This is not BIP-32, nor SLIP-0010 (for which see
+    /// `Keypair::derive_from_path()`): children derived here cannot be
+    /// derived by wallets, and public keys cannot be derived from public
+    /// keys.  It suits servers which need many keys but want to store one.
+    #[cfg(feature = "hkdf")]
+    pub fn derive_child<D>(&self, info: &[u8]) -> Keypair where D: Digest<OutputSize = U64> + Default {
+        let prk = hmac::hkdf_extract::<D>(&[], &self.secret.expand::<D>().to_bytes());
+        let mut seed: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+
+        hmac::hkdf_expand::<D>(&prk, info, &mut seed);
+        Keypair::from_seed::<D>(&seed)
+    }
 }
 
 #[cfg(feature = "serde")]
 impl Serialize for Keypair {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        serializer.serialize_bytes(&self.to_bytes()[..])
+        serializer.serialize_bytes(&self.to_raw_bytes()[..])
     }
 }
 
@@ -882,20 +2196,59 @@ impl<'d> Deserialize<'d> for Keypair {
             }
 
             fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Keypair, E> where E: SerdeError {
-                let secret_key = SecretKey::from_bytes(&bytes[..SECRET_KEY_LENGTH]);
-                let public_key = PublicKey::from_bytes(&bytes[SECRET_KEY_LENGTH..]);
-
-                if secret_key.is_ok() && public_key.is_ok() {
-                    Ok(Keypair{ secret: secret_key.unwrap(), public: public_key.unwrap() })
-                } else {
-                    Err(SerdeError::invalid_length(bytes.len(), &self))
-                }
+                Keypair::from_bytes(bytes).or(Err(SerdeError::invalid_length(bytes.len(), &self)))
             }
         }
         deserializer.deserialize_bytes(KeypairVisitor)
     }
 }
 
+/// Deterministically derive a `Keypair` for use in tests from a string `label`.
+///
+/// The secret key is the first 32 bytes of `SHA-512(b"test-keypair-v1" ||
+/// label)`, so the same label always produces the same keypair, on every
+/// platform and with every version of this crate.  This allows test suites to
+/// share a single canonical source of test keys, rather than scattering copies
+/// of hard-coded key bytes around.
+///
+/// # Warning
+///
+/// Anyone who knows the label knows the secret key.  Never use these keys for
+/// anything other than testing.
+///
+/// # Example
+///
+/// ```
+/// # extern crate ed25519_dalek;
+/// # #[cfg(feature = "testing")]
+/// # fn main() {
+/// use ed25519_dalek::{Keypair, keypair_for_testing};
+///
+/// let alice: Keypair = keypair_for_testing("alice");
+/// let alice_again: Keypair = keypair_for_testing("alice");
+///
+/// assert_eq!(alice.public, alice_again.public);
+/// # }
+/// # #[cfg(not(feature = "testing"))]
+/// # fn main() { }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+pub fn keypair_for_testing(label: &str) -> Keypair {
+    use sha2::Sha512;
+
+    let mut h: Sha512 = Sha512::default();
+    let mut hash: [u8; 64] = [0u8; 64];
+
+    h.input(b"test-keypair-v1");
+    h.input(label.as_bytes());
+    hash.copy_from_slice(h.fixed_result().as_slice());
+
+    let secret: SecretKey = SecretKey(*array_ref!(&hash, 0, SECRET_KEY_LENGTH));
+    let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+    Keypair{ secret, public }
+}
+
 #[cfg(test)]
 mod test {
     use std::io::BufReader;
@@ -975,14 +2328,525 @@ mod test {
         good_sig = keypair.sign::<Sha512>(&good);
         bad_sig  = keypair.sign::<Sha512>(&bad);
 
-        assert!(keypair.verify::<Sha512>(&good, &good_sig) == true,
+        assert!(keypair.verify::<Sha512>(good, &good_sig).is_ok(),
                 "Verification of a valid signature failed!");
-        assert!(keypair.verify::<Sha512>(&good, &bad_sig)  == false,
+        assert!(keypair.verify::<Sha512>(good, &bad_sig).is_err(),
                 "Verification of a signature on a different message passed!");
-        assert!(keypair.verify::<Sha512>(&bad,  &good_sig) == false,
+        assert!(keypair.verify::<Sha512>(bad,  &good_sig).is_err(),
                 "Verification of a signature on a different message passed!");
     }
 
+    #[test]
+    fn xor_with_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("xor_with");
+        let message: &[u8] = "test message".as_bytes();
+        let signature: Signature = keypair.sign::<Sha512>(message);
+        let pad: [u8; SIGNATURE_LENGTH] = keypair_for_testing("pad").to_raw_bytes();
+
+        let masked: [u8; SIGNATURE_LENGTH] = signature.xor_with(&pad);
+        assert!(masked[..] != signature.to_bytes()[..]);
+
+        let unmasked: Signature = Signature::from_xored(&masked, &pad);
+        assert_eq!(unmasked, signature);
+        assert!(keypair.verify::<Sha512>(message, &unmasked).is_ok());
+    }
+
+    #[test]
+    fn sign_for_epoch_is_bound_to_epoch() {
+        let keypair: Keypair = keypair_for_testing("epoch");
+        let signature: Signature = keypair.sign_for_epoch::<Sha512>(b"test message", 5);
+        let mut signed: [u8; 20] = [0u8; 20];
+
+        signed[7] = 5;
+        signed[8..].copy_from_slice(b"test message");
+
+        assert_eq!(keypair.public.verify_for_epoch::<Sha512>(b"test message", &signature, 5),
+                   Ok(()));
+        assert_eq!(keypair.public.verify_for_epoch::<Sha512>(b"test message", &signature, 6),
+                   Err(SignatureError::InvalidSignature));
+        assert!(keypair.verify::<Sha512>(&signed, &signature).is_ok());
+    }
+
+    #[test]
+    fn sign_with_binding_is_bound_to_binding() {
+        let keypair: Keypair = keypair_for_testing("binding");
+        let signature: Signature = keypair.sign_with_binding::<Sha512>(b"message", b"transcript");
+
+        assert_eq!(keypair.public.verify_with_binding::<Sha512>(b"message", b"transcript",
+                                                                &signature), Ok(()));
+        assert_eq!(keypair.public.verify_with_binding::<Sha512>(b"message", b"transcripT",
+                                                                &signature),
+                   Err(SignatureError::InvalidSignature));
+        // Moving bytes between the binding and the message is detected.
+        assert_eq!(keypair.public.verify_with_binding::<Sha512>(b"tmessage", b"transcrip",
+                                                                &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn verify_returning_message_returns_message() {
+        let keypair: Keypair = keypair_for_testing("returning");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+
+        assert_eq!(keypair.public.verify_returning_message::<Sha512>(b"test message", &signature),
+                   Ok(&b"test message"[..]));
+        assert_eq!(keypair.public.verify_returning_message::<Sha512>(b"test massage", &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn raw_bytes_are_the_same_as_bytes() {
+        let keypair: Keypair = keypair_for_testing("raw_bytes");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+
+        assert_eq!(&keypair.to_raw_bytes()[..], &keypair.to_bytes()[..]);
+        assert_eq!(Keypair::from_raw_bytes(&keypair.to_raw_bytes()).unwrap().public,
+                   keypair.public);
+        assert_eq!(keypair.public.to_raw_bytes(), keypair.public.to_bytes());
+        assert_eq!(&signature.to_raw_bytes()[..], &signature.to_bytes()[..]);
+    }
+
+    #[test]
+    fn verify_and_extract_wraps_message() {
+        let keypair: Keypair = keypair_for_testing("extract");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let verified: VerifiedMessage = keypair.public
+            .verify_and_extract::<Sha512>(b"test message", &signature).unwrap();
+
+        assert_eq!(verified.message(), b"test message");
+        assert_eq!(keypair.public.verify_and_extract::<Sha512>(b"test massage", &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    // The tests foo, bar, foo2, and foo3 from RFC8032 §7.2, as (secret key,
+    // public key, message, context, signature).
+    static ED25519CTX_VECTORS: [(&str, &str, &str, &str, &str); 4] = [
+        ("0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+         "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+         "f726936d19c800494e3fdaff20b276a8", "666f6f",
+         "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a\
+          8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d"),
+        ("0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+         "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+         "f726936d19c800494e3fdaff20b276a8", "626172",
+         "fc60d5872fc46b3aa69f8b5b4351d5808f92bcc044606db097abab6dbcb1aee3\
+          216c48e8b3b66431b5b186d1d28f8ee15a5ca2df6668346291c2043d4eb3e90d"),
+        ("0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+         "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+         "508e9e6882b979fea900f62adceaca35", "666f6f",
+         "8b70c1cc8310e1de20ac53ce28ae6e7207f33c3295e03bb5c0732a1d20dc6490\
+          8922a8b052cf99b7c4fe107a5abb5b2c4085ae75890d02df26269d8945f84b0b"),
+        ("ab9c2853ce297ddab85c993b3ae14bcad39b2c682beabc27d6d4eb20711d6560",
+         "0f1d1274943b91415889152e893d80e93275a1fc0b65fd71b4b0dda10ad7d772",
+         "f726936d19c800494e3fdaff20b276a8", "666f6f",
+         "21655b5f1aa965996b3f97b3c849eafba922a0a62992f73b3d1b73106a84ad85\
+          e9b86a7b6005ea868337ff2d20a7f5fbd4cd10b0be49a68da2b2e0dc0ad8960f"),
+    ];
+
+    #[test]
+    fn expanded_secret_key_bytes_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("expanded");
+        let bytes: [u8; 64] = keypair.secret.expand::<Sha512>().to_bytes();
+        let restored: ExpandedSecretKey = ExpandedSecretKey::from_bytes(&bytes).unwrap();
+
+        assert_eq!(&restored.to_bytes()[..], &bytes[..]);
+        assert_eq!(restored.sign::<Sha512>(b"test message", &keypair.public),
+                   keypair.sign::<Sha512>(b"test message"));
+        assert!(ExpandedSecretKey::try_from(&bytes[..63]).is_err());
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn from_os_entropy_generates_distinct_keypairs() {
+        let a: Keypair = Keypair::from_os_entropy().unwrap();
+        let b: Keypair = Keypair::from_os_entropy().unwrap();
+
+        assert!(a.public != b.public);
+        assert_eq!(a.verify_own_public_key::<Sha512>(), Ok(()));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn zeroize_clears_secret_keys() {
+        let mut secret: SecretKey = keypair_for_testing("zeroize").secret;
+        let mut expanded: ExpandedSecretKey = secret.expand::<Sha512>();
+
+        secret.zeroize();
+        expanded.zeroize();
+        assert_eq!(secret.to_bytes(), [0u8; SECRET_KEY_LENGTH]);
+        assert_eq!(&expanded.to_bytes()[..], &[0u8; 64][..]);
+    }
+
+    #[test]
+    fn sign_canonical_has_canonical_s() {
+        let keypair: Keypair = keypair_for_testing("canonical");
+        let signature: Signature = keypair.sign_canonical::<Sha512>(b"test message");
+        let mut malleated: Signature = signature;
+        let mut carry: u16 = 0;
+
+        // s + l, which is accepted by verify() whenever it fits in 253 bits.
+        for i in 0..32 {
+            let sum: u16 = signature.s[i] as u16 + constants::l[i] as u16 + carry;
+            malleated.s[i] = sum as u8;
+            carry = sum >> 8;
+        }
+
+        assert_eq!(signature, keypair.sign::<Sha512>(b"test message"));
+        assert!(signature.has_canonical_s());
+        assert!(!malleated.has_canonical_s());
+    }
+
+    #[test]
+    fn verify_reports_why_it_failed() {
+        let keypair: Keypair = keypair_for_testing("verify errors");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let mut high_s: Signature = signature;
+        let not_a_point: PublicKey = (0u8..).map(|y| PublicKey(CompressedEdwardsY([y; 32])))
+            .find(|public| public.decompress().is_none()).unwrap();
+
+        high_s.s[31] |= 0x80;
+        assert_eq!(keypair.public.verify::<Sha512>(b"test message", &signature), Ok(()));
+        assert_eq!(keypair.public.verify::<Sha512>(b"test massage", &signature),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(keypair.public.verify::<Sha512>(b"test message", &high_s),
+                   Err(SignatureError::ScalarFormatError));
+        assert_eq!(not_a_point.verify::<Sha512>(b"test message", &signature),
+                   Err(SignatureError::PointDecompressionError));
+        assert!(keypair.public.is_valid_signature::<Sha512>(b"test message", &signature));
+        assert!(!keypair.public.is_valid_signature::<Sha512>(b"test massage", &signature));
+    }
+
+    #[test]
+    fn verify_own_public_key_detects_mismatch() {
+        let keypair: Keypair = keypair_for_testing("own public key");
+        let mismatched: Keypair = Keypair{ secret: keypair_for_testing("other").secret,
+                                           public: keypair.public };
+
+        assert_eq!(keypair.verify_own_public_key::<Sha512>(), Ok(()));
+        assert_eq!(mismatched.verify_own_public_key::<Sha512>(),
+                   Err(SignatureError::InconsistentKeypair));
+    }
+
+    #[test]
+    fn sign_with_context_rfc8032_vectors() {
+        for &(secret, public, message, context, expected) in ED25519CTX_VECTORS.iter() {
+            let secret_bytes: Vec<u8> = FromHex::from_hex(secret).unwrap();
+            let public_bytes: Vec<u8> = FromHex::from_hex(public).unwrap();
+            let message: Vec<u8> = FromHex::from_hex(message).unwrap();
+            let context: Vec<u8> = FromHex::from_hex(context).unwrap();
+            let expected: Vec<u8> = FromHex::from_hex(expected).unwrap();
+            let keypair: Keypair = Keypair{ secret: SecretKey::from_bytes(&secret_bytes).unwrap(),
+                                            public: PublicKey::from_bytes(&public_bytes).unwrap() };
+            let signature: Signature = keypair.sign_with_context::<Sha512>(&message, &context)
+                .unwrap();
+
+            assert_eq!(&signature.to_bytes()[..], &expected[..]);
+            assert_eq!(keypair.public.verify_with_context::<Sha512>(&message, &context, &signature),
+                       Ok(()));
+            assert!(keypair.public.verify::<Sha512>(&message, &signature).is_err());
+        }
+    }
+
+    #[test]
+    fn sign_with_context_is_bound_to_context() {
+        let keypair: Keypair = keypair_for_testing("context");
+        let signature: Signature = keypair.sign_with_context::<Sha512>(b"grant", b"auth").unwrap();
+
+        assert_eq!(keypair.public.verify_with_context::<Sha512>(b"grant", b"admin", &signature),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(keypair.sign_with_context::<Sha512>(b"grant", &[0u8; 256]),
+                   Err(SignatureError::InvalidInput));
+        assert_eq!(keypair.public.verify_with_context::<Sha512>(b"grant", &[0u8; 256], &signature),
+                   Err(SignatureError::InvalidInput));
+    }
+
+    // TEST abc from RFC8032 §7.3.
+    #[test]
+    fn sign_prehashed_rfc8032_vector() {
+        let secret_bytes: Vec<u8> = FromHex::from_hex(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42").unwrap();
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf").unwrap();
+        let expected: Vec<u8> = FromHex::from_hex(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41\
+             31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406").unwrap();
+        let secret: SecretKey = SecretKey::from_bytes(&secret_bytes).unwrap();
+        let public: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+        let keypair: Keypair = Keypair{ secret, public };
+        let mut prehash: Sha512 = Sha512::default();
+
+        prehash.input(b"abc");
+
+        let signature: Signature = keypair.sign_prehashed(prehash, None).unwrap();
+
+        assert_eq!(&signature.to_bytes()[..], &expected[..]);
+        assert_eq!(public.verify_prehashed(prehash, None, &signature), Ok(()));
+        assert_eq!(public.verify_prehashed(prehash, Some(b""), &signature), Ok(()));
+        assert!(public.verify_prehashed(prehash, Some(b"foo"), &signature).is_err());
+        assert!(public.verify::<Sha512>(&Sha512::digest(b"abc"), &signature).is_err());
+    }
+
+    #[test]
+    fn prehashed_rejects_long_context() {
+        let keypair: Keypair = keypair_for_testing("prehashed");
+        let signature: Signature = keypair.sign_prehashed(Sha512::default(), None).unwrap();
+
+        assert_eq!(keypair.sign_prehashed(Sha512::default(), Some(&[0u8; 256])).unwrap_err(),
+                   SignatureError::InvalidInput);
+        assert_eq!(keypair.public.verify_prehashed(Sha512::default(), Some(&[0u8; 256]), &signature),
+                   Err(SignatureError::InvalidInput));
+        assert!(keypair.sign_prehashed(Sha512::default(), Some(&[0u8; 255])).is_ok());
+    }
+
+    #[test]
+    fn r_commitment_matches() {
+        let keypair: Keypair = keypair_for_testing("r_commitment");
+        let good_sig: Signature = keypair.sign::<Sha512>(b"test message");
+        let bad_sig: Signature = keypair.sign::<Sha512>(b"wrong message");
+        let commitment: [u8; 32] = good_sig.r_commitment();
+
+        assert_eq!(&commitment[..], &good_sig.to_bytes()[..32]);
+        assert!(good_sig.verify_r_commitment(&commitment));
+        assert!(!bad_sig.verify_r_commitment(&commitment));
+    }
+
+    // The encodings of the eight points of small order, including the
+    // non-canonical encodings with the sign bit set on points whose x is 0.
+    static SMALL_ORDER_POINTS: [&str; 10] = [
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "0100000000000000000000000000000000000000000000000000000000000080",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa", ];
+
+    #[test]
+    fn small_order_public_keys_are_rejected() {
+        use curve25519_dalek::constants::EIGHT_TORSION;
+
+        let public: PublicKey = keypair_for_testing("is_weak").public;
+
+        assert!(!public.is_weak());
+        assert_eq!(PublicKey::from_bytes(public.as_bytes()), Ok(public));
+
+        for point in SMALL_ORDER_POINTS.iter() {
+            let bytes: Vec<u8> = FromHex::from_hex(point).unwrap();
+
+            assert!(PublicKey(CompressedEdwardsY(*array_ref!(bytes, 0, 32))).is_weak(), "{}", point);
+            assert_eq!(PublicKey::from_bytes(&bytes), Err("Public key is a point of small order!"));
+
+            let mut keypair_bytes: [u8; KEYPAIR_LENGTH] = [7u8; KEYPAIR_LENGTH];
+
+            keypair_bytes[SECRET_KEY_LENGTH..].copy_from_slice(&bytes);
+            assert!(Keypair::from_bytes(&keypair_bytes).is_err(), "{}", point);
+            assert!(Keypair::try_from(keypair_bytes).is_err(), "{}", point);
+        }
+        for point in EIGHT_TORSION.iter() {
+            assert!(PublicKey::from_bytes(point.compress().as_bytes()).is_err());
+        }
+    }
+
+    #[test]
+    fn is_torsion_free_rejects_torsion() {
+        let public: PublicKey = keypair_for_testing("is_torsion_free").public;
+        let mut order_two: [u8; 32] = [0xff; 32];
+
+        // The point (0, -1), of order two.
+        order_two[0]  = 0xec;
+        order_two[31] = 0x7f;
+
+        let t: ExtendedPoint = CompressedEdwardsY(order_two).decompress().unwrap();
+        let a: ExtendedPoint = public.decompress().unwrap();
+        let torsioned: PublicKey = PublicKey((&a + &t).compress());
+
+        assert!(public.is_torsion_free());
+        assert!(!PublicKey(CompressedEdwardsY(order_two)).is_torsion_free());
+        assert!(!torsioned.is_torsion_free());
+    }
+
+    #[test]
+    fn signature_compares_with_bytes() {
+        let signature: Signature = keypair_for_testing("ct_eq_bytes").sign::<Sha512>(b"test message");
+        let mut bytes: [u8; SIGNATURE_LENGTH] = signature.to_bytes();
+
+        assert!(signature.ct_eq_bytes(&bytes));
+        assert!(signature == bytes);
+
+        bytes[63] ^= 1;
+        assert!(!signature.ct_eq_bytes(&bytes));
+        assert!(signature != bytes);
+    }
+
+    #[test]
+    fn to_vec_matches_to_bytes() {
+        let keypair: Keypair = keypair_for_testing("to_vec");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+
+        assert_eq!(keypair.public.to_vec(), keypair.public.to_bytes().to_vec());
+        assert_eq!(signature.to_vec(), signature.to_bytes().to_vec());
+    }
+
+    #[test]
+    fn keys_and_signatures_in_collections() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let keypairs: Vec<Keypair> = ["a", "b", "c"].iter().map(|label| keypair_for_testing(label)).collect();
+        let publics: Vec<PublicKey> = keypairs.iter().map(|keypair| keypair.public).collect();
+        let signatures: Vec<Signature> = keypairs.iter().map(|keypair| keypair.sign::<Sha512>(b"")).collect();
+        let hashed: HashSet<PublicKey> = publics.iter().chain(publics.iter()).cloned().collect();
+        let ordered: BTreeSet<PublicKey> = publics.iter().rev().cloned().collect();
+        let signature_set: HashSet<Signature> = signatures.iter().chain(signatures.iter()).cloned().collect();
+        let mut sorted: Vec<PublicKey> = publics.clone();
+
+        sorted.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+        assert_eq!(hashed.len(), 3);
+        assert!(publics.iter().all(|public| hashed.contains(public)));
+        assert_eq!(ordered.into_iter().collect::<Vec<PublicKey>>(), sorted);
+        assert_eq!(signature_set.len(), 3);
+        assert!(signatures.iter().all(|signature| signature_set.contains(signature)));
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn verify_debug_reports_the_key_and_signature() {
+        use std::string::ToString;
+        use sha2::Sha256;
+
+        let keypair: Keypair = keypair_for_testing("verify_debug");
+        let signature: Signature = keypair_for_testing("other").sign::<Sha512>(b"test message");
+        let error: SignatureMismatchError = keypair.public
+            .verify_debug::<Sha512>(b"test message", &signature).unwrap_err();
+
+        assert_eq!(keypair.public.verify_debug::<Sha512>(b"test message",
+                                                         &keypair.sign::<Sha512>(b"test message")),
+                   Ok(()));
+        assert_eq!(error.expected_key_id, keypair.public.key_id());
+        assert_eq!(&error.expected_key_id[..], &Sha256::digest(keypair.public.as_bytes())[..8]);
+        assert_eq!(&error.signature_bytes[..], &signature.to_bytes()[..]);
+        assert_eq!(SignatureError::from(error), SignatureError::InvalidSignature);
+        assert!(error.to_string().contains(&signature.to_string()));
+    }
+
+    #[test]
+    fn verify_strict_rejects_malleable_signatures() {
+        use curve25519_dalek::edwards::Identity;
+
+        let keypair: Keypair = keypair_for_testing("verify_strict");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let mut s_plus_l: Signature = signature;
+        let mut non_canonical_r: Signature = signature;
+        let mut small_order_r: Signature = signature;
+
+        // Add ℓ to s without reducing, so that the equation still holds.
+        let mut carry: u16 = 0;
+        for i in 0..32 {
+            carry += signature.s.0[i] as u16 + constants::l.0[i] as u16;
+            s_plus_l.s.0[i] = carry as u8;
+            carry >>= 8;
+        }
+        // The point (0, 1) with its sign bit set, which decompresses as (0, 1).
+        non_canonical_r.r = CompressedEdwardsY([0u8; 32]);
+        non_canonical_r.r.0[0] = 1;
+        non_canonical_r.r.0[31] = 0x80;
+        small_order_r.r = ExtendedPoint::identity().compress();
+
+        assert_eq!(keypair.public.verify_strict::<Sha512>(b"test message", &signature), Ok(()));
+        assert_eq!(keypair.public.verify_strict::<Sha512>(b"test massage", &signature),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(keypair.public.verify_strict::<Sha512>(b"test message", &s_plus_l),
+                   Err(SignatureError::ScalarFormatError));
+        assert_eq!(keypair.public.verify_strict::<Sha512>(b"test message", &non_canonical_r),
+                   Err(SignatureError::InvalidEncoding));
+        assert_eq!(keypair.public.verify_strict::<Sha512>(b"test message", &small_order_r),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn validate_signature_s_checks_range() {
+        let signature: Signature = keypair_for_testing("validate_signature_s").sign::<Sha512>(b"");
+        let mut bytes: [u8; SIGNATURE_LENGTH] = signature.to_bytes();
+
+        assert_eq!(validate_signature_s(&bytes), Ok(()));
+
+        bytes[32..].copy_from_slice(&constants::l.0);
+        assert_eq!(validate_signature_s(&bytes), Err(SignatureError::ScalarFormatError));
+        bytes[32] -= 1;
+        assert_eq!(validate_signature_s(&bytes), Ok(()));
+        bytes[63] = 0x80;
+        assert_eq!(validate_signature_s(&bytes), Err(SignatureError::ScalarFormatError));
+    }
+
+    #[test]
+    fn verify_strict_rejects_torsioned_public_keys() {
+        let keypair: Keypair = keypair_for_testing("verify_strict");
+        let expanded: ExpandedSecretKey = keypair.secret.expand::<Sha512>();
+        let mut order_two: [u8; 32] = [0xff; 32];
+
+        // The point (0, -1), of order two.
+        order_two[0]  = 0xec;
+        order_two[31] = 0x7f;
+
+        let t: ExtendedPoint = CompressedEdwardsY(order_two).decompress().unwrap();
+        let a: ExtendedPoint = keypair.public.decompress().unwrap();
+        let torsioned: PublicKey = PublicKey((&a + &t).compress());
+
+        // Half of all challenges kill the torsion component, so that
+        // `verify()` accepts the signature.
+        let (message, signature): ([u8; 1], Signature) = (0u8..).map(|i| [i])
+            .map(|m| (m, expanded.sign_with_dom2_and_noise::<Sha512>(None, &[], &[&m], &torsioned)))
+            .find(|&(m, sig)| torsioned.verify::<Sha512>(&m, &sig).is_ok()).unwrap();
+
+        assert_eq!(torsioned.verify_strict::<Sha512>(&message, &signature),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(::batch::try_verify_batch::<Sha512>(&[&message], &[signature], &[torsioned]),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn negate_is_an_involution() {
+        use curve25519_dalek::edwards::IsIdentity;
+
+        let public: PublicKey = keypair_for_testing("negate").public;
+        let negated: PublicKey = public.negate();
+        let sum: ExtendedPoint = &public.decompress().unwrap() + &negated.decompress().unwrap();
+        let mut order_two: [u8; 32] = [0xff; 32];
+
+        order_two[0]  = 0xec;
+        order_two[31] = 0x7f;
+
+        assert!(negated != public);
+        assert_eq!(negated.as_bytes()[31] ^ public.as_bytes()[31], 0x80);
+        assert_eq!(negated.negate(), public);
+        assert!(sum.is_identity());
+        assert_eq!(PublicKey(CompressedEdwardsY(order_two)).negate().to_bytes(), order_two);
+    }
+
+    #[test]
+    fn verify_constant_time_is_padded() {
+        use std::time::Instant;
+
+        let keypair: Keypair = keypair_for_testing("verify_constant_time");
+        let good: &[u8] = "test message".as_bytes();
+        let good_sig: Signature = keypair.sign::<Sha512>(good);
+        let mut bad_sig: [u8; SIGNATURE_LENGTH] = good_sig.to_bytes();
+
+        bad_sig[63] |= 224;
+        let bad_sig: Signature = Signature::from_bytes(&bad_sig).unwrap();
+
+        assert_eq!(keypair.public.verify_constant_time::<Sha512>(good, &good_sig), Ok(()));
+
+        let start: Instant = Instant::now();
+        assert_eq!(keypair.public.verify_constant_time::<Sha512>(good, &bad_sig),
+                   Err(SignatureError::InvalidSignature));
+        assert!(start.elapsed() >= CONSTANT_TIME_VERIFY_DURATION);
+    }
+
     // TESTVECTORS is taken from sign.input.gz in agl's ed25519 Golang
     // package. It is a selection of test cases from
     // http://ed25519.cr.yp.to/python/sign.input
@@ -1024,7 +2888,7 @@ mod test {
             let sig2: Signature = keypair.sign::<Sha512>(&msg_bytes);
 
             assert!(sig1 == sig2, "Signature bytes not equal on line {}", lineno);
-            assert!(keypair.verify::<Sha512>(&msg_bytes, &sig2),
+            assert!(keypair.verify::<Sha512>(&msg_bytes, &sig2).is_ok(),
                     "Signature verification failed on line {}", lineno);
         }
     }
@@ -1049,6 +2913,220 @@ mod test {
             175, 002, 026, 104, 247, 007, 081, 026, ]))))
     }
 
+    /// A fake RNG which fills every byte with the same value.
+    struct ConstantRng(u8);
+
+    impl Rng for ConstantRng {
+        fn next_u32(&mut self) -> u32 { u32::from_le_bytes([self.0; 4]) }
+
+        fn fill_bytes(&mut self, bytes: &mut [u8]) {
+            for byte in bytes.iter_mut() {
+                *byte = self.0;
+            }
+        }
+    }
+
+    #[test]
+    fn hedged_signatures_verify_and_differ() {
+        let keypair: Keypair = keypair_for_testing("hedged");
+        let first: Signature = keypair.sign_with_rng::<Sha512>(b"test message", &mut ConstantRng(1));
+        let second: Signature = keypair.sign_with_rng::<Sha512>(b"test message", &mut ConstantRng(2));
+
+        assert_eq!(keypair.verify::<Sha512>(b"test message", &first), Ok(()));
+        assert_eq!(keypair.verify::<Sha512>(b"test message", &second), Ok(()));
+        assert!(first != second);
+        assert!(first != keypair.sign::<Sha512>(b"test message"));
+        assert_eq!(keypair.sign_with_rng::<Sha512>(b"test message", &mut ConstantRng(1)), first);
+    }
+
+    #[test]
+    fn sign_array_matches_sign() {
+        let keypair: Keypair = keypair_for_testing("sign array");
+        let hash: [u8; 32] = [7u8; 32];
+
+        assert_eq!(keypair.sign_array::<Sha512, 32>(&hash), keypair.sign::<Sha512>(&hash[..]));
+    }
+
+    #[test]
+    fn conversions_from_bytes() {
+        let keypair: Keypair = keypair_for_testing("conversions");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let bytes: [u8; KEYPAIR_LENGTH] = keypair.to_raw_bytes();
+
+        assert_eq!(PublicKey::try_from(&bytes[SECRET_KEY_LENGTH..]), Ok(keypair.public));
+        assert_eq!(PublicKey::try_from(keypair.public.to_bytes()), Ok(keypair.public));
+        assert_eq!(Signature::try_from(&signature.to_bytes()[..]), Ok(signature));
+        assert_eq!(Signature::from(signature.to_bytes()), signature);
+        assert_eq!(SecretKey::try_from(&bytes[..SECRET_KEY_LENGTH]).unwrap().as_bytes(),
+                   keypair.secret.as_bytes());
+        assert_eq!(Keypair::try_from(bytes).unwrap().to_raw_bytes()[..], bytes[..]);
+        assert_eq!(Keypair::try_from(&bytes[..]).unwrap().public, keypair.public);
+
+        assert_eq!(PublicKey::try_from(&bytes[..]), Err("Wrong length of bytes for creating public key!"));
+        assert!(SecretKey::try_from(&bytes[..]).is_err());
+        assert!(ExpandedSecretKey::try_from(&bytes[1..]).is_err());
+        assert!(Keypair::try_from(&bytes[1..]).is_err());
+        assert!(Signature::try_from(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn public_key_from_secret_key() {
+        let keypair: Keypair = keypair_for_testing("to_public");
+
+        assert_eq!(keypair.secret.to_public::<Sha512>(), keypair.public);
+
+        #[cfg(feature = "sha2")]
+        assert_eq!(PublicKey::from(&keypair.secret), keypair.public);
+    }
+
+    #[test]
+    fn keypair_from_seed() {
+        let zero: Keypair = Keypair::from_seed::<Sha512>(&[0u8; 32]);
+        let seed_bytes: Vec<u8> = FromHex::from_hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60").unwrap();
+        let seed: [u8; 32] = *array_ref!(seed_bytes, 0, 32);
+        let test_1: Keypair = Keypair::from_seed::<Sha512>(&seed);
+        let public_1: Vec<u8> = FromHex::from_hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let public_zero: Vec<u8> = FromHex::from_hex("3b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29").unwrap();
+
+        // TEST 1 from RFC8032 §7.1.
+        assert_eq!(&test_1.public.as_bytes()[..], &public_1[..]);
+        assert_eq!(&zero.public.as_bytes()[..], &public_zero[..]);
+        assert_eq!(SecretKey::from_seed(&seed).to_bytes(), seed);
+        assert_eq!(Keypair::from_raw_bytes(&test_1.to_raw_bytes()).unwrap().public, test_1.public);
+    }
+
+    #[test]
+    fn keypair_for_testing_is_deterministic() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let alice_again: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+
+        assert_eq!(alice.secret.to_bytes(), alice_again.secret.to_bytes());
+        assert_eq!(alice.public, alice_again.public);
+        assert!(alice.public != bob.public);
+
+        // Pin the derivation, so that it never silently changes.
+        assert_eq!(alice.secret.to_bytes(), [
+            205, 221,  17, 106, 148,  56,   6, 136,
+            197, 119, 143,  97, 212,  88, 255,   5,
+             17,  20,  65, 146, 114,  39, 108, 178,
+            146,  18, 159,  47, 112, 215, 125,  68, ]);
+        assert_eq!(alice.public.to_bytes(), [
+            120, 237, 115, 212,  94,  67, 147, 162,
+            139,  10, 170, 188, 158,  61,  48, 119,
+            183,  21,  10,  33, 200, 201,  25, 255,
+            246,  74, 222,  20,  43,  98, 184,  95, ]);
+    }
+
+    #[test]
+    fn keypair_from_entropy() {
+        let entropy: [u8; 100] = [42u8; 100];
+        let keypair: Keypair = Keypair::from_entropy::<Sha512>(&entropy).unwrap();
+        let hash: Vec<u8> = Sha512::digest(&entropy).to_vec();
+
+        assert_eq!(&keypair.secret.to_bytes()[..], &hash[..32]);
+        assert_eq!(keypair.public, PublicKey::from_secret::<Sha512>(&keypair.secret));
+        assert!(Keypair::from_entropy::<Sha512>(&entropy[..32]).is_ok());
+        assert_eq!(Keypair::from_entropy::<Sha512>(&entropy[..31]).unwrap_err(),
+                   SignatureError::InvalidKeyLength);
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn from_x25519_secret_is_domain_separated() {
+        // Alice's private key from RFC 7748 §6.1.
+        let x25519_secret: Vec<u8> = FromHex::from_hex(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a").unwrap();
+        let expected_public: Vec<u8> = FromHex::from_hex(
+            "bcc6b522e8767407e706cff47c3dc8b12c4c2754e2ad5a5c3084038c09bb31b8").unwrap();
+        let keypair: Keypair = Keypair::from_x25519_secret(array_ref!(x25519_secret, 0, 32));
+
+        assert!(keypair.secret.to_bytes()[..] != x25519_secret[..]);
+        assert_eq!(&keypair.public.to_bytes()[..], &expected_public[..]);
+    }
+
+    #[cfg(feature = "hkdf")]
+    #[test]
+    fn derive_range_children_are_distinct() {
+        let root: SecretKey = SecretKey([7u8; SECRET_KEY_LENGTH]);
+        let children: Vec<SecretKey> = root.derive_range::<Sha512>(0, 16);
+        let again: Vec<SecretKey> = root.derive_range::<Sha512>(1, 2);
+
+        assert_eq!(children.len(), 16);
+        assert_eq!(root.derive_range::<Sha512>(5, 5).len(), 0);
+        assert_eq!(children[1].to_bytes(), again[0].to_bytes());
+
+        for i in 0..children.len() {
+            for j in (i + 1)..children.len() {
+                assert!(children[i].to_bytes() != children[j].to_bytes());
+            }
+        }
+        assert_eq!(children[0].to_bytes(), [
+            160, 143, 145,  85, 241, 212,  68,  46,
+            178,  79,  81, 106, 206, 251,  42,   2,
+            200,  48, 229, 179, 180, 209,  71,  80,
+            252, 145, 236, 250, 176, 183, 229, 247, ]);
+    }
+
+    #[cfg(feature = "hkdf")]
+    #[test]
+    fn derive_child_is_deterministic_and_distinct() {
+        let root: Keypair = keypair_for_testing("derive_child");
+        let signing: Keypair = root.derive_child::<Sha512>(b"signing");
+        let backup: Keypair = root.derive_child::<Sha512>(b"backup");
+
+        assert_eq!(root.derive_child::<Sha512>(b"signing").public, signing.public);
+        assert!(signing.public != backup.public);
+        assert!(signing.public != root.public);
+        assert!(root.derive_child::<Sha512>(b"").public != signing.public);
+        assert!(signing.verify_own_public_key::<Sha512>().is_ok());
+
+        // Pin the derivation, so that it never silently changes.
+        assert_eq!(signing.secret.to_bytes(), [
+            144, 156, 163,  58, 228, 162,   1, 191,
+            202,  44, 239,  56,  57, 139, 242, 128,
+            211, 111, 148, 205, 162, 142, 181, 242,
+            159, 239,  80, 110, 155, 180, 113, 210, ]);
+    }
+
+    // Test vector 1 for ed25519 from SLIP-0010.
+    #[cfg(all(feature = "hkdf", feature = "sha2"))]
+    #[test]
+    fn derive_from_path_slip10_vector() {
+        let master: Vec<u8> = FromHex::from_hex(
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7").unwrap();
+        let master_chain_code: Vec<u8> = FromHex::from_hex(
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb").unwrap();
+        let expected_secret: Vec<u8> = FromHex::from_hex(
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793").unwrap();
+        let expected_public: Vec<u8> = FromHex::from_hex(
+            "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a").unwrap();
+        let expected_chain_code: Vec<u8> = FromHex::from_hex(
+            "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230").unwrap();
+
+        let secret: SecretKey = SecretKey::from_bytes(&master).unwrap();
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+        let root: Keypair = Keypair{ secret, public };
+        let chain_code: &[u8; 32] = array_ref!(master_chain_code, 0, 32);
+
+        let (child, child_chain_code) = Keypair::derive_from_path(
+            &root, chain_code, "m/0'/1'/2'/2'/1000000000'").unwrap();
+
+        assert_eq!(&child.secret.to_bytes()[..], &expected_secret[..]);
+        assert_eq!(&child.public.to_bytes()[..], &expected_public[..]);
+        assert_eq!(&child_chain_code[..], &expected_chain_code[..]);
+
+        let (same, same_chain_code) = Keypair::derive_from_path(&root, chain_code, "m").unwrap();
+        assert_eq!(same.to_raw_bytes()[..], root.to_raw_bytes()[..]);
+        assert_eq!(&same_chain_code[..], &master_chain_code[..]);
+
+        for path in ["", "m/", "44'", "m/44", "m/44'/0", "m/'", "m/+1'", "m/2147483648'",
+                     "m/4294967296'", "m/44''", "m//0'"].iter() {
+            assert_eq!(Keypair::derive_from_path(&root, chain_code, path).unwrap_err(),
+                       SignatureError::InvalidInput);
+        }
+    }
+
     #[cfg(all(test, feature = "serde"))]
     use bincode::{serialize, deserialize, Infinite};
 
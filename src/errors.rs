@@ -0,0 +1,152 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Errors which may occur when verifying signatures.
+
+use core::fmt;
+use core::fmt::Display;
+
+use ed25519::KEY_ID_LENGTH;
+
+/// Errors which may occur while processing signatures and keypairs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignatureError {
+    /// The signature did not verify under the given public key and message.
+    InvalidSignature,
+    /// The `s` of a signature was too large to be a scalar modulo ℓ.
+    ScalarFormatError,
+    /// A public key was not the encoding of a point on the curve.
+    PointDecompressionError,
+    /// The signature is valid, but it has been seen before.
+    ReplayedSignature,
+    /// An encoded key or signature was malformed, e.g. it had the wrong
+    /// length, prefix, or checksum, or contained invalid characters.
+    InvalidEncoding,
+    /// The public half of a keypair does not correspond to its secret half.
+    InconsistentKeypair,
+    /// A key was of the wrong length or type for ed25519.
+    InvalidKeyLength,
+    /// No public key is known for the given key identifier.
+    KeyNotFound,
+    /// The operating system's random number generator could not be used.
+    EntropyError,
+    /// A ciphertext could not be decrypted, either because it was malformed or
+    /// because it was not encrypted to the given key.
+    DecryptionFailed,
+    /// An argument was malformed, e.g. a derivation path could not be parsed.
+    InvalidInput,
+    /// A certificate or signature was used before or after its period of
+    /// validity.
+    OutsideValidityPeriod,
+    /// A key was asked to sign a message which its policy does not allow.
+    PolicyDenied,
+    /// A signer's counter has reached its maximum, and cannot be used again.
+    CounterExhausted,
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SignatureError::InvalidSignature
+                => write!(f, "Verification equation was not satisfied"),
+            SignatureError::ScalarFormatError
+                => write!(f, "Signature scalar is out of range"),
+            SignatureError::PointDecompressionError
+                => write!(f, "Public key is not a point on the curve"),
+            SignatureError::ReplayedSignature
+                => write!(f, "Signature has already been seen"),
+            SignatureError::InvalidEncoding
+                => write!(f, "Malformed encoding of key or signature"),
+            SignatureError::InconsistentKeypair
+                => write!(f, "Public key does not match secret key"),
+            SignatureError::InvalidKeyLength
+                => write!(f, "Key is not an ed25519 key"),
+            SignatureError::KeyNotFound
+                => write!(f, "No public key is known for this key identifier"),
+            SignatureError::EntropyError
+                => write!(f, "Could not obtain randomness from the operating system"),
+            SignatureError::DecryptionFailed
+                => write!(f, "Ciphertext could not be decrypted"),
+            SignatureError::InvalidInput
+                => write!(f, "Malformed input"),
+            SignatureError::OutsideValidityPeriod
+                => write!(f, "Certificate or signature is not valid at this time"),
+            SignatureError::PolicyDenied
+                => write!(f, "Signing this message is not allowed"),
+            SignatureError::CounterExhausted
+                => write!(f, "Signing counter is exhausted"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for SignatureError {
+    fn description(&self) -> &str {
+        match *self {
+            SignatureError::InvalidSignature      => "invalid signature",
+            SignatureError::ScalarFormatError     => "scalar format error",
+            SignatureError::PointDecompressionError => "point decompression error",
+            SignatureError::ReplayedSignature     => "replayed signature",
+            SignatureError::InvalidEncoding       => "invalid encoding",
+            SignatureError::InconsistentKeypair   => "inconsistent keypair",
+            SignatureError::InvalidKeyLength      => "invalid key length",
+            SignatureError::KeyNotFound           => "key not found",
+            SignatureError::EntropyError          => "entropy error",
+            SignatureError::DecryptionFailed      => "decryption failed",
+            SignatureError::InvalidInput          => "invalid input",
+            SignatureError::OutsideValidityPeriod => "outside validity period",
+            SignatureError::PolicyDenied          => "policy denied",
+            SignatureError::CounterExhausted      => "counter exhausted",
+        }
+    }
+}
+
+/// A signature which failed to verify, together with the key identifier of
+/// the public key it was checked against, for diagnosing which key the signer
+/// really used.
+///
+/// This is returned by `PublicKey::verify_debug()`, and converts into the
+/// `SignatureError` it wraps.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SignatureMismatchError {
+    /// The `PublicKey::key_id()` of the public key which the signature was
+    /// expected to verify under.
+    pub expected_key_id: [u8; KEY_ID_LENGTH],
+    /// The bytes of the signature which failed to verify.
+    pub signature_bytes: [u8; 64],
+    /// Why the signature failed to verify.
+    pub reason: SignatureError,
+}
+
+impl Display for SignatureMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Signature ")?;
+        for byte in self.signature_bytes.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, " did not verify under the public key with key id ")?;
+        for byte in self.expected_key_id.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+impl From<SignatureMismatchError> for SignatureError {
+    fn from(error: SignatureMismatchError) -> SignatureError {
+        error.reason
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for SignatureMismatchError {
+    fn description(&self) -> &str {
+        "signature mismatch"
+    }
+}
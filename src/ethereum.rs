@@ -0,0 +1,61 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Ethereum-style addresses for ed25519 public keys.
+
+use tiny_keccak::Hasher;
+use tiny_keccak::Keccak;
+
+use ed25519::PublicKey;
+
+/// The length of an Ethereum-style address, in bytes.
+pub const ETHEREUM_ADDRESS_LENGTH: usize = 20;
+
+impl PublicKey {
+    /// Compute the Ethereum-style address of this public key: the last 20
+    /// bytes of the Keccak-256 digest of its 32-byte encoding.
+    ///
+    /// Note that Keccak-256 is the original Keccak submission, as used by
+    /// Ethereum, and not the standardised SHA3-256, which pads differently.
+    ///
+    /// # Warning
+    ///
+    /// Ethereum itself derives addresses from secp256k1 keys, which are
+    /// hashed in their 64-byte uncompressed form, so an address computed
+    /// here never corresponds to an Ethereum account; it is only meaningful
+    /// to systems which define their addresses this way.
+    pub fn to_ethereum_style_address(&self) -> [u8; ETHEREUM_ADDRESS_LENGTH] {
+        let mut h: Keccak = Keccak::v256();
+        let mut digest: [u8; 32] = [0u8; 32];
+        let mut address: [u8; ETHEREUM_ADDRESS_LENGTH] = [0u8; ETHEREUM_ADDRESS_LENGTH];
+
+        h.update(self.as_bytes());
+        h.finalize(&mut digest);
+        address.copy_from_slice(&digest[32 - ETHEREUM_ADDRESS_LENGTH..]);
+        address
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use hex::FromHex;
+    use super::*;
+
+    #[test]
+    fn ethereum_style_address_of_rfc8032_test_1_key() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let expected: Vec<u8> = FromHex::from_hex(
+            "f7cc70adc63659b5d37671dc2b588db32446684a").unwrap();
+        let public: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+
+        assert_eq!(&public.to_ethereum_style_address()[..], &expected[..]);
+    }
+}
@@ -0,0 +1,135 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signatures which carry, and are bound to, a time after which they should
+//! no longer be accepted.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// The domain separation string prepended to the expiry and message, so that
+/// an expiring signature cannot be confused with a signature on a message
+/// which happens to begin with eight bytes.
+const EXPIRING_DOMAIN: &[u8] = b"ed25519-dalek expiring signature";
+
+/// A signature together with the time, in seconds since the UNIX epoch, after
+/// which it expires.
+///
+/// The expiry is signed along with the message, so it cannot be extended
+/// without invalidating the signature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExpiringSignature {
+    /// The signature on the expiry and the message.
+    pub signature: Signature,
+    /// The last second, since the UNIX epoch, at which the signature is
+    /// valid.
+    pub expiry: u64,
+}
+
+impl ExpiringSignature {
+    /// Check that this signature on the `message` is valid under `public_key`,
+    /// and that it has not expired at the time `now`, in seconds since the
+    /// UNIX epoch.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the signature is valid and `now` is no later than its
+    ///   expiry,
+    /// * an error as for `PublicKey::verify()` if the signature is invalid,
+    /// * `Err(SignatureError::OutsideValidityPeriod)` if the signature is
+    ///   valid, but has expired.
+    pub fn verify_not_expired<D>(&self, public_key: &PublicKey, message: &[u8], now: u64)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let expiry: [u8; 8] = self.expiry.to_be_bytes();
+
+        public_key.verify_with_dom2::<D>(None, &[EXPIRING_DOMAIN, &expiry, message],
+                                         &self.signature)?;
+
+        if now > self.expiry {
+            return Err(SignatureError::OutsideValidityPeriod);
+        }
+        Ok(())
+    }
+
+    /// The number of seconds from `now` until this signature expires, or
+    /// `None` if it has already expired.
+    ///
+    /// This does not check the signature.
+    pub fn time_remaining(&self, now: u64) -> Option<u64> {
+        if now > self.expiry { None } else { Some(self.expiry - now) }
+    }
+}
+
+impl Keypair {
+    /// Sign a `message` with this keypair, such that the signature expires
+    /// after the time `expiry_unix_secs`, in seconds since the UNIX epoch.
+    ///
+    /// The message actually signed is `"ed25519-dalek expiring signature"`,
+    /// followed by the expiry as eight big-endian bytes, followed by the
+    /// `message`.  Verify it with `ExpiringSignature::verify_not_expired()`.
+    pub fn sign_with_expiry<D>(&self, message: &[u8], expiry_unix_secs: u64) -> ExpiringSignature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let expiry: [u8; 8] = expiry_unix_secs.to_be_bytes();
+        let signature: Signature = self.secret.expand::<D>()
+            .sign_with_dom2::<D>(None, &[EXPIRING_DOMAIN, &expiry, message], &self.public);
+
+        ExpiringSignature{ signature, expiry: expiry_unix_secs }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn expiring_signature_verifies_until_expiry() {
+        let keypair: Keypair = keypair_for_testing("expiring");
+        let signature: ExpiringSignature = keypair.sign_with_expiry::<Sha512>(b"test message", 1000);
+
+        assert_eq!(signature.verify_not_expired::<Sha512>(&keypair.public, b"test message", 999),
+                   Ok(()));
+        assert_eq!(signature.verify_not_expired::<Sha512>(&keypair.public, b"test message", 1000),
+                   Ok(()));
+        assert_eq!(signature.verify_not_expired::<Sha512>(&keypair.public, b"test message", 1001),
+                   Err(SignatureError::OutsideValidityPeriod));
+        assert_eq!(signature.verify_not_expired::<Sha512>(&keypair.public, b"other message", 999),
+                   Err(SignatureError::InvalidSignature));
+        assert!(keypair.verify::<Sha512>(b"test message", &signature.signature).is_err());
+    }
+
+    #[test]
+    fn expiry_cannot_be_extended() {
+        let keypair: Keypair = keypair_for_testing("expiring");
+        let mut signature: ExpiringSignature = keypair.sign_with_expiry::<Sha512>(b"test message",
+                                                                                   1000);
+
+        signature.expiry = 2000;
+        assert_eq!(signature.verify_not_expired::<Sha512>(&keypair.public, b"test message", 1500),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn time_remaining() {
+        let keypair: Keypair = keypair_for_testing("expiring");
+        let signature: ExpiringSignature = keypair.sign_with_expiry::<Sha512>(b"test message", 1000);
+
+        assert_eq!(signature.time_remaining(400), Some(600));
+        assert_eq!(signature.time_remaining(1000), Some(0));
+        assert_eq!(signature.time_remaining(1001), None);
+    }
+}
@@ -0,0 +1,165 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Display and parsing of keys and signatures as hexadecimal strings.
+//!
+//! `PublicKey` and `Signature` are displayed as lowercase hexadecimal, and
+//! parsed from hexadecimal of either case.  `SecretKey` is parsed likewise,
+//! but is only displayed with the "hazmat" feature, so that a secret key
+//! cannot end up in a log by accident.
+
+use core::fmt;
+use core::fmt::Display;
+use core::str::FromStr;
+
+use ed25519::{PublicKey, SecretKey, Signature};
+use ed25519::{PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH, SIGNATURE_LENGTH};
+
+/// Write `bytes` to `f` as lowercase hexadecimal.
+fn write_hex(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(f, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+/// The value of the hexadecimal digit `c`, of either case.
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Decode the hexadecimal string `s` into `bytes`, which it must exactly
+/// fill.
+///
+/// # Returns
+///
+/// `Err(length_error)` if `s` is the wrong length, or `Err(digit_error)` if
+/// it contains anything other than hexadecimal digits.
+fn read_hex(s: &str, bytes: &mut [u8], length_error: &'static str, digit_error: &'static str)
+        -> Result<(), &'static str> {
+
+    if s.len() != 2 * bytes.len() {
+        return Err(length_error);
+    }
+    for (byte, digits) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+        let high: u8 = hex_digit(digits[0]).ok_or(digit_error)?;
+        let low: u8 = hex_digit(digits[1]).ok_or(digit_error)?;
+
+        *byte = (high << 4) | low;
+    }
+    Ok(())
+}
+
+impl Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<PublicKey, &'static str> {
+        let mut bytes: [u8; PUBLIC_KEY_LENGTH] = [0u8; PUBLIC_KEY_LENGTH];
+
+        read_hex(s, &mut bytes, "Wrong length of hex for public key! Need 64 hex digits.",
+                 "Invalid hex digit in public key!")?;
+        PublicKey::from_bytes(&bytes)
+    }
+}
+
+impl Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, &self.to_bytes())
+    }
+}
+
+impl FromStr for Signature {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Signature, &'static str> {
+        let mut bytes: [u8; SIGNATURE_LENGTH] = [0u8; SIGNATURE_LENGTH];
+
+        read_hex(s, &mut bytes, "Wrong length of hex for signature! Need 128 hex digits.",
+                 "Invalid hex digit in signature!")?;
+        Signature::from_bytes(&bytes)
+    }
+}
+
+#[cfg(feature = "hazmat")]
+impl Display for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(f, self.as_bytes())
+    }
+}
+
+impl FromStr for SecretKey {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<SecretKey, &'static str> {
+        let mut bytes: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+
+        read_hex(s, &mut bytes, "Wrong length of hex for secret key! Need 64 hex digits.",
+                 "Invalid hex digit in secret key!")?;
+        SecretKey::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::{String, ToString};
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    // The public key of TEST 1 from RFC8032 §7.1.
+    static PUBLIC_KEY_HEX: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    #[test]
+    fn hex_display_and_parse() {
+        let public: PublicKey = PUBLIC_KEY_HEX.parse().unwrap();
+        let signature: Signature = keypair_for_testing("hex").sign::<Sha512>(b"test message");
+
+        assert_eq!(public.to_string(), PUBLIC_KEY_HEX);
+        assert_eq!(PUBLIC_KEY_HEX.to_uppercase().parse::<PublicKey>(), Ok(public));
+        assert_eq!(signature.to_string().len(), 128);
+        assert_eq!(signature.to_string().parse::<Signature>(), Ok(signature));
+        assert_eq!(format!("{:?}", public), format!("PublicKey(\"{}\")", PUBLIC_KEY_HEX));
+        assert_eq!(format!("{:?}", signature), format!("Signature(\"{}\")", signature));
+    }
+
+    #[test]
+    fn secret_key_from_hex() {
+        let keypair: Keypair = keypair_for_testing("hex");
+        let hex: String = keypair.secret.as_bytes().iter().map(|b| format!("{:02X}", b)).collect();
+        let secret: SecretKey = hex.parse().unwrap();
+
+        assert_eq!(secret.as_bytes(), keypair.secret.as_bytes());
+
+        #[cfg(feature = "hazmat")]
+        assert_eq!(secret.to_string(), hex.to_lowercase());
+    }
+
+    #[test]
+    fn hex_malformed_is_rejected() {
+        assert_eq!(PUBLIC_KEY_HEX[..62].parse::<PublicKey>(),
+                   Err("Wrong length of hex for public key! Need 64 hex digits."));
+        assert_eq!(PUBLIC_KEY_HEX.replace("d", "g").parse::<PublicKey>(),
+                   Err("Invalid hex digit in public key!"));
+        assert_eq!(PUBLIC_KEY_HEX.parse::<Signature>(),
+                   Err("Wrong length of hex for signature! Need 128 hex digits."));
+        assert_eq!(PUBLIC_KEY_HEX.replace("d7", "é").parse::<SecretKey>().unwrap_err(),
+                   "Invalid hex digit in secret key!");
+    }
+}
@@ -0,0 +1,204 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! HMAC (RFC 2104) and HKDF (RFC 5869), generic over the hash function.
+//!
+//! These are written out here, rather than taken from the `hmac` and `hkdf`
+//! crates, because current releases of those crates are built on `digest`
+//! 0.10, whose traits the `digest` 0.6 hashes taken by the `D: Digest`
+//! parameters throughout this crate do not implement.  They are tested
+//! against all seven test cases of RFC 5869.
+
+use digest::Digest;
+
+use generic_array::GenericArray;
+use generic_array::typenum::Unsigned;
+
+/// Compute `HMAC-D(key, message)`, where the message is the concatenation of
+/// each of the `message` parts.
+pub(crate) fn hmac<D>(key: &[u8], message: &[&[u8]]) -> GenericArray<u8, D::OutputSize>
+        where D: Digest {
+
+    let mut padded_key: GenericArray<u8, D::BlockSize> = GenericArray::default();
+    let mut pad: GenericArray<u8, D::BlockSize> = GenericArray::default();
+    let mut inner: D = D::default();
+    let mut outer: D = D::default();
+
+    if key.len() > D::BlockSize::to_usize() {
+        let digest = D::digest(key);
+        padded_key[..digest.len()].copy_from_slice(&digest);
+    } else {
+        padded_key[..key.len()].copy_from_slice(key);
+    }
+
+    for i in 0..pad.len() {
+        pad[i] = padded_key[i] ^ 0x36;
+    }
+    inner.input(&pad);
+    for part in message {
+        inner.input(part);
+    }
+
+    for i in 0..pad.len() {
+        pad[i] = padded_key[i] ^ 0x5c;
+    }
+    outer.input(&pad);
+    outer.input(&inner.result());
+    outer.result()
+}
+
+/// HKDF-Extract a pseudorandom key from the input keying material `ikm`.
+pub(crate) fn hkdf_extract<D>(salt: &[u8], ikm: &[u8]) -> GenericArray<u8, D::OutputSize>
+        where D: Digest {
+    hmac::<D>(salt, &[ikm])
+}
+
+/// HKDF-Expand the pseudorandom key `prk` and `info` to fill `okm`.
+///
+/// # Panics
+///
+/// If `okm` is longer than 255 times the output size of `D`.
+pub(crate) fn hkdf_expand<D>(prk: &[u8], info: &[u8], okm: &mut [u8])
+        where D: Digest {
+
+    let hash_length: usize = D::OutputSize::to_usize();
+    let mut t: GenericArray<u8, D::OutputSize> = GenericArray::default();
+
+    assert!(okm.len() <= 255 * hash_length, "HKDF output length is too long");
+
+    for (i, chunk) in okm.chunks_mut(hash_length).enumerate() {
+        let counter: [u8; 1] = [(i + 1) as u8];
+        let previous: &[u8] = if i == 0 { &[] } else { &t[..] };
+
+        t = hmac::<D>(prk, &[previous, info, &counter]);
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use digest::{BlockInput, FixedOutput, Input};
+    use generic_array::typenum::{U20, U64};
+    use hex::FromHex;
+    use sha1;
+    use sha2::Sha256;
+    use super::*;
+
+    /// SHA-1 from the `sha1` crate, behind the `digest` 0.6 traits which
+    /// `hmac()` takes, for the SHA-1 test cases of RFC 5869.
+    #[derive(Clone, Default)]
+    struct Sha1(sha1::Sha1);
+
+    impl Input for Sha1 {
+        fn process(&mut self, input: &[u8]) {
+            sha1::Digest::update(&mut self.0, input);
+        }
+    }
+
+    impl BlockInput for Sha1 {
+        type BlockSize = U64;
+    }
+
+    impl FixedOutput for Sha1 {
+        type OutputSize = U20;
+
+        fn fixed_result(self) -> GenericArray<u8, U20> {
+            GenericArray::clone_from_slice(&sha1::Digest::finalize(self.0))
+        }
+    }
+
+    /// Check HKDF-`D` against a test case from RFC 5869, appendix A.
+    fn check_hkdf<D>(ikm: &[u8], salt: &[u8], info: &[u8], expected_prk: &str, expected_okm: &str)
+            where D: Digest {
+
+        let expected_prk: Vec<u8> = FromHex::from_hex(expected_prk).unwrap();
+        let expected_okm: Vec<u8> = FromHex::from_hex(expected_okm).unwrap();
+        let mut okm: Vec<u8> = vec![0u8; expected_okm.len()];
+
+        let prk = hkdf_extract::<D>(salt, ikm);
+        hkdf_expand::<D>(&prk, info, &mut okm);
+
+        assert_eq!(&prk[..], &expected_prk[..]);
+        assert_eq!(okm, expected_okm);
+    }
+
+    /// The bytes `start..end`.
+    fn range(start: u16, end: u16) -> Vec<u8> {
+        (start..end).map(|byte| byte as u8).collect()
+    }
+
+    #[test]
+    fn hmac_sha256_rfc4231_case_2() {
+        let expected: Vec<u8> = FromHex::from_hex(
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843").unwrap();
+
+        assert_eq!(&hmac::<Sha256>(b"Jefe", &[b"what do ya want ", b"for nothing?"])[..],
+                   &expected[..]);
+    }
+
+    #[test]
+    fn hkdf_sha256_rfc5869_case_1() {
+        check_hkdf::<Sha256>(&[0x0b; 22], &range(0x00, 0x0d), &range(0xf0, 0xfa),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c\
+             5db02d56ecc4c5bf34007208d5b887185865");
+    }
+
+    #[test]
+    fn hkdf_sha256_rfc5869_case_2() {
+        check_hkdf::<Sha256>(&range(0x00, 0x50), &range(0x60, 0xb0), &range(0xb0, 0x100),
+            "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+            "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+             59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+             cc30c58179ec3e87c14c01d5c1f3434f1d87");
+    }
+
+    #[test]
+    fn hkdf_sha256_rfc5869_case_3() {
+        check_hkdf::<Sha256>(&[0x0b; 22], &[], &[],
+            "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d\
+             9d201395faa4b61a96c8");
+    }
+
+    #[test]
+    fn hkdf_sha1_rfc5869_case_4() {
+        check_hkdf::<Sha1>(&[0x0b; 11], &range(0x00, 0x0d), &range(0xf0, 0xfa),
+            "9b6c18c432a7bf8f0e71c8eb88f4b30baa2ba243",
+            "085a01ea1b10f36933068b56efa5ad81a4f14b822f5b091568a9cdd4f155fda2\
+             c22e422478d305f3f896");
+    }
+
+    #[test]
+    fn hkdf_sha1_rfc5869_case_5() {
+        check_hkdf::<Sha1>(&range(0x00, 0x50), &range(0x60, 0xb0), &range(0xb0, 0x100),
+            "8adae09a2a307059478d309b26c4115a224cfaf6",
+            "0bd770a74d1160f7c9f12cd5912a06ebff6adcae899d92191fe4305673ba2ffe\
+             8fa3f1a4e5ad79f3f334b3b202b2173c486ea37ce3d397ed034c7f9dfeb15c5e\
+             927336d0441f4c4300e2cff0d0900b52d3b4");
+    }
+
+    #[test]
+    fn hkdf_sha1_rfc5869_case_6() {
+        check_hkdf::<Sha1>(&[0x0b; 22], &[], &[],
+            "da8c8a73c7fa77288ec6f5e7c297786aa0d32d01",
+            "0ac1af7002b3d761d1e55298da9d0506b9ae52057220a306e07b6b87e8df21d0\
+             ea00033de03984d34918");
+    }
+
+    // The salt is not provided, so it is HashLen zero bytes.
+    #[test]
+    fn hkdf_sha1_rfc5869_case_7() {
+        check_hkdf::<Sha1>(&[0x0c; 22], &[0u8; 20], &[],
+            "2adccada18779e7c2077ad2eb19d3f3e731385dd",
+            "2c91117204d745f3500d636a62f64f0ab3bae548aa53d423b0d1f27ebba6f5e5\
+             673a081d70cce7acfc48");
+    }
+}
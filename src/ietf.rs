@@ -0,0 +1,168 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Verification of ed25519 signatures one step at a time, in the order given
+//! by RFC8032 §5.1.7, recording the outcome of each step.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::vartime;
+use curve25519_dalek::scalar::Scalar;
+
+use subtle::slices_equal;
+
+use ed25519::{PublicKey, Signature};
+
+/// The outcome of each step of verifying a signature with an
+/// `IetfEddsaVerifier`.
+///
+/// Steps which were not reached, because an earlier step failed, are `false`
+/// or `None`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct IetfVerificationTrace {
+    /// Step 1: whether the scalar `S` was in range.
+    pub s_in_range: bool,
+    /// Step 1: whether the public key `A` decoded to a point.
+    pub a_decompressed: bool,
+    /// Step 1: whether the first half of the signature, `R`, decoded to a
+    /// point.
+    pub r_decompressed: bool,
+    /// Step 2: the digest `H(R || A || M)`, before reduction modulo ℓ.
+    pub hash_value: Option<[u8; 64]>,
+    /// Step 3: the encoding of the point `[S]B - [k]A`, which must equal `R`.
+    pub check_point: Option<[u8; 32]>,
+    /// Whether the signature is valid.
+    pub final_result: bool,
+}
+
+/// A verifier of ed25519 signatures which performs the steps of RFC8032
+/// §5.1.7 in order, and reports the result of each.
+///
+/// This is slower than `PublicKey::verify()`, and is intended for debugging
+/// and for tracing the algorithm, e.g. when checking an implementation
+/// against a formal model.  Its verdict is always the same as that of
+/// `PublicKey::verify()`: in particular, `S` is checked with the same test
+/// of its high three bits, and the group equation is checked without the
+/// cofactor, by comparing encodings.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IetfEddsaVerifier;
+
+impl IetfEddsaVerifier {
+    /// Construct an `IetfEddsaVerifier`.
+    pub fn new() -> IetfEddsaVerifier {
+        IetfEddsaVerifier
+    }
+
+    /// Verify a `signature` on a `message` with the `public_key`, using the
+    /// digest `D`.
+    ///
+    /// # Returns
+    ///
+    /// An `IetfVerificationTrace`, whose `final_result` says whether the
+    /// signature is valid.
+    pub fn verify_step_by_step<D>(&self, public_key: &PublicKey, message: &[u8],
+                                  signature: &Signature) -> IetfVerificationTrace
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut trace: IetfVerificationTrace = IetfVerificationTrace{
+            s_in_range: false,
+            a_decompressed: false,
+            r_decompressed: false,
+            hash_value: None,
+            check_point: None,
+            final_result: false,
+        };
+        let signature_bytes: [u8; 64] = signature.to_bytes();
+        let r: CompressedEdwardsY = CompressedEdwardsY(*array_ref!(signature_bytes, 0, 32));
+        let s: Scalar = Scalar(*array_ref!(signature_bytes, 32, 32));
+
+        // Step 1: decode S, A, and R.
+        trace.s_in_range = s[31] & 224 == 0;
+        if !trace.s_in_range {
+            return trace;
+        }
+        let a: ExtendedPoint = match public_key.0.decompress() {
+            Some(a) => a,
+            None    => return trace,
+        };
+        trace.a_decompressed = true;
+
+        if r.decompress().is_none() {
+            return trace;
+        }
+        trace.r_decompressed = true;
+
+        // Step 2: compute k = H(R || A || M).
+        let mut h: D = D::default();
+        let mut digest: [u8; 64] = [0u8; 64];
+
+        h.input(r.as_bytes());
+        h.input(public_key.as_bytes());
+        h.input(message);
+        digest.copy_from_slice(h.result().as_slice());
+        trace.hash_value = Some(digest);
+
+        // Step 3: check that [S]B - [k]A = R.
+        let k: Scalar = Scalar::reduce(&digest);
+        let check: CompressedEdwardsY = vartime::double_scalar_mult_basepoint(&k, &(-(&a)), &s)
+            .compress();
+
+        trace.check_point = Some(check.to_bytes());
+        trace.final_result = slices_equal(check.as_bytes(), r.as_bytes()) == 1;
+        trace
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn verify_step_by_step_accepts_good_signature() {
+        let keypair: Keypair = keypair_for_testing("ietf");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let trace: IetfVerificationTrace = IetfEddsaVerifier::new()
+            .verify_step_by_step::<Sha512>(&keypair.public, b"test message", &signature);
+
+        assert!(trace.s_in_range && trace.a_decompressed && trace.r_decompressed);
+        assert!(trace.hash_value.is_some());
+        assert_eq!(&trace.check_point.unwrap()[..], &signature.to_bytes()[..32]);
+        assert!(trace.final_result);
+    }
+
+    #[test]
+    fn verify_step_by_step_agrees_with_verify() {
+        let keypair: Keypair = keypair_for_testing("ietf");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let mut bad_r: [u8; 64] = signature.to_bytes();
+        let mut bad_s: [u8; 64] = signature.to_bytes();
+
+        bad_r[0] ^= 1;
+        bad_s[63] |= 224;
+
+        for bytes in [bad_r, bad_s].iter() {
+            let signature: Signature = Signature::from_bytes(bytes).unwrap();
+            let trace: IetfVerificationTrace = IetfEddsaVerifier::new()
+                .verify_step_by_step::<Sha512>(&keypair.public, b"test message", &signature);
+
+            assert!(!trace.final_result);
+            assert_eq!(trace.final_result, keypair.verify::<Sha512>(b"test message", &signature).is_ok());
+        }
+        let trace: IetfVerificationTrace = IetfEddsaVerifier::new()
+            .verify_step_by_step::<Sha512>(&keypair.public, b"another message", &signature);
+
+        assert!(trace.r_decompressed && !trace.final_result);
+    }
+}
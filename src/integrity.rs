@@ -0,0 +1,120 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Messages authenticated both by an ed25519 signature and by an
+//! HMAC-SHA-256 tag under a shared symmetric key.
+//!
+//! The signature shows who signed the message, to anyone with the public
+//! key.  The tag shows, to holders of the symmetric key only, that the
+//! message came from one of them.  Neither is a substitute for the other:
+//! the tag cannot show which holder of the symmetric key made it.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use sha2::Sha256;
+
+use subtle::slices_equal;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+use hmac;
+
+/// The length of an HMAC-SHA-256 tag, in bytes.
+pub const HMAC_TAG_LENGTH: usize = 32;
+
+/// Compute the HMAC-SHA-256 tag on `message` under `hmac_key`.
+fn compute_tag(message: &[u8], hmac_key: &[u8; 32]) -> [u8; HMAC_TAG_LENGTH] {
+    let mut tag: [u8; HMAC_TAG_LENGTH] = [0u8; HMAC_TAG_LENGTH];
+
+    tag.copy_from_slice(&hmac::hmac::<Sha256>(hmac_key, &[message]));
+    tag
+}
+
+/// Sign a `message` with the `keypair`, using the hash function `D`, and
+/// compute its HMAC-SHA-256 tag under the `hmac_key`.
+pub fn sign_with_hmac<D>(keypair: &Keypair, message: &[u8], hmac_key: &[u8; 32])
+        -> (Signature, [u8; HMAC_TAG_LENGTH])
+        where D: Digest<OutputSize = U64> + Default {
+    (keypair.sign::<D>(message), compute_tag(message, hmac_key))
+}
+
+/// Check both the `signature` on the `message` under the `public_key`, using
+/// the hash function `D`, and its HMAC-SHA-256 `tag` under the `hmac_key`.
+///
+/// # Returns
+///
+/// `Ok(())` if both are valid, and `Err(SignatureError::InvalidSignature)` if
+/// either is not.
+pub fn verify_with_hmac<D>(public_key: &PublicKey, message: &[u8], signature: &Signature,
+                           hmac_key: &[u8; 32], tag: &[u8; HMAC_TAG_LENGTH])
+        -> Result<(), SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    let tag_is_valid: bool = slices_equal(&compute_tag(message, hmac_key), tag) == 1;
+
+    if public_key.verify::<D>(message, signature).is_ok() && tag_is_valid {
+        Ok(())
+    } else {
+        Err(SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use std::vec::Vec;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    fn hmac_key_for_testing() -> [u8; 32] {
+        let mut key: [u8; 32] = [0u8; 32];
+
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        key
+    }
+
+    #[test]
+    fn sign_with_hmac_tag_is_hmac_sha256() {
+        let keypair: Keypair = keypair_for_testing("integrity");
+        let (signature, tag) = sign_with_hmac::<Sha512>(&keypair, b"test message",
+                                                        &hmac_key_for_testing());
+        let expected: Vec<u8> = FromHex::from_hex(
+            "90b89bc3c5ca2c29390637567684d2bd7e5d21dc4a4d3c2502a2b07f84ab6148").unwrap();
+
+        assert_eq!(&tag[..], &expected[..]);
+        assert_eq!(signature, keypair.sign::<Sha512>(b"test message"));
+    }
+
+    #[test]
+    fn verify_with_hmac_checks_both() {
+        let keypair: Keypair = keypair_for_testing("integrity");
+        let key: [u8; 32] = hmac_key_for_testing();
+        let (signature, tag) = sign_with_hmac::<Sha512>(&keypair, b"test message", &key);
+        let other: Signature = keypair.sign::<Sha512>(b"other message");
+        let mut bad_tag: [u8; 32] = tag;
+
+        bad_tag[0] ^= 1;
+        assert_eq!(verify_with_hmac::<Sha512>(&keypair.public, b"test message", &signature,
+                                              &key, &tag), Ok(()));
+        assert_eq!(verify_with_hmac::<Sha512>(&keypair.public, b"test message", &signature,
+                                              &key, &bad_tag),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(verify_with_hmac::<Sha512>(&keypair.public, b"test message", &other,
+                                              &key, &tag),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(verify_with_hmac::<Sha512>(&keypair.public, b"test message", &signature,
+                                              &[0u8; 32], &tag),
+                   Err(SignatureError::InvalidSignature));
+    }
+}
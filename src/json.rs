@@ -0,0 +1,278 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! JSON encodings of keypairs and public keys.
+
+use std::string::String;
+use std::vec::Vec;
+
+use base64;
+
+use hex::{FromHex, ToHex};
+
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+use serde_json;
+use serde_json::Value;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use errors::SignatureError;
+
+/// A borrowed `Keypair`, serialised in the format of `Keypair::to_json()`.
+struct JsonKeypair<'a>(&'a Keypair);
+
+impl<'a> Serialize for JsonKeypair<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut state = serializer.serialize_struct("Keypair", 2)?;
+
+        state.serialize_field("secret", &self.0.secret.as_bytes().to_hex())?;
+        state.serialize_field("public", &self.0.public.as_bytes().to_hex())?;
+        state.end()
+    }
+}
+
+/// The `encoding` member of the verifier format.
+const VERIFIER_ENCODING: &str = "raw-compressed-point";
+
+/// A borrowed `PublicKey`, serialised in the format of
+/// `PublicKey::to_verifier_json()`.
+struct VerifierJson<'a>(&'a PublicKey);
+
+impl<'a> Serialize for VerifierJson<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut state = serializer.serialize_struct("PublicKey", 4)?;
+
+        state.serialize_field("algorithm", "Ed25519")?;
+        state.serialize_field("public_key",
+                              &base64::encode_config(self.0.as_bytes(), base64::URL_SAFE_NO_PAD))?;
+        state.serialize_field("key_id", &verifier_key_id(self.0))?;
+        state.serialize_field("encoding", VERIFIER_ENCODING)?;
+        state.end()
+    }
+}
+
+/// The `key_id` member of the verifier format for a `public_key`.
+fn verifier_key_id(public_key: &PublicKey) -> String {
+    public_key.key_id()[..4].to_vec().to_hex()
+}
+
+/// The string `member` of the JSON object `object`.
+fn str_member<'a>(object: &'a Value, member: &str) -> Result<&'a str, SignatureError> {
+    object.get(member).and_then(Value::as_str).ok_or(SignatureError::InvalidEncoding)
+}
+
+/// Decode the hexadecimal string `member` of the JSON object `object`.
+fn hex_member(object: &Value, member: &str) -> Result<Vec<u8>, SignatureError> {
+    let encoded: &str = str_member(object, member)?;
+
+    FromHex::from_hex(encoded).or(Err(SignatureError::InvalidEncoding))
+}
+
+impl Keypair {
+    /// Encode this `Keypair` as JSON.
+    ///
+    /// The output is a JSON object with exactly two members, each a string of
+    /// lowercase hexadecimal:
+    ///
+    /// ```text
+    /// {"secret":"<64 hex digits>","public":"<64 hex digits>"}
+    /// ```
+    ///
+    /// `secret` is the 32-byte `SecretKey`, and `public` is the 32-byte
+    /// compressed `PublicKey`, as returned by their respective `to_bytes()`
+    /// methods.  This format is a stable API: it will not change without a
+    /// major version bump.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "json")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// let json: &str = "{\"secret\":\"9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60\",\
+    ///                    \"public\":\"d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a\"}";
+    /// let keypair: Keypair = Keypair::from_json(json).unwrap();
+    ///
+    /// assert_eq!(keypair.to_json().unwrap(), json);
+    /// # }
+    /// # #[cfg(not(feature = "json"))]
+    /// # fn main() { }
+    /// ```
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&JsonKeypair(self))
+    }
+
+    /// Decode a `Keypair` from JSON, in the format of `Keypair::to_json()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Keypair`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidEncoding` if the input is not a JSON object
+    ///   whose `secret` and `public` members are 32 bytes of hex, or
+    /// * `SignatureError::InconsistentKeypair` if the `public` key is not the
+    ///   one derived from the `secret` key.
+    pub fn from_json(json: &str) -> Result<Keypair, SignatureError> {
+        let object: Value = serde_json::from_str(json).or(Err(SignatureError::InvalidEncoding))?;
+
+        if !object.is_object() {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let secret: SecretKey = SecretKey::from_bytes(&hex_member(&object, "secret")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_bytes(&hex_member(&object, "public")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if PublicKey::from_secret::<Sha512>(&secret) != public {
+            return Err(SignatureError::InconsistentKeypair);
+        }
+        Ok(Keypair{ secret, public })
+    }
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` as JSON, for exchanging public keys in
+    /// developer tools.
+    ///
+    /// The output is a JSON object with exactly four string members:
+    ///
+    /// ```text
+    /// {"algorithm":"Ed25519","public_key":"<base64url>","key_id":"<8 hex digits>","encoding":"raw-compressed-point"}
+    /// ```
+    ///
+    /// `public_key` is the 32-byte compressed `PublicKey` in unpadded
+    /// base64url, and `key_id` is the first four bytes of its SHA-256 digest
+    /// in lowercase hexadecimal (the start of its `PublicKey::key_id()`), so
+    /// that a key mistyped in transit is caught.  `encoding` names the version
+    /// of the format; any other value is rejected.  This is not a standard,
+    /// but it will only change in the same way as the format of
+    /// `Keypair::to_json()`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "json")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let json: &str = "{\"algorithm\":\"Ed25519\",\
+    ///                    \"public_key\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\",\
+    ///                    \"key_id\":\"21fe31df\",\"encoding\":\"raw-compressed-point\"}";
+    /// let public: PublicKey = PublicKey::from_verifier_json(json).unwrap();
+    ///
+    /// assert_eq!(public.to_verifier_json(), json);
+    /// # }
+    /// # #[cfg(not(feature = "json"))]
+    /// # fn main() { }
+    /// ```
+    pub fn to_verifier_json(&self) -> String {
+        // Serialising a struct of strings to a String cannot fail.
+        serde_json::to_string(&VerifierJson(self)).unwrap()
+    }
+
+    /// Decode a `PublicKey` from JSON, in the format of
+    /// `PublicKey::to_verifier_json()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `PublicKey`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the input is not a JSON
+    /// object in that format, its `algorithm` or `encoding` is not the one
+    /// given there, its `public_key` is not 32 bytes of base64url, or its `key_id`
+    /// does not match the `public_key`.
+    pub fn from_verifier_json(json: &str) -> Result<PublicKey, SignatureError> {
+        let object: Value = serde_json::from_str(json).or(Err(SignatureError::InvalidEncoding))?;
+
+        if str_member(&object, "algorithm")? != "Ed25519" ||
+           str_member(&object, "encoding")? != VERIFIER_ENCODING {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let bytes: Vec<u8> = base64::decode_config(str_member(&object, "public_key")?,
+                                                   base64::URL_SAFE_NO_PAD)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_bytes(&bytes).or(Err(SignatureError::InvalidEncoding))?;
+
+        if str_member(&object, "key_id")? != verifier_key_id(&public) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        Ok(public)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The first test vector from RFC 8032 §7.1.
+    static SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    static PUBLIC: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+
+    #[test]
+    fn json_roundtrip() {
+        let json: String = format!("{{\"secret\":\"{}\",\"public\":\"{}\"}}", SECRET, PUBLIC);
+        let keypair: Keypair = Keypair::from_json(&json).unwrap();
+
+        assert_eq!(keypair.public.as_bytes().to_hex(), PUBLIC);
+        assert_eq!(keypair.to_json().unwrap(), json);
+    }
+
+    #[test]
+    fn json_inconsistent_keypair_is_rejected() {
+        let json: String = format!("{{\"secret\":\"{}\",\"public\":\"{}\"}}", PUBLIC, PUBLIC);
+
+        assert_eq!(Keypair::from_json(&json).unwrap_err(), SignatureError::InconsistentKeypair);
+    }
+
+    static VERIFIER_JSON: &str = "{\"algorithm\":\"Ed25519\",\
+        \"public_key\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\",\
+        \"key_id\":\"21fe31df\",\"encoding\":\"raw-compressed-point\"}";
+
+    #[test]
+    fn verifier_json_roundtrip() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(PUBLIC).unwrap();
+        let public: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+
+        assert_eq!(public.to_verifier_json(), VERIFIER_JSON);
+        assert_eq!(PublicKey::from_verifier_json(VERIFIER_JSON), Ok(public));
+    }
+
+    #[test]
+    fn verifier_json_malformed_is_rejected() {
+        let wrong_key_id: String = VERIFIER_JSON.replace("21fe31df", "21fe31de");
+        let wrong_algorithm: String = VERIFIER_JSON.replace("Ed25519", "Ed448");
+        let wrong_encoding: String = VERIFIER_JSON.replace("raw-compressed-point", "raw");
+        let truncated: String = VERIFIER_JSON.replace("HURo", "HU");
+
+        for json in [wrong_key_id, wrong_algorithm, wrong_encoding, truncated].iter() {
+            assert_eq!(PublicKey::from_verifier_json(json), Err(SignatureError::InvalidEncoding));
+        }
+        assert_eq!(PublicKey::from_verifier_json("{}"), Err(SignatureError::InvalidEncoding));
+    }
+
+    #[test]
+    fn json_malformed_is_rejected() {
+        let short: String = format!("{{\"secret\":\"{}\",\"public\":\"{}\"}}", SECRET, &PUBLIC[2..]);
+        let missing: String = format!("{{\"secret\":\"{}\"}}", SECRET);
+
+        assert_eq!(Keypair::from_json(&short).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_json(&missing).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_json("[]").unwrap_err(), SignatureError::InvalidEncoding);
+    }
+}
@@ -0,0 +1,256 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! JSON Web Key encodings of keys, as specified for ed25519 in RFC8037.
+
+use std::string::String;
+use std::vec::Vec;
+
+use base64;
+
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+use serde_json;
+use serde_json::Value;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use errors::SignatureError;
+
+/// A borrowed `PublicKey`, and perhaps its `SecretKey`, serialised as a JWK.
+struct Jwk<'a>(&'a PublicKey, Option<&'a SecretKey>);
+
+impl<'a> Serialize for Jwk<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut state = serializer.serialize_struct("Jwk", if self.1.is_some() { 4 } else { 3 })?;
+
+        state.serialize_field("kty", "OKP")?;
+        state.serialize_field("crv", "Ed25519")?;
+        if let Some(secret) = self.1 {
+            state.serialize_field("d", &base64::encode_config(secret.as_bytes(), base64::URL_SAFE_NO_PAD))?;
+        }
+        state.serialize_field("x", &base64::encode_config(self.0.as_bytes(), base64::URL_SAFE_NO_PAD))?;
+        state.end()
+    }
+}
+
+/// Parse a JWK, checking that it is an ed25519 key.
+///
+/// # Returns
+///
+/// The JSON object, or `SignatureError::InvalidEncoding` if `json` is not a
+/// JSON object, or `SignatureError::InvalidKeyLength` if its `kty` or `crv`
+/// is not that of an ed25519 key.
+fn parse_jwk(json: &str) -> Result<Value, SignatureError> {
+    let object: Value = serde_json::from_str(json).or(Err(SignatureError::InvalidEncoding))?;
+
+    if !object.is_object() {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    if object.get("kty").and_then(Value::as_str) != Some("OKP") ||
+       object.get("crv").and_then(Value::as_str) != Some("Ed25519") {
+        return Err(SignatureError::InvalidKeyLength);
+    }
+    Ok(object)
+}
+
+/// Decode the base64url string `member` of the JWK `object`.
+fn base64url_member(object: &Value, member: &str) -> Result<Vec<u8>, SignatureError> {
+    let encoded: &str = object.get(member).and_then(Value::as_str).ok_or(SignatureError::InvalidEncoding)?;
+
+    base64::decode_config(encoded, base64::URL_SAFE_NO_PAD).or(Err(SignatureError::InvalidEncoding))
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` as a JWK, as specified for ed25519 in RFC8037.
+    ///
+    /// The output is a JSON object with a `kty` of `"OKP"`, a `crv` of
+    /// `"Ed25519"`, and the 32-byte compressed `PublicKey` in unpadded
+    /// base64url as `x`:
+    ///
+    /// ```text
+    /// {"kty":"OKP","crv":"Ed25519","x":"<base64url>"}
+    /// ```
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "jwk")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let jwk: &str = "{\"kty\":\"OKP\",\"crv\":\"Ed25519\",\
+    ///                   \"x\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\"}";
+    /// let public: PublicKey = PublicKey::from_jwk(jwk).unwrap();
+    ///
+    /// assert_eq!(public.to_jwk(), jwk);
+    /// # }
+    /// # #[cfg(not(feature = "jwk"))]
+    /// # fn main() { }
+    /// ```
+    pub fn to_jwk(&self) -> String {
+        // Serialising a struct of strings to a String cannot fail.
+        serde_json::to_string(&Jwk(self, None)).unwrap()
+    }
+
+    /// Decode a `PublicKey` from a public or private JWK, in the format of
+    /// `PublicKey::to_jwk()`.  Other members, such as `kid` or `use`, are
+    /// ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `PublicKey`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the JWK is not an ed25519 key,
+    ///   or
+    /// * `SignatureError::InvalidEncoding` if it is malformed, or its `x` is
+    ///   not 32 bytes of base64url encoding a valid public key.
+    pub fn from_jwk(json: &str) -> Result<PublicKey, SignatureError> {
+        let object: Value = parse_jwk(json)?;
+
+        PublicKey::from_bytes(&base64url_member(&object, "x")?).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+impl SecretKey {
+    /// Encode this `SecretKey` as a private JWK, in the format of
+    /// `Keypair::to_jwk()`, including the public key derived from it with
+    /// SHA-512.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_jwk(&self) -> String {
+        serde_json::to_string(&Jwk(&PublicKey::from_secret::<Sha512>(self), Some(self))).unwrap()
+    }
+
+    /// Decode the `SecretKey` of a private JWK.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `SecretKey`, or an error as
+    /// for `Keypair::from_jwk()`.
+    pub fn from_jwk(json: &str) -> Result<SecretKey, SignatureError> {
+        Keypair::from_jwk(json).map(|keypair| keypair.secret)
+    }
+}
+
+impl Keypair {
+    /// Encode this `Keypair` as a private JWK, as specified for ed25519 in
+    /// RFC8037.
+    ///
+    /// The output has the members of `PublicKey::to_jwk()`, and the 32-byte
+    /// `SecretKey` in unpadded base64url as `d`:
+    ///
+    /// ```text
+    /// {"kty":"OKP","crv":"Ed25519","d":"<base64url>","x":"<base64url>"}
+    /// ```
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "jwk")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// // The example from RFC8037 §A.1.
+    /// let jwk: &str = "{\"kty\":\"OKP\",\"crv\":\"Ed25519\",\
+    ///                   \"d\":\"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A\",\
+    ///                   \"x\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\"}";
+    /// let keypair: Keypair = Keypair::from_jwk(jwk).unwrap();
+    ///
+    /// assert_eq!(keypair.to_jwk(), jwk);
+    /// # }
+    /// # #[cfg(not(feature = "jwk"))]
+    /// # fn main() { }
+    /// ```
+    pub fn to_jwk(&self) -> String {
+        serde_json::to_string(&Jwk(&self.public, Some(&self.secret))).unwrap()
+    }
+
+    /// Decode a `Keypair` from a private JWK, in the format of
+    /// `Keypair::to_jwk()`.  Other members, such as `kid` or `use`, are
+    /// ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Keypair`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the JWK is not an ed25519 key,
+    /// * `SignatureError::InvalidEncoding` if it is malformed, or is a public
+    ///   JWK with no `d`, or
+    /// * `SignatureError::InconsistentKeypair` if its `x` is not the public
+    ///   key derived from its `d`.
+    pub fn from_jwk(json: &str) -> Result<Keypair, SignatureError> {
+        let object: Value = parse_jwk(json)?;
+        let secret: SecretKey = SecretKey::from_bytes(&base64url_member(&object, "d")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_bytes(&base64url_member(&object, "x")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if PublicKey::from_secret::<Sha512>(&secret) != public {
+            return Err(SignatureError::InconsistentKeypair);
+        }
+        Ok(Keypair{ secret, public })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    // The example private key from RFC8037 §A.1.
+    static PRIVATE_JWK: &str = "{\"kty\":\"OKP\",\"crv\":\"Ed25519\",\
+        \"d\":\"nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A\",\
+        \"x\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\"}";
+
+    // The corresponding public key from RFC8037 §A.2.
+    static PUBLIC_JWK: &str = "{\"kty\":\"OKP\",\"crv\":\"Ed25519\",\
+        \"x\":\"11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo\"}";
+
+    #[test]
+    fn jwk_roundtrip() {
+        let keypair: Keypair = Keypair::from_jwk(PRIVATE_JWK).unwrap();
+        let random: Keypair = keypair_for_testing("jwk");
+
+        assert_eq!(keypair.to_jwk(), PRIVATE_JWK);
+        assert_eq!(keypair.secret.to_jwk(), PRIVATE_JWK);
+        assert_eq!(keypair.public.to_jwk(), PUBLIC_JWK);
+        assert_eq!(PublicKey::from_jwk(PUBLIC_JWK), Ok(keypair.public));
+        assert_eq!(PublicKey::from_jwk(PRIVATE_JWK), Ok(keypair.public));
+        assert_eq!(SecretKey::from_jwk(PRIVATE_JWK).unwrap().as_bytes(), keypair.secret.as_bytes());
+        assert_eq!(Keypair::from_jwk(&random.to_jwk()).unwrap().to_jwk(), random.to_jwk());
+    }
+
+    #[test]
+    fn jwk_malformed_is_rejected() {
+        let x25519: String = PUBLIC_JWK.replace("Ed25519", "X25519");
+        let ec: String = PUBLIC_JWK.replace("OKP", "EC");
+        let truncated: String = PUBLIC_JWK.replace("HURo", "HU");
+        let inconsistent: String = PRIVATE_JWK.replace("nWGx", "nWGy");
+
+        assert_eq!(Keypair::from_jwk(PUBLIC_JWK).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_jwk(&inconsistent).unwrap_err(), SignatureError::InconsistentKeypair);
+        assert_eq!(PublicKey::from_jwk(&x25519), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_jwk(&ec), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_jwk(&truncated), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_jwk("[]"), Err(SignatureError::InvalidEncoding));
+    }
+}
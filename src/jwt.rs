@@ -0,0 +1,232 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding of signatures for use in JSON Web Tokens.
+
+use std::string::String;
+use std::vec::Vec;
+
+use base64;
+use serde_json;
+use serde_json::Value;
+use sha2::Sha512;
+
+use ed25519::PublicKey;
+use ed25519::Signature;
+use ed25519::SIGNATURE_LENGTH;
+use errors::SignatureError;
+
+/// The length of a base64url encoded `Signature`, without padding.
+pub const JWT_SIGNATURE_LENGTH: usize = 86;
+
+impl Signature {
+    /// Encode this `Signature` as unpadded base64url (RFC 4648 §5), as used
+    /// in the signature segment of a JSON Web Token.
+    ///
+    /// # Returns
+    ///
+    /// An 86-character `String`.
+    pub fn as_jwt_base64url(&self) -> String {
+        base64::encode_config(&self.to_bytes()[..], base64::URL_SAFE_NO_PAD)
+    }
+
+    /// Decode a `Signature` from the unpadded base64url encoding used in JSON
+    /// Web Tokens.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Signature`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the string is not exactly
+    /// 86 characters of canonical, unpadded base64url.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "jwt")]
+    /// # fn main() {
+    /// use ed25519_dalek::Signature;
+    ///
+    /// let encoded: &str = "hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6d\
+    ///                      WbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+    /// let signature: Signature = Signature::from_jwt_base64url(encoded).unwrap();
+    ///
+    /// assert_eq!(signature.as_jwt_base64url(), encoded);
+    /// # }
+    /// # #[cfg(not(feature = "jwt"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_jwt_base64url(encoded: &str) -> Result<Signature, SignatureError> {
+        if encoded.len() != JWT_SIGNATURE_LENGTH {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let bytes: Vec<u8> = base64::decode_config(encoded, base64::URL_SAFE_NO_PAD)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if bytes.len() != SIGNATURE_LENGTH {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let signature: Signature = Signature::from_bytes(&bytes)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        // Reject encodings whose unused trailing bits are set, so that each
+        // signature has exactly one encoding.
+        if signature.as_jwt_base64url() != encoded {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        Ok(signature)
+    }
+}
+
+/// Decode a base64url segment of a JSON Web Token as a JSON value.
+fn json_segment(segment: &str) -> Result<Value, SignatureError> {
+    let bytes: Vec<u8> = base64::decode_config(segment, base64::URL_SAFE_NO_PAD)
+        .or(Err(SignatureError::InvalidEncoding))?;
+
+    serde_json::from_slice(&bytes).or(Err(SignatureError::InvalidEncoding))
+}
+
+/// Verify a JSON Web Token in the JWS Compact Serialization (RFC 7515 §7.1),
+/// signed with the `EdDSA` algorithm of RFC 8037, under the `public_key`.
+///
+/// The header must be a JSON object whose `alg` is `"EdDSA"`, so that a token
+/// made for another algorithm is never checked as an ed25519 signature.  No
+/// claims (such as `exp`) are checked; that is for the caller to do with the
+/// payload.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the payload, parsed as JSON, or whose
+/// error value is
+///
+/// * `SignatureError::InvalidSignature` if the signature does not verify, or
+/// * `SignatureError::InvalidEncoding` if the token does not have three
+///   segments, any segment is not base64url, the header or payload is not
+///   JSON, or the header's `alg` is not `"EdDSA"`.
+pub fn verify_jwt(token: &str, public_key: &PublicKey) -> Result<Value, SignatureError> {
+    let segments: Vec<&str> = token.split('.').collect();
+
+    if segments.len() != 3 {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let header: Value = json_segment(segments[0])?;
+
+    if header["alg"] != "EdDSA" {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let payload: Value = json_segment(segments[1])?;
+    let signature: Signature = Signature::from_jwt_base64url(segments[2])?;
+    let signed: usize = segments[0].len() + 1 + segments[1].len();
+
+    public_key.verify::<Sha512>(&token.as_bytes()[..signed], &signature)?;
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use sha2::Sha512;
+    use ed25519::PublicKey;
+    use super::*;
+
+    // The signature from RFC 8037 §A.4, "Ed25519 Signing".
+    static ENCODED: &str = "hgyY0il_MGCjP0JzlnLWG1PPOt7-09PGcvMg3AIbQR6d\
+                            WbhijcNR4ki4iylGjg5BhVsPt9g7sVvpAr_MuM0KAg";
+
+    #[test]
+    fn jwt_base64url_roundtrip() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let public_key: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+        let signature: Signature = Signature::from_jwt_base64url(ENCODED).unwrap();
+
+        assert!(public_key.verify::<Sha512>(
+            b"eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc", &signature).is_ok());
+        assert_eq!(signature.as_jwt_base64url(), ENCODED);
+    }
+
+    #[test]
+    fn jwt_base64url_bad_length_is_rejected() {
+        assert_eq!(Signature::from_jwt_base64url(&ENCODED[..85]).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+
+    #[test]
+    fn jwt_base64url_bad_characters_are_rejected() {
+        let mut encoded: String = String::from(ENCODED);
+
+        encoded.replace_range(..1, "+");
+        assert_eq!(Signature::from_jwt_base64url(&encoded).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+
+    #[test]
+    fn jwt_base64url_noncanonical_is_rejected() {
+        // The final character of ENCODED is 'g', i.e. 0b100000, of which only
+        // the top two bits are used; 'h' sets an unused trailing bit.
+        let mut encoded: String = String::from(&ENCODED[..85]);
+
+        encoded.push('h');
+        assert_eq!(Signature::from_jwt_base64url(&encoded).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+
+        fn token_for_testing(header: &str, payload: &str) -> (String, PublicKey) {
+        let keypair: ::ed25519::Keypair = ::ed25519::keypair_for_testing("jwt");
+        let mut token: String = base64::encode_config(header, base64::URL_SAFE_NO_PAD);
+
+        token.push('.');
+        token.push_str(&base64::encode_config(payload, base64::URL_SAFE_NO_PAD));
+        let signature: Signature = keypair.sign::<Sha512>(token.as_bytes());
+
+        token.push('.');
+        token.push_str(&signature.as_jwt_base64url());
+        (token, keypair.public)
+    }
+
+        #[test]
+    fn verify_jwt_returns_payload() {
+        let (token, public_key) = token_for_testing(r#"{"alg":"EdDSA","typ":"JWT"}"#,
+                                                    r#"{"sub":"1234567890","admin":true}"#);
+        let payload: Value = verify_jwt(&token, &public_key).unwrap();
+
+        assert_eq!(payload["sub"], "1234567890");
+        assert_eq!(payload["admin"], true);
+    }
+
+        #[test]
+    fn verify_jwt_bad_signature_is_rejected() {
+        let (token, _) = token_for_testing(r#"{"alg":"EdDSA"}"#, r#"{"sub":"alice"}"#);
+        let other: PublicKey = ::ed25519::keypair_for_testing("other").public;
+
+        assert_eq!(verify_jwt(&token, &other).unwrap_err(), SignatureError::InvalidSignature);
+    }
+
+        #[test]
+    fn verify_jwt_other_algorithms_are_rejected() {
+        let (token, public_key) = token_for_testing(r#"{"alg":"HS256"}"#, r#"{"sub":"alice"}"#);
+
+        assert_eq!(verify_jwt(&token, &public_key).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(verify_jwt("a.b", &public_key).unwrap_err(), SignatureError::InvalidEncoding);
+    }
+
+    // The token of RFC 8037 §A.4 is validly signed, but its payload is not
+    // JSON, so it is not a JSON Web Token.
+        #[test]
+    fn verify_jwt_non_json_payload_is_rejected() {
+        let public_bytes: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let public_key: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+        let token: String = format!("eyJhbGciOiJFZERTQSJ9.RXhhbXBsZSBvZiBFZDI1NTE5IHNpZ25pbmc.{}",
+                                    ENCODED);
+
+        assert_eq!(verify_jwt(&token, &public_key).unwrap_err(), SignatureError::InvalidEncoding);
+    }
+}
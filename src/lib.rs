@@ -56,7 +56,8 @@
 //! ```
 //!
 //! As well as to verify that this is, indeed, a valid signature on
-//! that `message`:
+//! that `message`.  On failure, the `SignatureError` says why, e.g. whether
+//! the equation did not hold or the signature was malformed:
 //!
 //! ```
 //! # extern crate rand;
@@ -72,9 +73,7 @@
 //! # let keypair: Keypair = Keypair::generate::<Sha512>(&mut cspring);
 //! # let message: &[u8] = "This is a test of the tsunami alert system.".as_bytes();
 //! # let signature: Signature = keypair.sign::<Sha512>(message);
-//! let verified: bool = keypair.verify::<Sha512>(message, &signature);
-//!
-//! assert!(verified);
+//! assert!(keypair.verify::<Sha512>(message, &signature).is_ok());
 //! # }
 //! ```
 //!
@@ -98,9 +97,7 @@
 //! # let signature: Signature = keypair.sign::<Sha512>(message);
 //!
 //! let public_key: PublicKey = keypair.public;
-//! let verified: bool = public_key.verify::<Sha512>(message, &signature);
-//!
-//! assert!(verified);
+//! assert!(public_key.verify::<Sha512>(message, &signature).is_ok());
 //! # }
 //! ```
 //!
@@ -126,11 +123,10 @@
 //! # let message: &[u8] = "This is a test of the tsunami alert system.".as_bytes();
 //! # let signature: Signature = keypair.sign::<Sha512>(message);
 //! # let public_key: PublicKey = keypair.public;
-//! # let verified: bool = public_key.verify::<Sha512>(message, &signature);
 //!
 //! let public_key_bytes: [u8; PUBLIC_KEY_LENGTH] = public_key.to_bytes();
 //! let secret_key_bytes: [u8; SECRET_KEY_LENGTH] = keypair.secret.to_bytes();
-//! let keypair_bytes:    [u8; KEYPAIR_LENGTH]    = keypair.to_bytes();
+//! let keypair_bytes:    [u8; KEYPAIR_LENGTH]    = keypair.to_raw_bytes();
 //! let signature_bytes:  [u8; SIGNATURE_LENGTH]  = signature.to_bytes();
 //! # }
 //! ```
@@ -152,7 +148,7 @@
 //! # let signature_orig: Signature = keypair_orig.sign::<Sha512>(message);
 //! # let public_key_bytes: [u8; PUBLIC_KEY_LENGTH] = keypair_orig.public.to_bytes();
 //! # let secret_key_bytes: [u8; SECRET_KEY_LENGTH] = keypair_orig.secret.to_bytes();
-//! # let keypair_bytes:    [u8; KEYPAIR_LENGTH]    = keypair_orig.to_bytes();
+//! # let keypair_bytes:    [u8; KEYPAIR_LENGTH]    = keypair_orig.to_raw_bytes();
 //! # let signature_bytes:  [u8; SIGNATURE_LENGTH]  = signature_orig.to_bytes();
 //! #
 //! let public_key: PublicKey = PublicKey::from_bytes(&public_key_bytes)?;
@@ -200,7 +196,6 @@
 //! # let message: &[u8] = "This is a test of the tsunami alert system.".as_bytes();
 //! # let signature: Signature = keypair.sign::<Sha512>(message);
 //! # let public_key: PublicKey = keypair.public;
-//! # let verified: bool = public_key.verify::<Sha512>(message, &signature);
 //!
 //! let encoded_public_key: Vec<u8> = serialize(&public_key, Infinite).unwrap();
 //! let encoded_signature: Vec<u8> = serialize(&signature, Infinite).unwrap();
@@ -234,7 +229,6 @@
 //! let message: &[u8] = "This is a test of the tsunami alert system.".as_bytes();
 //! # let signature: Signature = keypair.sign::<Sha512>(message);
 //! # let public_key: PublicKey = keypair.public;
-//! # let verified: bool = public_key.verify::<Sha512>(message, &signature);
 //! # let encoded_public_key: Vec<u8> = serialize(&public_key, Infinite).unwrap();
 //! # let encoded_signature: Vec<u8> = serialize(&signature, Infinite).unwrap();
 //! let decoded_public_key: PublicKey = deserialize(&encoded_public_key).unwrap();
@@ -243,9 +237,7 @@
 //! # assert_eq!(public_key, decoded_public_key);
 //! # assert_eq!(signature, decoded_signature);
 //! #
-//! let verified: bool = decoded_public_key.verify::<Sha512>(&message, &decoded_signature);
-//!
-//! assert!(verified);
+//! assert!(decoded_public_key.verify::<Sha512>(&message, &decoded_signature).is_ok());
 //! # }
 //! # #[cfg(not(feature = "serde"))]
 //! # fn main() {}
@@ -257,6 +249,10 @@
 #![allow(unused_features)]
 #![deny(missing_docs)] // refuse to compile if documentation is missing
 
+#[cfg(all(feature = "zip215", feature = "strict-rfc8032"))]
+compile_error!("The \"zip215\" and \"strict-rfc8032\" features select incompatible \
+                verification rules, and cannot both be enabled.");
+
 #[macro_use]
 extern crate arrayref;
 extern crate curve25519_dalek;
@@ -267,14 +263,17 @@ extern crate subtle;
 #[cfg(feature = "std")]
 extern crate rand;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "std"))]
 #[macro_use]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(any(test, feature = "sha2"))]
 extern crate sha2;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "hex"))]
 extern crate hex;
 
 #[cfg(all(test, feature = "bench"))]
@@ -283,10 +282,324 @@ extern crate test;
 #[cfg(feature = "serde")]
 extern crate serde;
 
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+
+#[cfg(feature = "p12")]
+extern crate p12;
+
+#[cfg(feature = "aes-gcm")]
+extern crate aes_gcm;
+
+#[cfg(feature = "signature")]
+extern crate signature;
+
+#[cfg(feature = "sha2_0_10")]
+extern crate sha2_0_10;
+
+#[cfg(feature = "bs58")]
+extern crate bs58;
+
+#[cfg(feature = "libc")]
+extern crate libc;
+
+#[cfg(feature = "tiny-keccak")]
+extern crate tiny_keccak;
+
+#[cfg(feature = "base64")]
+extern crate base64;
+
+#[cfg(feature = "multibase")]
+extern crate multibase;
+
+#[cfg(feature = "scrypt")]
+extern crate scrypt;
+
+#[cfg(feature = "crypto_secretbox")]
+extern crate crypto_secretbox;
+
+#[cfg(feature = "prost")]
+extern crate prost;
+
+#[cfg(feature = "toml")]
+extern crate toml;
+
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
+
+#[cfg(feature = "argon2")]
+extern crate argon2;
+
+#[cfg(feature = "rayon")]
+extern crate rayon;
+
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[cfg(feature = "wasm")]
+extern crate js_sys;
+
+#[cfg(all(test, target_arch = "wasm32"))]
+extern crate wasm_bindgen_test;
+
 #[cfg(all(test, feature = "serde"))]
 extern crate bincode;
 
+#[cfg(all(test, feature = "hkdf"))]
+extern crate sha1;
+
 mod ed25519;
+mod errors;
+
+#[cfg(feature = "std")]
+mod aggregate;
+
+#[cfg(feature = "std")]
+mod backup;
+
+#[cfg(feature = "base64-keys")]
+mod base64_keys;
+
+#[cfg(feature = "std")]
+mod batch;
+
+mod blinding;
+
+#[cfg(feature = "std")]
+pub mod certificate;
+
+#[cfg(feature = "cosign")]
+mod cosign;
+
+#[cfg(feature = "did-key")]
+mod did_key;
+
+#[cfg(feature = "std")]
+mod dkg;
+
+#[cfg(all(feature = "x25519", feature = "aes-gcm"))]
+mod ecies;
+
+#[cfg(feature = "ethereum")]
+mod ethereum;
+
+mod expiring;
+
+mod hex_encoding;
+
+#[cfg(feature = "hkdf")]
+mod hmac;
+
+mod ietf;
+
+#[cfg(all(feature = "hkdf", feature = "sha2"))]
+mod integrity;
+
+#[cfg(feature = "json")]
+mod json;
+
+#[cfg(feature = "jwk")]
+mod jwk;
+
+#[cfg(feature = "jwt")]
+mod jwt;
+
+#[cfg(feature = "libp2p")]
+mod libp2p;
+
+#[cfg(all(feature = "locked-memory", any(target_os = "linux", target_os = "macos")))]
+mod locked_memory;
+
+#[cfg(feature = "std")]
+pub mod migration;
+
+#[cfg(all(feature = "multibase", feature = "std"))]
+mod multibase_encoding;
+
+#[cfg(feature = "multi-sig")]
+mod multi_sig;
+
+#[cfg(feature = "multicodec")]
+mod multicodec;
+
+#[cfg(feature = "rayon")]
+mod parallel;
+
+#[cfg(feature = "argon2")]
+mod password;
+
+#[cfg(feature = "pem")]
+mod pem;
+
+#[cfg(feature = "pinned")]
+mod pinned;
+
+#[cfg(feature = "pkcs12")]
+mod pkcs12;
+
+#[cfg(feature = "pkcs8")]
+mod pkcs8;
+
+#[cfg(feature = "precomputed-tables")]
+mod precomputed;
+
+mod proof;
+
+#[cfg(all(feature = "prost", feature = "std"))]
+mod protobuf;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+mod registry;
+
+#[cfg(feature = "std")]
+mod replay;
+
+#[cfg(feature = "ring-signatures")]
+mod ring_signature;
+
+#[cfg(feature = "rustcrypto")]
+mod rustcrypto;
+
+#[cfg(feature = "std")]
+mod signable;
+
+#[cfg(feature = "sha2")]
+mod signing_key;
+
+#[cfg(feature = "solana")]
+mod solana;
+
+#[cfg(feature = "ssh")]
+mod ssh;
+
+#[cfg(all(unix, feature = "ssh-agent"))]
+mod ssh_agent;
+
+#[cfg(target_has_atomic = "64")]
+mod stateful;
+
+#[cfg(feature = "std")]
+mod streaming;
+
+#[cfg(all(feature = "bench", feature = "std", feature = "sha2"))]
+mod stress;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+mod suite;
+
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+
+#[cfg(feature = "tezos")]
+mod tezos;
+
+#[cfg(feature = "threshold")]
+mod threshold;
+
+#[cfg(feature = "toml")]
+mod toml_keys;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+mod vote;
+
+#[cfg(feature = "std")]
+mod wire;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+
+#[cfg(feature = "zip215")]
+mod zip215;
 
 // Export everything public in ed25519.
 pub use ed25519::*;
+pub use errors::*;
+
+#[cfg(feature = "std")]
+pub use aggregate::*;
+
+#[cfg(feature = "std")]
+pub use batch::*;
+
+pub use blinding::*;
+
+#[cfg(feature = "std")]
+pub use dkg::*;
+
+#[cfg(all(feature = "x25519", feature = "aes-gcm"))]
+pub use ecies::*;
+
+#[cfg(feature = "ethereum")]
+pub use ethereum::*;
+
+pub use expiring::*;
+
+pub use ietf::*;
+
+#[cfg(all(feature = "hkdf", feature = "sha2"))]
+pub use integrity::*;
+
+#[cfg(feature = "jwt")]
+pub use jwt::*;
+
+#[cfg(all(feature = "locked-memory", any(target_os = "linux", target_os = "macos")))]
+pub use locked_memory::*;
+
+#[cfg(feature = "multi-sig")]
+pub use multi_sig::*;
+
+#[cfg(feature = "pinned")]
+pub use pinned::*;
+
+#[cfg(feature = "pkcs12")]
+pub use pkcs12::*;
+
+#[cfg(feature = "precomputed-tables")]
+pub use precomputed::*;
+
+pub use proof::*;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+pub use registry::*;
+
+#[cfg(feature = "std")]
+pub use replay::*;
+
+#[cfg(feature = "ring-signatures")]
+pub use ring_signature::*;
+
+#[cfg(feature = "std")]
+pub use signable::*;
+
+#[cfg(feature = "sha2")]
+pub use signing_key::*;
+
+#[cfg(target_has_atomic = "64")]
+pub use stateful::*;
+
+#[cfg(all(unix, feature = "ssh-agent"))]
+pub use ssh_agent::*;
+
+#[cfg(feature = "std")]
+pub use streaming::*;
+
+#[cfg(all(feature = "bench", feature = "std", feature = "sha2"))]
+pub use stress::*;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+pub use suite::*;
+
+#[cfg(feature = "threshold")]
+pub use threshold::*;
+
+#[cfg(all(feature = "std", feature = "sha2"))]
+pub use vote::*;
+
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+
+#[cfg(feature = "std")]
+pub use wire::*;
@@ -0,0 +1,146 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! The protobuf encoding of libp2p peer identity keys.
+//!
+//! libp2p encodes a private key as the message
+//!
+//! ```text
+//! message PrivateKey {
+//!     required KeyType Type = 1;
+//!     required bytes Data = 2;
+//! }
+//! ```
+//!
+//! where the `KeyType` of ed25519 is 1, and its `Data` is the 32-byte secret
+//! key followed by the 32-byte public key.
+
+use std::vec::Vec;
+
+use prost::bytes::Buf;
+use prost::encoding;
+use prost::encoding::DecodeContext;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use ed25519::{KEYPAIR_LENGTH, SECRET_KEY_LENGTH};
+use errors::SignatureError;
+
+/// The tag of the `Type` field.
+const KEY_TYPE_TAG: u32 = 1;
+
+/// The tag of the `Data` field.
+const DATA_TAG: u32 = 2;
+
+/// The libp2p `KeyType` of ed25519 keys.
+const KEY_TYPE_ED25519: i32 = 1;
+
+impl Keypair {
+    /// Encode this `Keypair` as a libp2p `PrivateKey` protobuf message.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_libp2p_protobuf(&self) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::with_capacity(KEYPAIR_LENGTH);
+        let mut encoded: Vec<u8> = Vec::with_capacity(KEYPAIR_LENGTH + 4);
+
+        data.extend_from_slice(self.secret.as_bytes());
+        data.extend_from_slice(self.public.as_bytes());
+        encoding::int32::encode(KEY_TYPE_TAG, &KEY_TYPE_ED25519, &mut encoded);
+        encoding::bytes::encode(DATA_TAG, &data, &mut encoded);
+        encoded
+    }
+
+    /// Decode a `Keypair` from a libp2p `PrivateKey` protobuf message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the key is not an ed25519 key,
+    /// * `SignatureError::InvalidEncoding` if the message is malformed, either
+    ///   field is missing, or the `Data` is not 64 bytes, or
+    /// * `SignatureError::InconsistentKeypair` if the public key is not the
+    ///   one derived from the secret key.
+    pub fn from_libp2p_protobuf(mut bytes: &[u8]) -> Result<Keypair, SignatureError> {
+        let mut key_type: Option<i32> = None;
+        let mut data: Option<Vec<u8>> = None;
+
+        while bytes.has_remaining() {
+            let (tag, wire_type) = encoding::decode_key(&mut bytes).or(Err(SignatureError::InvalidEncoding))?;
+            let merged = match tag {
+                KEY_TYPE_TAG => encoding::int32::merge(wire_type, key_type.get_or_insert(0),
+                                                       &mut bytes, DecodeContext::default()),
+                DATA_TAG     => encoding::bytes::merge(wire_type, data.get_or_insert_with(Vec::new),
+                                                       &mut bytes, DecodeContext::default()),
+                _            => encoding::skip_field(wire_type, tag, &mut bytes, DecodeContext::default()),
+            };
+            merged.or(Err(SignatureError::InvalidEncoding))?;
+        }
+        match key_type {
+            Some(KEY_TYPE_ED25519) => (),
+            Some(_)                => return Err(SignatureError::InvalidKeyLength),
+            None                   => return Err(SignatureError::InvalidEncoding),
+        }
+        let data: Vec<u8> = data.ok_or(SignatureError::InvalidEncoding)?;
+
+        if data.len() != KEYPAIR_LENGTH {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let secret: SecretKey = SecretKey::from_bytes(&data[..SECRET_KEY_LENGTH])
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_bytes(&data[SECRET_KEY_LENGTH..])
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if PublicKey::from_secret::<Sha512>(&secret) != public {
+            return Err(SignatureError::InconsistentKeypair);
+        }
+        Ok(Keypair{ secret, public })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    // The ed25519 private key test vector from the libp2p peer ID spec.
+    static PRIVATE_KEY: &str = "080112407e0830617c4a7de83925dfb2694556b12936c477a0e1feb2e148ec9da\
+                               60fee7d1ed1e8fae2c4a144b8be8fd4b47bf3d3b34b871c3cacf6010f0e42d474fce27e";
+
+    #[test]
+    fn libp2p_protobuf_roundtrip() {
+        let encoded: Vec<u8> = FromHex::from_hex(PRIVATE_KEY).unwrap();
+        let keypair: Keypair = Keypair::from_libp2p_protobuf(&encoded).unwrap();
+        let random: Keypair = keypair_for_testing("libp2p");
+
+        assert_eq!(&keypair.public.as_bytes()[..], &encoded[36..]);
+        assert_eq!(keypair.to_libp2p_protobuf(), encoded);
+        assert_eq!(Keypair::from_libp2p_protobuf(&random.to_libp2p_protobuf()).unwrap().public, random.public);
+    }
+
+    #[test]
+    fn libp2p_protobuf_malformed_is_rejected() {
+        let encoded: Vec<u8> = FromHex::from_hex(PRIVATE_KEY).unwrap();
+        let mut secp256k1: Vec<u8> = encoded.clone();
+        let mut inconsistent: Vec<u8> = encoded.clone();
+
+        secp256k1[1] = 2;
+        inconsistent[4] ^= 1;
+
+        assert_eq!(Keypair::from_libp2p_protobuf(&secp256k1).unwrap_err(), SignatureError::InvalidKeyLength);
+        assert_eq!(Keypair::from_libp2p_protobuf(&inconsistent).unwrap_err(), SignatureError::InconsistentKeypair);
+        assert_eq!(Keypair::from_libp2p_protobuf(&encoded[..67]).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_libp2p_protobuf(&encoded[2..]).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_libp2p_protobuf(&encoded[..2]).unwrap_err(), SignatureError::InvalidEncoding);
+    }
+}
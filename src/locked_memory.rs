@@ -0,0 +1,179 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Secret keys kept in a page of memory which is inaccessible except while
+//! signing.
+
+use core::ptr;
+
+use std::io;
+
+use libc;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{ExpandedSecretKey, PublicKey, SecretKey, Signature};
+use ed25519::SECRET_KEY_LENGTH;
+
+/// Overwrite `bytes` with zeroes, in a way which the compiler will not elide.
+fn zero(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
+
+/// Change the protection of the page at `page` to `protection`.
+fn protect(page: *mut libc::c_void, page_size: usize, protection: libc::c_int) {
+    // This cannot fail for a page which we mapped ourselves, so a failure
+    // means that the process is in no state to handle secrets.
+    if unsafe { libc::mprotect(page, page_size, protection) } != 0 {
+        panic!("mprotect failed: {}", io::Error::last_os_error());
+    }
+}
+
+/// An ed25519 secret key stored in its own page of memory, which is locked
+/// into RAM (so that it is never written to swap), and which may not be read
+/// at all except for the duration of a call to `sign()`.
+///
+/// A stray read of the page, e.g. through a buffer overrun elsewhere in the
+/// process, crashes the process rather than leaking the key.  The page is
+/// zeroed and unmapped when the `LockedMemoryKey` is dropped.
+///
+/// # Warning
+///
+/// This narrows, but does not close, the window in which the key is exposed:
+/// while signing, the key and its expansion are copied onto the stack (they
+/// are zeroed afterwards), and a debugger attached to the process, or the
+/// root user, can always change the page's protection and read it.
+#[derive(Debug)]
+pub struct LockedMemoryKey {
+    page: *mut libc::c_void,
+    page_size: usize,
+    /// The public key corresponding to the locked secret key.
+    pub public: PublicKey,
+}
+
+// The page is owned exclusively by the `LockedMemoryKey`.  It is not `Sync`,
+// since concurrent calls to `sign()` would race to change its protection.
+unsafe impl Send for LockedMemoryKey {}
+
+/// Makes the page of a `LockedMemoryKey` readable for as long as it lives.
+struct Guard<'a>(&'a LockedMemoryKey);
+
+impl<'a> Guard<'a> {
+    fn new(key: &'a LockedMemoryKey) -> Guard<'a> {
+        protect(key.page, key.page_size, libc::PROT_READ);
+        Guard(key)
+    }
+
+    fn secret_bytes(&self) -> &[u8; SECRET_KEY_LENGTH] {
+        unsafe { &*(self.0.page as *const [u8; SECRET_KEY_LENGTH]) }
+    }
+}
+
+impl<'a> Drop for Guard<'a> {
+    fn drop(&mut self) {
+        protect(self.0.page, self.0.page_size, libc::PROT_NONE);
+    }
+}
+
+impl LockedMemoryKey {
+    /// Move a `secret_key` into a new page of locked memory, deriving its
+    /// public key using the hash function `D`.
+    ///
+    /// The `secret_key` passed in is zeroed, but copies of it which the
+    /// caller made (or which the compiler made when moving it) may remain
+    /// elsewhere in memory.
+    ///
+    /// # Returns
+    ///
+    /// The `LockedMemoryKey`, or an `io::Error` if the page could not be
+    /// allocated or locked, e.g. because it would exceed `RLIMIT_MEMLOCK`.
+    pub fn new<D>(mut secret_key: SecretKey) -> io::Result<LockedMemoryKey>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let page_size: usize = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let page: *mut libc::c_void = unsafe {
+            libc::mmap(ptr::null_mut(), page_size, libc::PROT_READ | libc::PROT_WRITE,
+                       libc::MAP_PRIVATE | libc::MAP_ANON, -1, 0)
+        };
+
+        if page == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { libc::mlock(page, page_size) } != 0 {
+            let error: io::Error = io::Error::last_os_error();
+
+            unsafe { libc::munmap(page, page_size) };
+            return Err(error);
+        }
+        let public: PublicKey = PublicKey::from_secret::<D>(&secret_key);
+
+        unsafe { ptr::copy_nonoverlapping(secret_key.0.as_ptr(), page as *mut u8,
+                                          SECRET_KEY_LENGTH) };
+        zero(&mut secret_key.0);
+        protect(page, page_size, libc::PROT_NONE);
+
+        Ok(LockedMemoryKey{ page, page_size, public })
+    }
+
+    /// Sign a `message` with the locked secret key, using the hash function
+    /// `D`.
+    pub fn sign<D>(&self, message: &[u8]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut secret: SecretKey = {
+            let guard: Guard = Guard::new(self);
+
+            SecretKey(*guard.secret_bytes())
+        };
+        let mut expanded: ExpandedSecretKey = secret.expand::<D>();
+        let signature: Signature = expanded.sign::<D>(message, &self.public);
+
+        zero(&mut secret.0);
+        zero(&mut expanded.nonce);
+        unsafe { ptr::write_volatile(&mut expanded.key.0, [0u8; 32]) };
+        signature
+    }
+}
+
+impl Drop for LockedMemoryKey {
+    fn drop(&mut self) {
+        protect(self.page, self.page_size, libc::PROT_READ | libc::PROT_WRITE);
+        unsafe {
+            zero(::std::slice::from_raw_parts_mut(self.page as *mut u8, self.page_size));
+            libc::munlock(self.page, self.page_size);
+            libc::munmap(self.page, self.page_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn locked_memory_key_signs() {
+        let keypair: Keypair = keypair_for_testing("locked");
+        let secret: SecretKey = SecretKey::from_bytes(keypair.secret.as_bytes()).unwrap();
+        let locked: LockedMemoryKey = LockedMemoryKey::new::<Sha512>(secret).unwrap();
+        let signature: Signature = locked.sign::<Sha512>(b"test message");
+
+        assert_eq!(locked.public, keypair.public);
+        assert_eq!(signature, keypair.sign::<Sha512>(b"test message"));
+        assert!(locked.public.verify::<Sha512>(b"test message", &signature).is_ok());
+
+        // The guard restores the page's protection, so it can be opened again.
+        assert_eq!(locked.sign::<Sha512>(b"test message"), signature);
+    }
+}
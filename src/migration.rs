@@ -0,0 +1,77 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Helpers for systems migrating to ed25519 from other signature schemes.
+//!
+//! # Converting ECDSA keys
+//!
+//! There is no way to "convert" an ECDSA key (over P-256, secp256k1, or any
+//! other curve) into an ed25519 key.  The two are different algorithms over
+//! different groups, and although either secret key is "just" 32 bytes,
+//! feeding the bytes of an ECDSA secret key into `SecretKey::from_bytes()`
+//! yields an unrelated ed25519 key, whose public key no verifier has ever
+//! seen.  Worse, it reuses the same secret material in two schemes.
+//!
+//! Instead, every user must generate a fresh ed25519 keypair, and the new
+//! public key should be vouched for by the old one, e.g. by signing it with
+//! the existing ECDSA key, through whatever channel the system already
+//! trusts:
+//!
+//! ```
+//! extern crate rand;
+//! extern crate sha2;
+//! extern crate ed25519_dalek;
+//!
+//! # fn main() {
+//! use rand::OsRng;
+//! use sha2::Sha512;
+//! use ed25519_dalek::Keypair;
+//!
+//! let mut csprng: OsRng = OsRng::new().unwrap();
+//! let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+//!
+//! // Now sign `keypair.public.to_bytes()` with the old ECDSA key, publish
+//! // both, and retire the ECDSA key once all verifiers have migrated.
+//! # }
+//! ```
+
+use std::string::String;
+
+/// Explain to a user of an ECDSA key why it cannot be converted, and what to
+/// do instead.
+///
+/// # Returns
+///
+/// A human-readable, multi-line message suitable for display in a command
+/// line tool or migration wizard.
+pub fn prompt_ecdsa_user_to_regenerate() -> String {
+    String::from("\
+ECDSA and Ed25519 are different signature algorithms, and an ECDSA key cannot
+be converted into an Ed25519 key.  You must generate a new Ed25519 keypair:
+
+    let mut csprng: OsRng = OsRng::new().unwrap();
+    let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+
+Then sign the new public key with your existing ECDSA key so that others can
+trust it, and retire the ECDSA key once everyone has switched over.
+")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prompt_mentions_regeneration() {
+        let prompt: String = prompt_ecdsa_user_to_regenerate();
+
+        assert!(prompt.contains("cannot\nbe converted"));
+        assert!(prompt.contains("Keypair::generate"));
+    }
+}
@@ -0,0 +1,337 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! n-of-n multi-signatures, in the three-round protocol of Maxwell, Poelstra,
+//! Seurin, and Wuille ("Simple Schnorr Multi-Signatures with Applications to
+//! Bitcoin", 2018).
+//!
+//! The public keys `A_1, …, A_n` of the signers are aggregated into one key,
+//! `Ã = a_1·A_1 + … + a_n·A_n`, where each coefficient `a_i` is a hash of
+//! `A_i` and of the whole list of keys.  Unlike the plain sum of
+//! `PublicKey::aggregate_many()`, this leaves no room for a rogue-key
+//! attack, since a key chosen after the others changes every coefficient.
+//!
+//! To sign a message, each signer chooses a `SecretNonce`, with public nonce
+//! `R_i`, and then:
+//!
+//! 1. sends every other signer its `NonceCommitment`, a hash of `R_i`;
+//! 2. once it has every commitment, sends every other signer its
+//!    `PublicNonce`, `R_i`; and
+//! 3. once it has every nonce, checks each against its commitment, and sends
+//!    its `PartialSignature`, `s_i = r_i + H(R || Ã || M)·a_i·x_i`, where
+//!    `R = R_1 + … + R_n`.
+//!
+//! Anyone may then combine the partial signatures into one ordinary
+//! signature, `(R, s_1 + … + s_n)`, which `PublicKey::verify()` accepts under
+//! `Ã`.  The commitment round is what makes this secure: a signer who saw the
+//! other nonces before choosing its own could choose it to forge signatures.
+//!
+//! # Warning
+//!
+//! A `SecretNonce` must never be used for two messages, or for the same
+//! message with different co-signers, since two partial signatures with the
+//! same nonce reveal the signer's secret key.  `partial_sign()` takes the
+//! nonce by value for this reason, and `SecretNonce` is not `Clone`.
+
+use core::fmt;
+use core::fmt::Debug;
+
+use std::vec::Vec;
+
+use rand::Rng;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::Identity;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// The domain separation string for the key aggregation coefficients.
+const KEY_AGGREGATION_DOMAIN: &[u8] = b"ed25519-dalek MuSig key aggregation";
+
+/// The domain separation string for nonce commitments.
+const NONCE_COMMITMENT_DOMAIN: &[u8] = b"ed25519-dalek MuSig nonce commitment";
+
+/// Reduce the output of the hash function `D` to a scalar.
+fn reduce<D>(h: D) -> Scalar where D: Digest<OutputSize = U64> + Default {
+    let digest = h.fixed_result();
+
+    Scalar::reduce(array_ref!(digest, 0, 64))
+}
+
+/// Derive the coefficient `a_i` for each of the `public_keys`.
+fn coefficients<D>(public_keys: &[PublicKey]) -> Vec<Scalar>
+        where D: Digest<OutputSize = U64> + Default {
+
+    let mut h: D = D::default();
+
+    h.input(KEY_AGGREGATION_DOMAIN);
+    h.input(&(public_keys.len() as u64).to_le_bytes());
+    for public_key in public_keys {
+        h.input(public_key.as_bytes());
+    }
+    let list = h.fixed_result();
+
+    public_keys.iter().map(|public_key| {
+        let mut h: D = D::default();
+
+        h.input(&list);
+        h.input(public_key.as_bytes());
+        reduce(h)
+    }).collect()
+}
+
+/// Aggregate the `public_keys` of the signers into the key which their
+/// combined signatures verify under.
+///
+/// The aggregate depends on the order of the `public_keys`, so every signer
+/// must use the same order.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the aggregate `PublicKey`, or whose error
+/// value is
+///
+/// * `SignatureError::InvalidInput` if there are no `public_keys`, or
+/// * `SignatureError::PointDecompressionError` if one of them is not a point
+///   on the curve.
+pub fn aggregate_public_keys<D>(public_keys: &[PublicKey]) -> Result<PublicKey, SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    if public_keys.is_empty() {
+        return Err(SignatureError::InvalidInput);
+    }
+    let mut sum: ExtendedPoint = ExtendedPoint::identity();
+
+    for (public_key, a) in public_keys.iter().zip(coefficients::<D>(public_keys).iter()) {
+        let point: ExtendedPoint = public_key.0.decompress().ok_or(SignatureError::PointDecompressionError)?;
+
+        sum = &sum + &(&point * a);
+    }
+    Ok(PublicKey(sum.compress()))
+}
+
+/// A signer's commitment to its `PublicNonce`, sent in the first round.
+#[derive(Clone, Copy)]
+pub struct NonceCommitment(pub [u8; 64]);
+
+impl Debug for NonceCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NonceCommitment({:?})", &self.0[..])
+    }
+}
+
+impl PartialEq for NonceCommitment {
+    fn eq(&self, other: &NonceCommitment) -> bool {
+        self.0[..] == other.0[..]
+    }
+}
+
+impl Eq for NonceCommitment {}
+
+/// A signer's public nonce, `R_i`, sent in the second round.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PublicNonce(pub CompressedEdwardsY);
+
+impl PublicNonce {
+    /// The commitment to this nonce, as a hash with `D`.
+    pub fn commitment<D>(&self) -> NonceCommitment where D: Digest<OutputSize = U64> + Default {
+        let mut h: D = D::default();
+        let mut commitment: [u8; 64] = [0u8; 64];
+
+        h.input(NONCE_COMMITMENT_DOMAIN);
+        h.input(self.0.as_bytes());
+        commitment.copy_from_slice(h.fixed_result().as_slice());
+        NonceCommitment(commitment)
+    }
+}
+
+/// A signer's secret nonce, `r_i`, for a single signature.
+pub struct SecretNonce {
+    r: Scalar,
+    public: PublicNonce,
+}
+
+impl Debug for SecretNonce {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SecretNonce({:?})", self.public)
+    }
+}
+
+impl SecretNonce {
+    /// Choose a new `SecretNonce`, using randomness from the `csprng`.
+    pub fn new(csprng: &mut dyn Rng) -> SecretNonce {
+        let mut bytes: [u8; 64] = [0u8; 64];
+
+        csprng.fill_bytes(&mut bytes);
+
+        let r: Scalar = Scalar::reduce(&bytes);
+        let public: PublicNonce = PublicNonce((&r * &constants::ED25519_BASEPOINT_TABLE).compress());
+
+        SecretNonce{ r, public }
+    }
+
+    /// The `PublicNonce` for this nonce.
+    pub fn public_nonce(&self) -> PublicNonce {
+        self.public
+    }
+}
+
+/// One signer's share of a multi-signature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PartialSignature {
+    r: CompressedEdwardsY,
+    s: Scalar,
+}
+
+/// Make the `keypair`'s `PartialSignature` on a `message`, in the third
+/// round.
+///
+/// The `public_keys`, `nonces`, and `commitments` are those of every signer,
+/// the `keypair`'s own included, in the same order, which must be the order
+/// given to `aggregate_public_keys()`.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the `PartialSignature`, or whose error
+/// value is
+///
+/// * `SignatureError::InvalidInput` if the numbers of public keys, nonces,
+///   and commitments differ, if a nonce does not match its commitment, or if
+///   the `keypair` and its `secret_nonce` are not among the signers, or
+/// * `SignatureError::PointDecompressionError` if a public key or nonce is
+///   not a point on the curve.
+pub fn partial_sign<D>(keypair: &Keypair, secret_nonce: SecretNonce, public_keys: &[PublicKey],
+                       nonces: &[PublicNonce], commitments: &[NonceCommitment], message: &[u8])
+        -> Result<PartialSignature, SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    if nonces.len() != public_keys.len() || commitments.len() != public_keys.len() {
+        return Err(SignatureError::InvalidInput);
+    }
+    if nonces.iter().zip(commitments.iter()).any(|(nonce, commitment)| nonce.commitment::<D>() != *commitment) {
+        return Err(SignatureError::InvalidInput);
+    }
+    let index: usize = (0..public_keys.len())
+        .find(|&i| public_keys[i] == keypair.public && nonces[i] == secret_nonce.public)
+        .ok_or(SignatureError::InvalidInput)?;
+    let aggregate: PublicKey = aggregate_public_keys::<D>(public_keys)?;
+    let mut r: ExtendedPoint = ExtendedPoint::identity();
+
+    for nonce in nonces {
+        r = &r + &nonce.0.decompress().ok_or(SignatureError::PointDecompressionError)?;
+    }
+    let r: CompressedEdwardsY = r.compress();
+    let mut h: D = D::default();
+
+    h.input(r.as_bytes());
+    h.input(aggregate.as_bytes());
+    h.input(message);
+
+    let c: Scalar = reduce(h);
+    let a: Scalar = coefficients::<D>(public_keys)[index];
+    let x: Scalar = keypair.secret.expand::<D>().key;
+
+    Ok(PartialSignature{ r, s: Scalar::multiply_add(&(&c * &a), &x, &secret_nonce.r) })
+}
+
+/// Combine the `partials` of every signer into a `Signature` under the key
+/// from `aggregate_public_keys()`.
+///
+/// The partial signatures are not checked: a combination with an invalid
+/// partial signature in it will fail to verify.
+///
+/// # Returns
+///
+/// A `Result` whose okay value is the `Signature`, or whose error value is
+/// `SignatureError::InvalidInput` if there are no `partials`, or they were
+/// made with different sets of nonces.
+pub fn combine_partial_signatures(partials: &[PartialSignature]) -> Result<Signature, SignatureError> {
+    let r: CompressedEdwardsY = partials.first().ok_or(SignatureError::InvalidInput)?.r;
+
+    if partials.iter().any(|partial| partial.r != r) {
+        return Err(SignatureError::InvalidInput);
+    }
+    let s: Scalar = partials.iter().fold(Scalar::zero(), |s, partial| &s + &partial.s);
+
+    Ok(Signature{ r, s })
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn two_of_two_signature_verifies_under_aggregate_key() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let alice: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+        let public_keys: [PublicKey; 2] = [alice.public, bob.public];
+        let aggregate: PublicKey = aggregate_public_keys::<Sha512>(&public_keys).unwrap();
+
+        // Round 1: exchange commitments.  Round 2: exchange nonces.
+        let alice_nonce: SecretNonce = SecretNonce::new(&mut csprng);
+        let bob_nonce: SecretNonce = SecretNonce::new(&mut csprng);
+        let nonces: [PublicNonce; 2] = [alice_nonce.public_nonce(), bob_nonce.public_nonce()];
+        let commitments: [NonceCommitment; 2] = [nonces[0].commitment::<Sha512>(),
+                                                 nonces[1].commitment::<Sha512>()];
+
+        // Round 3: exchange partial signatures.
+        let partials: [PartialSignature; 2] = [
+            partial_sign::<Sha512>(&alice, alice_nonce, &public_keys, &nonces, &commitments, b"transfer").unwrap(),
+            partial_sign::<Sha512>(&bob, bob_nonce, &public_keys, &nonces, &commitments, b"transfer").unwrap(),
+        ];
+        let signature: Signature = combine_partial_signatures(&partials).unwrap();
+
+        assert_eq!(aggregate.verify::<Sha512>(b"transfer", &signature), Ok(()));
+        assert_eq!(aggregate.verify::<Sha512>(b"transfer!", &signature), Err(SignatureError::InvalidSignature));
+        assert_eq!(combine_partial_signatures(&partials[..1]).and_then(|s| aggregate.verify::<Sha512>(b"transfer", &s)),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn aggregate_key_resists_rogue_keys() {
+        let alice: PublicKey = keypair_for_testing("alice").public;
+        let bob: PublicKey = keypair_for_testing("bob").public;
+
+        assert!(aggregate_public_keys::<Sha512>(&[alice, bob]).unwrap() != alice.aggregate_with(&bob).unwrap());
+        assert!(aggregate_public_keys::<Sha512>(&[alice, bob]) != aggregate_public_keys::<Sha512>(&[bob, alice]));
+        assert_eq!(aggregate_public_keys::<Sha512>(&[]), Err(SignatureError::InvalidInput));
+    }
+
+    #[test]
+    fn partial_sign_checks_commitments() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let alice: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+        let public_keys: [PublicKey; 2] = [alice.public, bob.public];
+        let alice_nonce: SecretNonce = SecretNonce::new(&mut csprng);
+        let nonces: [PublicNonce; 2] = [alice_nonce.public_nonce(), SecretNonce::new(&mut csprng).public_nonce()];
+        let commitments: [NonceCommitment; 2] = [nonces[0].commitment::<Sha512>(),
+                                                 SecretNonce::new(&mut csprng).public_nonce().commitment::<Sha512>()];
+
+        assert_eq!(partial_sign::<Sha512>(&alice, alice_nonce, &public_keys, &nonces, &commitments, b"transfer"),
+                   Err(SignatureError::InvalidInput));
+        assert_eq!(partial_sign::<Sha512>(&bob, SecretNonce::new(&mut csprng), &public_keys, &nonces,
+                                          &[nonces[0].commitment::<Sha512>(), nonces[1].commitment::<Sha512>()],
+                                          b"transfer"),
+                   Err(SignatureError::InvalidInput));
+        assert_eq!(combine_partial_signatures(&[]), Err(SignatureError::InvalidInput));
+    }
+}
@@ -0,0 +1,88 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding of signatures in Multibase, as used in W3C Decentralized
+//! Identifier (DID) documents and Verifiable Credentials.
+//!
+//! A Multibase string is a single character naming the base, e.g. `'z'` for
+//! base58btc, followed by the data encoded in that base.
+
+use std::string::String;
+use std::vec::Vec;
+
+use multibase;
+use multibase::Base;
+
+use ed25519::Signature;
+use ed25519::SIGNATURE_LENGTH;
+use errors::SignatureError;
+
+impl Signature {
+    /// Encode this `Signature` in Multibase, as `'z'` followed by the
+    /// base58btc encoding of its 64 bytes.
+    pub fn to_multibase_base58btc(&self) -> String {
+        multibase::encode(Base::Base58Btc, &self.to_bytes()[..])
+    }
+
+    /// Decode a `Signature` from Multibase, in any of the bases which
+    /// Multibase defines.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Signature`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the prefix names no
+    /// known base, the rest of the string is not valid in that base, or it
+    /// does not decode to exactly 64 bytes.
+    pub fn from_multibase(encoded: &str) -> Result<Signature, SignatureError> {
+        let (_, bytes): (Base, Vec<u8>) = multibase::decode(encoded)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        if bytes.len() != SIGNATURE_LENGTH {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        Signature::from_bytes(&bytes).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn multibase_base58btc_roundtrip() {
+        let signature: Signature = keypair_for_testing("multibase").sign::<Sha512>(b"test message");
+        let encoded: String = signature.to_multibase_base58btc();
+
+        assert!(encoded.starts_with('z'));
+        assert_eq!(Signature::from_multibase(&encoded), Ok(signature));
+    }
+
+    #[test]
+    fn multibase_other_bases_are_decoded() {
+        let signature: Signature = keypair_for_testing("multibase").sign::<Sha512>(b"test message");
+
+        for base in [Base::Base16Lower, Base::Base32Lower, Base::Base64Url].iter() {
+            let encoded: String = multibase::encode(*base, &signature.to_bytes()[..]);
+
+            assert_eq!(Signature::from_multibase(&encoded), Ok(signature));
+        }
+    }
+
+    #[test]
+    fn multibase_malformed_is_rejected() {
+        let signature: Signature = keypair_for_testing("multibase").sign::<Sha512>(b"test message");
+        let truncated: String = multibase::encode(Base::Base58Btc, &signature.to_bytes()[..63]);
+
+        assert_eq!(Signature::from_multibase(&truncated), Err(SignatureError::InvalidEncoding));
+        assert_eq!(Signature::from_multibase("!abc"), Err(SignatureError::InvalidEncoding));
+        assert_eq!(Signature::from_multibase(""), Err(SignatureError::InvalidEncoding));
+    }
+}
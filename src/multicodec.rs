@@ -0,0 +1,114 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Multicodec encodings of public keys, as used in decentralized identity
+//! systems.
+//!
+//! A multicodec-prefixed public key is the unsigned varint of the key type's
+//! code, `0xed` for ed25519, i.e. the bytes `0xed 0x01`, followed by the
+//! 32-byte key.
+
+use std::vec::Vec;
+
+use ed25519::PublicKey;
+use ed25519::PUBLIC_KEY_LENGTH;
+use errors::SignatureError;
+
+/// The multicodec code for an ed25519 public key.
+const ED25519_PUB: u64 = 0xed;
+
+/// The varint encoding of `ED25519_PUB`.
+pub(crate) const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// Split an unsigned varint of at most nine bytes, as multiformats allows,
+/// from the front of `input`.
+///
+/// Multiformats also requires the minimal encoding, so a varint which ends
+/// with a zero byte after a continuation byte is rejected: otherwise
+/// `ed 81 00` would decode to the same code as `ed 01`.
+fn get_varint(input: &mut &[u8]) -> Option<u64> {
+    let mut value: u64 = 0;
+
+    for i in 0..9 {
+        let byte: u8 = *input.get(i)?;
+
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            if byte == 0 && i > 0 {
+                return None;
+            }
+            *input = &input[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` with its multicodec prefix.
+    pub fn to_multicodec_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(ED25519_MULTICODEC.len() + PUBLIC_KEY_LENGTH);
+
+        bytes.extend_from_slice(&ED25519_MULTICODEC);
+        bytes.extend_from_slice(self.as_bytes());
+        bytes
+    }
+
+    /// Decode a `PublicKey` from its multicodec-prefixed encoding.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `PublicKey`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the prefix is of some other
+    ///   type of key, or the key is not 32 bytes, or
+    /// * `SignatureError::InvalidEncoding` if the prefix is not a minimal
+    ///   varint, or the key is not a valid public key.
+    pub fn from_multicodec_bytes(mut bytes: &[u8]) -> Result<PublicKey, SignatureError> {
+        let code: u64 = get_varint(&mut bytes).ok_or(SignatureError::InvalidEncoding)?;
+
+        if code != ED25519_PUB || bytes.len() != PUBLIC_KEY_LENGTH {
+            return Err(SignatureError::InvalidKeyLength);
+        }
+        PublicKey::from_bytes(bytes).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn multicodec_roundtrip() {
+        let public: PublicKey = keypair_for_testing("multicodec").public;
+        let bytes: Vec<u8> = public.to_multicodec_bytes();
+
+        assert_eq!(&bytes[..2], &[0xed, 0x01]);
+        assert_eq!(&bytes[2..], public.as_bytes());
+        assert_eq!(PublicKey::from_multicodec_bytes(&bytes), Ok(public));
+    }
+
+    #[test]
+    fn multicodec_malformed_is_rejected() {
+        let bytes: Vec<u8> = keypair_for_testing("multicodec").public.to_multicodec_bytes();
+        let x25519: Vec<u8> = [&[0xec, 0x01], &bytes[2..]].concat();
+        let unterminated: Vec<u8> = FromHex::from_hex("ffffffffffffffffff").unwrap();
+        let non_minimal: Vec<u8> = [&[0xed, 0x81, 0x00], &bytes[2..]].concat();
+
+        assert_eq!(PublicKey::from_multicodec_bytes(&x25519), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_multicodec_bytes(&bytes[..33]), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_multicodec_bytes(&bytes[2..]), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_multicodec_bytes(&unterminated), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_multicodec_bytes(&non_minimal), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_multicodec_bytes(&[]), Err(SignatureError::InvalidEncoding));
+    }
+}
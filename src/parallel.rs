@@ -0,0 +1,94 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing many messages at once on all cores, with `rayon`.
+
+use std::vec::Vec;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use rayon::iter::IntoParallelRefIterator;
+use rayon::iter::ParallelIterator;
+
+use ed25519::{ExpandedSecretKey, Keypair, Signature};
+
+impl Keypair {
+    /// Sign each of the `messages` with this keypair's secret key, spreading
+    /// the work over rayon's global thread pool.
+    ///
+    /// The secret key is expanded once, and shared by every thread.  Each
+    /// signature is the same as `sign()` would make, and they are returned in
+    /// the order of the `messages`.
+    pub fn sign_batch_parallel<D>(&self, messages: &[&[u8]]) -> Vec<Signature>
+            where D: Digest<OutputSize = U64> + Default {
+        let expanded: ExpandedSecretKey = self.secret.expand::<D>();
+
+        messages.par_iter().map(|message| expanded.sign::<D>(message, &self.public)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn parallel_signatures_match_sequential_ones() {
+        let keypair: Keypair = keypair_for_testing("parallel");
+        let messages: Vec<Vec<u8>> = (0u8..100).map(|i| vec![i; i as usize]).collect();
+        let messages: Vec<&[u8]> = messages.iter().map(|message| &message[..]).collect();
+        let signatures: Vec<Signature> = keypair.sign_batch_parallel::<Sha512>(&messages);
+
+        assert_eq!(signatures.len(), messages.len());
+        for (message, signature) in messages.iter().zip(signatures.iter()) {
+            assert_eq!(*signature, keypair.sign::<Sha512>(message));
+        }
+        assert!(keypair.sign_batch_parallel::<Sha512>(&[]).is_empty());
+    }
+}
+
+#[cfg(all(test, feature = "bench"))]
+mod bench {
+    use test::Bencher;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    fn bench_signing(b: &mut Bencher, count: usize, parallel: bool) {
+        let keypair: Keypair = keypair_for_testing("parallel");
+        let messages: Vec<&[u8]> = vec![b"test message"; count];
+
+        if parallel {
+            b.iter(| | keypair.sign_batch_parallel::<Sha512>(&messages));
+        } else {
+            b.iter(| | messages.iter().map(|message| keypair.sign::<Sha512>(message)).collect::<Vec<Signature>>());
+        }
+    }
+
+    #[bench]
+    fn sign_sequential_10(b: &mut Bencher) { bench_signing(b, 10, false); }
+
+    #[bench]
+    fn sign_sequential_100(b: &mut Bencher) { bench_signing(b, 100, false); }
+
+    #[bench]
+    fn sign_sequential_1000(b: &mut Bencher) { bench_signing(b, 1000, false); }
+
+    #[bench]
+    fn sign_parallel_10(b: &mut Bencher) { bench_signing(b, 10, true); }
+
+    #[bench]
+    fn sign_parallel_100(b: &mut Bencher) { bench_signing(b, 100, true); }
+
+    #[bench]
+    fn sign_parallel_1000(b: &mut Bencher) { bench_signing(b, 1000, true); }
+}
@@ -0,0 +1,81 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Derivation of keypairs from passwords, with the memory-hard Argon2id.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use ed25519::SECRET_KEY_LENGTH;
+use errors::SignatureError;
+
+/// The Argon2id memory cost, in KiB.
+const ARGON2_MEMORY_COST: u32 = 65536;
+
+/// The Argon2id number of passes.
+const ARGON2_TIME_COST: u32 = 3;
+
+/// The Argon2id degree of parallelism.
+const ARGON2_PARALLELISM: u32 = 4;
+
+impl Keypair {
+    /// Derive a `Keypair` from a `password` and `salt`, by using Argon2id,
+    /// version 0x13, to hash them to the 32 bytes of the secret key.  The
+    /// public key is derived using SHA-512.
+    ///
+    /// The parameters are those recommended by RFC9106: 64 MiB of memory,
+    /// 3 passes and 4 lanes, so each derivation takes a noticeable fraction
+    /// of a second.  The `salt` should be unique to the keypair, and stored
+    /// alongside it; the same `password` and `salt` always give the same
+    /// keypair.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    /// `SignatureError::EntropyError` if Argon2id fails, for example because
+    /// its memory could not be allocated.
+    pub fn from_argon2id(password: &str, salt: &[u8; 16]) -> Result<Keypair, SignatureError> {
+        let params: Params = Params::new(ARGON2_MEMORY_COST, ARGON2_TIME_COST, ARGON2_PARALLELISM,
+                                         Some(SECRET_KEY_LENGTH))
+            .or(Err(SignatureError::EntropyError))?;
+        let mut seed: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(password.as_bytes(), salt, &mut seed)
+            .or(Err(SignatureError::EntropyError))?;
+
+        let secret: SecretKey = SecretKey(seed);
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Ok(Keypair{ secret, public })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn argon2id_derivation() {
+        let keypair: Keypair = Keypair::from_argon2id("correct horse battery staple", &[7u8; 16]).unwrap();
+        let other: Keypair = Keypair::from_argon2id("correct horse battery staple", &[8u8; 16]).unwrap();
+
+        assert!(keypair.verify_own_public_key::<Sha512>().is_ok());
+        assert!(keypair.public != other.public);
+
+        // Pin the derivation, so that it never silently changes.
+        assert_eq!(keypair.secret.to_bytes(), [
+             11,  22, 126,  32, 255, 184, 163,  31,
+            117, 235,  62,  71,  24, 114, 186,  10,
+             87,  71, 213, 110, 196, 148, 219,  91,
+            236, 176, 113,   8,  20,  27, 255,  36, ]);
+    }
+}
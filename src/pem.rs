@@ -0,0 +1,279 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! PEM armor for keys and signatures.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use base64;
+
+use ed25519::{Keypair, PublicKey, SecretKey, Signature};
+use ed25519::PUBLIC_KEY_LENGTH;
+use errors::SignatureError;
+
+/// The PEM label of a `PublicKey`.
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+
+/// The PEM label of a `SecretKey` or `Keypair`.
+const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// The PEM label of a `Signature`.
+const SIGNATURE_LABEL: &str = "SIGNATURE";
+
+/// The DER encoding of a `SubjectPublicKeyInfo` for Ed25519, from RFC8410,
+/// up to the 32 bytes of the key.
+const SUBJECT_PUBLIC_KEY_INFO_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00, ];
+
+/// Find the first PEM block in `pem`.
+///
+/// # Returns
+///
+/// The label and decoded contents of the block, or `None` if there is no
+/// well-formed block.
+pub(crate) fn pem_block(pem: &str) -> Option<(String, Vec<u8>)> {
+    let mut lines = pem.lines().map(|line| line.trim())
+        .skip_while(|line| !line.starts_with("-----BEGIN "));
+    let begin: &str = lines.next()?;
+
+    if !begin.ends_with("-----") || begin.len() < 16 {
+        return None;
+    }
+    let label: &str = &begin[11..begin.len() - 5];
+    let end: String = format!("-----END {}-----", label);
+    let mut encoded: String = String::new();
+
+    for line in lines {
+        if line == end {
+            let contents: Vec<u8> = base64::decode_config(&encoded, base64::STANDARD).ok()?;
+
+            return Some((String::from(label), contents));
+        }
+        encoded.push_str(line);
+    }
+    None
+}
+
+/// Decode the contents of the first PEM block in `pem`.
+///
+/// # Returns
+///
+/// The contents, or `SignatureError::InvalidEncoding` if there is no
+/// well-formed block, or its label is not `label`.
+fn pem_contents(pem: &str, label: &str) -> Result<Vec<u8>, SignatureError> {
+    match pem_block(pem) {
+        Some((ref found, ref contents)) if found == label => Ok(contents.clone()),
+        _ => Err(SignatureError::InvalidEncoding),
+    }
+}
+
+/// Encode `contents` as a PEM block labelled `label`, with the base64 in
+/// lines of 64 characters.
+fn pem_encode(label: &str, contents: &[u8]) -> String {
+    let encoded: String = base64::encode_config(contents, base64::STANDARD);
+    let mut pem: String = format!("-----BEGIN {}-----\n", label);
+
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(&String::from_utf8_lossy(line));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` as a `PUBLIC KEY` PEM block, holding the DER
+    /// `SubjectPublicKeyInfo` of RFC8410, as written by OpenSSL and other
+    /// tools.  The base64 is in lines of 64 characters.
+    pub fn to_pem(&self) -> String {
+        let mut der: Vec<u8> = SUBJECT_PUBLIC_KEY_INFO_PREFIX.to_vec();
+
+        der.extend_from_slice(self.as_bytes());
+        pem_encode(PUBLIC_KEY_LABEL, &der)
+    }
+
+    /// Decode a `PublicKey` from the first PEM block in `pem`, which must be
+    /// a `PUBLIC KEY` block, holding the DER `SubjectPublicKeyInfo` of
+    /// RFC8410, as written by `PublicKey::to_pem()` and by OpenSSL.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `PublicKey`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the PEM is malformed, or does
+    /// not hold an Ed25519 public key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "pem")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let pem: &str = "-----BEGIN PUBLIC KEY-----\n\
+    ///                  MCowBQYDK2VwAyEA11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=\n\
+    ///                  -----END PUBLIC KEY-----\n";
+    /// let public: PublicKey = PublicKey::from_pem(pem).unwrap();
+    ///
+    /// assert_eq!(public.to_pem(), pem);
+    /// # }
+    /// # #[cfg(not(feature = "pem"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_pem(pem: &str) -> Result<PublicKey, SignatureError> {
+        let der: Vec<u8> = pem_contents(pem, PUBLIC_KEY_LABEL)?;
+        let prefix: usize = SUBJECT_PUBLIC_KEY_INFO_PREFIX.len();
+
+        if der.len() != prefix + PUBLIC_KEY_LENGTH || der[..prefix] != SUBJECT_PUBLIC_KEY_INFO_PREFIX[..] {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        PublicKey::from_bytes(&der[prefix..]).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+impl SecretKey {
+    /// Encode this `SecretKey` as a `PRIVATE KEY` PEM block, holding the
+    /// version 1 PKCS#8 `OneAsymmetricKey` of RFC8410, as written by OpenSSL
+    /// and other tools.  The base64 is in lines of 64 characters.
+    ///
+    /// Version 2 `OneAsymmetricKey`s, which also hold the public key, are not
+    /// written, since OpenSSL 3.0 cannot read them.  For those, use
+    /// `Keypair::to_pkcs8_der()`.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_pem(&self) -> String {
+        pem_encode(PRIVATE_KEY_LABEL, &self.to_pkcs8_der())
+    }
+
+    /// Decode a `SecretKey` from the first PEM block in `pem`, which must be
+    /// a `PRIVATE KEY` block, holding a version 1 or version 2 PKCS#8
+    /// `OneAsymmetricKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `SecretKey`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the PEM is malformed, or
+    /// otherwise as for `SecretKey::from_pkcs8_der()`.
+    pub fn from_pem(pem: &str) -> Result<SecretKey, SignatureError> {
+        SecretKey::from_pkcs8_der(&pem_contents(pem, PRIVATE_KEY_LABEL)?)
+    }
+}
+
+impl Keypair {
+    /// Encode this `Keypair` as a `PRIVATE KEY` PEM block, holding the
+    /// version 1 PKCS#8 `OneAsymmetricKey` of RFC8410, as for
+    /// `SecretKey::to_pem()`.
+    ///
+    /// The public key is not included, since OpenSSL 3.0 cannot read version 2
+    /// `OneAsymmetricKey`s.  For those, use `Keypair::to_pkcs8_der()`.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_pem(&self) -> String {
+        self.secret.to_pem()
+    }
+
+    /// Decode a `Keypair` from the first PEM block in `pem`, which must be a
+    /// `PRIVATE KEY` block, holding a version 1 or version 2 PKCS#8
+    /// `OneAsymmetricKey`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    /// `SignatureError::InvalidEncoding` if the PEM is malformed, or
+    /// otherwise as for `Keypair::from_pkcs8_der()`.
+    pub fn from_pem(pem: &str) -> Result<Keypair, SignatureError> {
+        Keypair::from_pkcs8_der(&pem_contents(pem, PRIVATE_KEY_LABEL)?)
+    }
+}
+
+impl Signature {
+    /// Encode this `Signature` as a `SIGNATURE` PEM block, holding its 64
+    /// bytes.
+    ///
+    /// There is no standard for this; it is only meant to be read by
+    /// `Signature::from_pem()`.
+    pub fn to_pem(&self) -> String {
+        pem_encode(SIGNATURE_LABEL, &self.to_bytes())
+    }
+
+    /// Decode a `Signature` from the first PEM block in `pem`, which must be
+    /// a `SIGNATURE` block.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Signature`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the PEM is malformed, or does
+    /// not hold 64 bytes.
+    pub fn from_pem(pem: &str) -> Result<Signature, SignatureError> {
+        Signature::from_bytes(&pem_contents(pem, SIGNATURE_LABEL)?).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Read;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    fn read_key(filename: &str) -> String {
+        let mut pem: String = String::new();
+
+        File::open(filename).unwrap().read_to_string(&mut pem).unwrap();
+        pem
+    }
+
+    #[test]
+    fn pem_openssl_keys() {
+        // Written by `openssl genpkey -algorithm ED25519` and
+        // `openssl pkey -pubout`.
+        let private: String = read_key("res/ed25519-openssl.pem");
+        let public: String = read_key("res/ed25519-openssl.pub.pem");
+        let keypair: Keypair = Keypair::from_pem(&private).unwrap();
+
+        assert_eq!(PublicKey::from_pem(&public).unwrap(), keypair.public);
+        assert_eq!(keypair.public.to_pem(), public);
+        assert_eq!(keypair.to_pem(), private);
+        assert_eq!(SecretKey::from_pem(&private).unwrap().to_pem(), private);
+    }
+
+    #[test]
+    fn pem_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("pem");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let decoded: Keypair = Keypair::from_pem(&keypair.to_pem()).unwrap();
+
+        assert_eq!(decoded.to_raw_bytes()[..], keypair.to_raw_bytes()[..]);
+        assert_eq!(SecretKey::from_pem(&keypair.to_pem()).unwrap().as_bytes(), keypair.secret.as_bytes());
+        assert_eq!(Keypair::from_pem(&pem_encode("PRIVATE KEY", &keypair.to_pkcs8_der())).unwrap().public,
+                   keypair.public);
+        assert_eq!(PublicKey::from_pem(&keypair.public.to_pem()), Ok(keypair.public));
+        assert_eq!(Signature::from_pem(&signature.to_pem()), Ok(signature));
+        assert!(signature.to_pem().lines().all(|line| line.len() <= 64));
+    }
+
+    #[test]
+    fn pem_wrong_label_is_rejected() {
+        let keypair: Keypair = keypair_for_testing("pem");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+
+        assert_eq!(PublicKey::from_pem(&keypair.to_pem()), Err(SignatureError::InvalidEncoding));
+        assert_eq!(Keypair::from_pem(&keypair.public.to_pem()).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Signature::from_pem(&keypair.public.to_pem()), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_pem(&signature.to_pem()), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_pem(""), Err(SignatureError::InvalidEncoding));
+    }
+}
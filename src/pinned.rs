@@ -0,0 +1,115 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Keypairs whose secret key stays at one address on the heap.
+
+use core::fmt;
+use core::fmt::Debug;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr;
+
+use std::boxed::Box;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{ExpandedSecretKey, PublicKey, SecretKey, Signature};
+use ed25519::SECRET_KEY_LENGTH;
+
+/// An ed25519 keypair which is pinned on the heap, so that its secret key is
+/// never moved, and so never leaves stale copies of itself behind in memory.
+/// The secret key is zeroed when the `PinnedKeypair` is dropped.
+///
+/// # Warning
+///
+/// This is a best-effort, defense-in-depth measure.  The secret key is still
+/// copied onto the stack while signing (and zeroed afterwards), it may be
+/// swapped to disk (see `LockedMemoryKey` for a key which cannot be), and
+/// anything which can read the process's memory, such as a debugger or a
+/// kernel memory dump, can still read it.
+pub struct PinnedKeypair {
+    secret: [u8; SECRET_KEY_LENGTH],
+    /// The public half of this keypair.
+    pub public: PublicKey,
+    _pinned: PhantomPinned,
+}
+
+impl Debug for PinnedKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PinnedKeypair({:?})", self.public)
+    }
+}
+
+impl PinnedKeypair {
+    /// Move a `secret_key` into a new `PinnedKeypair`, deriving its public
+    /// key using the hash function `D`.
+    ///
+    /// The `secret_key` passed in is zeroed, but copies of it which the
+    /// caller made (or which the compiler made when moving it) may remain
+    /// elsewhere in memory.
+    pub fn new<D>(mut secret_key: SecretKey) -> Pin<Box<PinnedKeypair>>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let public: PublicKey = PublicKey::from_secret::<D>(&secret_key);
+        let mut pinned: Pin<Box<PinnedKeypair>> = Box::pin(PinnedKeypair{
+            secret: [0u8; SECRET_KEY_LENGTH],
+            public,
+            _pinned: PhantomPinned,
+        });
+
+        // Writing to a field in place does not move the `PinnedKeypair`.
+        unsafe { pinned.as_mut().get_unchecked_mut().secret = secret_key.0 };
+        unsafe { ptr::write_volatile(&mut secret_key.0, [0u8; SECRET_KEY_LENGTH]) };
+        pinned
+    }
+
+    /// Sign a `message` with this keypair's secret key, using the hash
+    /// function `D`.
+    pub fn sign<D>(self: Pin<&Self>, message: &[u8]) -> Signature
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut secret: SecretKey = SecretKey(self.secret);
+        let mut expanded: ExpandedSecretKey = secret.expand::<D>();
+        let signature: Signature = expanded.sign::<D>(message, &self.public);
+
+        unsafe {
+            ptr::write_volatile(&mut secret.0, [0u8; SECRET_KEY_LENGTH]);
+            ptr::write_volatile(&mut expanded.nonce, [0u8; 32]);
+            ptr::write_volatile(&mut expanded.key.0, [0u8; 32]);
+        }
+        signature
+    }
+}
+
+impl Drop for PinnedKeypair {
+    fn drop(&mut self) {
+        unsafe { ptr::write_volatile(&mut self.secret, [0u8; SECRET_KEY_LENGTH]) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn pinned_keypair_signs() {
+        let keypair: Keypair = keypair_for_testing("pinned");
+        let secret: SecretKey = SecretKey::from_bytes(keypair.secret.as_bytes()).unwrap();
+        let pinned: Pin<Box<PinnedKeypair>> = PinnedKeypair::new::<Sha512>(secret);
+        let signature: Signature = pinned.as_ref().sign::<Sha512>(b"test message");
+
+        assert_eq!(pinned.public, keypair.public);
+        assert_eq!(signature, keypair.sign::<Sha512>(b"test message"));
+        assert_eq!(format!("{:?}", pinned), format!("PinnedKeypair({:?})", keypair.public));
+    }
+}
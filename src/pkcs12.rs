@@ -0,0 +1,110 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Import of keypairs from PKCS#12 (`.p12` or `.pfx`) bundles.
+
+use std::vec::Vec;
+
+use p12::PFX;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use errors::SignatureError;
+use pkcs8::secret_key_from_pkcs8;
+
+/// A DER-encoded X.509 certificate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct X509Certificate(pub Vec<u8>);
+
+impl Keypair {
+    /// Import a `Keypair`, and any accompanying certificates, from a PKCS#12
+    /// bundle.
+    ///
+    /// The first key in the bundle must be an Ed25519 key, stored as an
+    /// RFC8410 PKCS#8 private key.  The public key is derived from it using
+    /// SHA-512.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair` and the certificates in the
+    /// bundle, in the order in which they appear, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the key is not an Ed25519 key,
+    ///   or
+    /// * `SignatureError::InvalidEncoding` if the bundle is malformed, the
+    ///   `password` is wrong, or the bundle contains no keys.
+    pub fn try_from_pkcs12(bytes: &[u8], password: &str)
+            -> Result<(Keypair, Vec<X509Certificate>), SignatureError> {
+
+        let pfx: PFX = PFX::parse(bytes).or(Err(SignatureError::InvalidEncoding))?;
+
+        if !pfx.verify_mac(password) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let keys: Vec<Vec<u8>> = pfx.key_bags(password).or(Err(SignatureError::InvalidEncoding))?;
+        let certificates: Vec<Vec<u8>> = pfx.cert_x509_bags(password)
+            .or(Err(SignatureError::InvalidEncoding))?;
+
+        let secret: SecretKey = secret_key_from_pkcs8(keys.first()
+            .ok_or(SignatureError::InvalidEncoding)?)?;
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Ok((Keypair{ secret, public },
+            certificates.into_iter().map(X509Certificate).collect()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Read;
+    use hex::FromHex;
+    use super::*;
+
+    fn read_bundle(filename: &str) -> Vec<u8> {
+        let mut bundle: Vec<u8> = Vec::new();
+
+        File::open(filename).unwrap().read_to_end(&mut bundle).unwrap();
+        bundle
+    }
+
+    // The bundle holds the secret key of the first test from RFC8032 §7.1,
+    // and a self-signed certificate for it, made with:
+    //
+    //     openssl pkcs12 -export -inkey key.pem -in cert.pem \
+    //         -keypbe PBE-SHA1-3DES -certpbe PBE-SHA1-3DES -macalg sha1
+    #[test]
+    fn pkcs12_ed25519_bundle() {
+        let bundle: Vec<u8> = read_bundle("res/ed25519.p12");
+        let expected_public: Vec<u8> = FromHex::from_hex(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a").unwrap();
+        let (keypair, certificates) = Keypair::try_from_pkcs12(&bundle, "ed25519-dalek").unwrap();
+
+        assert_eq!(&keypair.public.to_bytes()[..], &expected_public[..]);
+        assert_eq!(certificates.len(), 1);
+        assert_eq!(certificates[0].0[0], 0x30);
+    }
+
+    #[test]
+    fn pkcs12_wrong_password_is_rejected() {
+        let bundle: Vec<u8> = read_bundle("res/ed25519.p12");
+
+        assert_eq!(Keypair::try_from_pkcs12(&bundle, "hunter2").unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+
+    #[test]
+    fn pkcs12_p256_key_is_rejected() {
+        let bundle: Vec<u8> = read_bundle("res/p256.p12");
+
+        assert_eq!(Keypair::try_from_pkcs12(&bundle, "ed25519-dalek").unwrap_err(),
+                   SignatureError::InvalidKeyLength);
+    }
+}
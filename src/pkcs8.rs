@@ -0,0 +1,318 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding and decoding of RFC8410 PKCS#8 private keys.
+//!
+//! A `SecretKey` is encoded as a version 1 `OneAsymmetricKey` (RFC5208),
+//! holding only the key.  A `Keypair` is encoded as a version 2
+//! `OneAsymmetricKey` (RFC5958), which also holds the public key, in its
+//! `publicKey` field.  Either version may be decoded as either type.
+
+use alloc::vec::Vec;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use ed25519::{PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+use errors::SignatureError;
+
+/// The DER encoding of the `AlgorithmIdentifier` for Ed25519, from RFC8410.
+const ED25519_ALGORITHM_IDENTIFIER: [u8; 7] = [0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70];
+
+/// Split the DER value with the given `tag` from the front of `input`.
+///
+/// # Returns
+///
+/// The contents of the value, and the remainder of `input`, or `None` if
+/// `input` does not begin with a well-formed value with that `tag`.  As DER
+/// requires, the length must be in its shortest form, so that each value has
+/// exactly one encoding.
+fn der_value(tag: u8, input: &[u8]) -> Option<(&[u8], &[u8])> {
+    if input.len() < 2 || input[0] != tag {
+        return None;
+    }
+    let (length, header): (usize, usize) = match input[1] {
+        n if n < 0x80 => (n as usize, 2),
+        0x81 if input.len() >= 3 && input[2] >= 0x80 => (input[2] as usize, 3),
+        0x82 if input.len() >= 4 && input[2] != 0 => (((input[2] as usize) << 8) | input[3] as usize, 4),
+        _ => return None,
+    };
+    if input.len() < header + length {
+        return None;
+    }
+    Some((&input[header..header + length], &input[header + length..]))
+}
+
+/// Extract the `SecretKey`, and the `PublicKey` if there is one, from an
+/// RFC8410 PKCS#8 `OneAsymmetricKey`.
+///
+/// # Returns
+///
+/// The keys, or `SignatureError::InvalidKeyLength` if the key is not an
+/// Ed25519 key, or `SignatureError::InvalidEncoding` if it is malformed.
+fn keys_from_pkcs8(der: &[u8]) -> Result<(SecretKey, Option<PublicKey>), SignatureError> {
+    let (key, trailing) = der_value(0x30, der).ok_or(SignatureError::InvalidEncoding)?;
+
+    if !trailing.is_empty() {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let (version, key) = der_value(0x02, key).ok_or(SignatureError::InvalidEncoding)?;
+
+    if version != [0] && version != [1] {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let (_, after_algorithm) = der_value(0x30, key).ok_or(SignatureError::InvalidEncoding)?;
+
+    if key[..key.len() - after_algorithm.len()] != ED25519_ALGORITHM_IDENTIFIER[..] {
+        return Err(SignatureError::InvalidKeyLength);
+    }
+    let (private_key, mut rest) = der_value(0x04, after_algorithm).ok_or(SignatureError::InvalidEncoding)?;
+    let (seed, seed_rest) = der_value(0x04, private_key).ok_or(SignatureError::InvalidEncoding)?;
+
+    if seed.len() != SECRET_KEY_LENGTH || !seed_rest.is_empty() {
+        return Err(SignatureError::InvalidKeyLength);
+    }
+    let secret: SecretKey = SecretKey::from_bytes(seed).or(Err(SignatureError::InvalidKeyLength))?;
+
+    // The optional `[0] IMPLICIT Attributes` are skipped.
+    if let Some((_, after_attributes)) = der_value(0xa0, rest) {
+        rest = after_attributes;
+    }
+    // The optional `[1] IMPLICIT BIT STRING publicKey` is only allowed in v2.
+    let public: Option<PublicKey> = match der_value(0x81, rest) {
+        Some((bits, after_public_key)) => {
+            if version != [1] || bits.len() != 1 + PUBLIC_KEY_LENGTH || bits[0] != 0 {
+                return Err(SignatureError::InvalidEncoding);
+            }
+            rest = after_public_key;
+            Some(PublicKey::from_bytes(&bits[1..]).or(Err(SignatureError::InvalidEncoding))?)
+        },
+        None => None,
+    };
+    if !rest.is_empty() {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    Ok((secret, public))
+}
+
+/// Extract the `SecretKey` from an RFC8410 PKCS#8 `OneAsymmetricKey`.
+///
+/// # Returns
+///
+/// As for `keys_from_pkcs8()`.
+pub(crate) fn secret_key_from_pkcs8(der: &[u8]) -> Result<SecretKey, SignatureError> {
+    Ok(keys_from_pkcs8(der)?.0)
+}
+
+/// Encode the `secret` key, and the `public` key if given, as an RFC8410
+/// PKCS#8 `OneAsymmetricKey`: version 2 if there is a `public` key, and
+/// version 1 otherwise.
+fn keys_to_pkcs8(secret: &SecretKey, public: Option<&PublicKey>) -> Vec<u8> {
+    let mut der: Vec<u8> = Vec::new();
+    let length: u8 = match public {
+        Some(_) => 0x51,
+        None => 0x2e,
+    };
+
+    der.extend_from_slice(&[0x30, length, 0x02, 0x01, public.is_some() as u8]);
+    der.extend_from_slice(&ED25519_ALGORITHM_IDENTIFIER);
+    der.extend_from_slice(&[0x04, 0x22, 0x04, 0x20]);
+    der.extend_from_slice(secret.as_bytes());
+
+    if let Some(public) = public {
+        der.extend_from_slice(&[0x81, 0x21, 0x00]);
+        der.extend_from_slice(public.as_bytes());
+    }
+    der
+}
+
+impl SecretKey {
+    /// Encode this `SecretKey` as a version 1 PKCS#8 `OneAsymmetricKey`, in
+    /// DER, as described in RFC8410.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        keys_to_pkcs8(self, None)
+    }
+
+    /// Decode a `SecretKey` from a version 1 or version 2 PKCS#8
+    /// `OneAsymmetricKey`, in DER, as described in RFC8410.  The public key
+    /// in a version 2 encoding is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `SecretKey`, or whose error value
+    /// is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the key is not an Ed25519 key,
+    ///   or
+    /// * `SignatureError::InvalidEncoding` if it is malformed.
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<SecretKey, SignatureError> {
+        secret_key_from_pkcs8(der)
+    }
+}
+
+impl Keypair {
+    /// Encode this `Keypair` as a version 2 PKCS#8 `OneAsymmetricKey`, in
+    /// DER, as described in RFC8410, with the public key in its `publicKey`
+    /// field.
+    ///
+    /// For a version 1 encoding, without the public key, use
+    /// `SecretKey::to_pkcs8_der()` on the `secret` key.
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        keys_to_pkcs8(&self.secret, Some(&self.public))
+    }
+
+    /// Decode a `Keypair` from a version 1 or version 2 PKCS#8
+    /// `OneAsymmetricKey`, in DER, as described in RFC8410.
+    ///
+    /// If the encoding has no public key, it is derived from the secret key,
+    /// using SHA-512.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Keypair`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the key is not an Ed25519 key,
+    /// * `SignatureError::InvalidEncoding` if it is malformed, or
+    /// * `SignatureError::InconsistentKeypair` if its public key is not the
+    ///   one derived from its secret key.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "pkcs8")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// // The example private key from RFC8410, section 10.3.
+    /// let der: [u8; 48] = [
+    ///     0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70,
+    ///     0x04, 0x22, 0x04, 0x20, 0xd4, 0xee, 0x72, 0xdb, 0xf9, 0x13, 0x58, 0x4a,
+    ///     0xd5, 0xb6, 0xd8, 0xf1, 0xf7, 0x69, 0xf8, 0xad, 0x3a, 0xfe, 0x7c, 0x28,
+    ///     0xcb, 0xf1, 0xd4, 0xfb, 0xe0, 0x97, 0xa8, 0x8f, 0x44, 0x75, 0x58, 0x42, ];
+    /// let keypair: Keypair = Keypair::from_pkcs8_der(&der).unwrap();
+    ///
+    /// assert_eq!(keypair.secret.to_pkcs8_der(), &der[..]);
+    /// assert_eq!(Keypair::from_pkcs8_der(&keypair.to_pkcs8_der()).unwrap().public,
+    ///            keypair.public);
+    /// # }
+    /// # #[cfg(not(feature = "pkcs8"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_pkcs8_der(der: &[u8]) -> Result<Keypair, SignatureError> {
+        let (secret, public) = keys_from_pkcs8(der)?;
+
+        match public {
+            Some(public) => {
+                let keypair: Keypair = Keypair{ secret, public };
+
+                keypair.verify_own_public_key::<Sha512>()?;
+                Ok(keypair)
+            },
+            None => Ok(Keypair{ public: PublicKey::from_secret::<Sha512>(&secret), secret }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    /// The example private key from RFC8410, section 10.3.
+    static RFC8410_V1: [u8; 48] = [
+        0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70,
+        0x04, 0x22, 0x04, 0x20, 0xd4, 0xee, 0x72, 0xdb, 0xf9, 0x13, 0x58, 0x4a,
+        0xd5, 0xb6, 0xd8, 0xf1, 0xf7, 0x69, 0xf8, 0xad, 0x3a, 0xfe, 0x7c, 0x28,
+        0xcb, 0xf1, 0xd4, 0xfb, 0xe0, 0x97, 0xa8, 0x8f, 0x44, 0x75, 0x58, 0x42, ];
+
+    /// The example private key with attributes and a public key, from RFC8410,
+    /// section 10.3.
+    static RFC8410_V2: [u8; 116] = [
+        0x30, 0x72, 0x02, 0x01, 0x01, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70,
+        0x04, 0x22, 0x04, 0x20, 0xd4, 0xee, 0x72, 0xdb, 0xf9, 0x13, 0x58, 0x4a,
+        0xd5, 0xb6, 0xd8, 0xf1, 0xf7, 0x69, 0xf8, 0xad, 0x3a, 0xfe, 0x7c, 0x28,
+        0xcb, 0xf1, 0xd4, 0xfb, 0xe0, 0x97, 0xa8, 0x8f, 0x44, 0x75, 0x58, 0x42,
+        0xa0, 0x1f, 0x30, 0x1d, 0x06, 0x0a, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d,
+        0x01, 0x09, 0x09, 0x14, 0x31, 0x0f, 0x0c, 0x0d, 0x43, 0x75, 0x72, 0x64,
+        0x6c, 0x65, 0x20, 0x43, 0x68, 0x61, 0x69, 0x72, 0x73, 0x81, 0x21, 0x00,
+        0x19, 0xbf, 0x44, 0x09, 0x69, 0x84, 0xcd, 0xfe, 0x85, 0x41, 0xba, 0xc1,
+        0x67, 0xdc, 0x3b, 0x96, 0xc8, 0x50, 0x86, 0xaa, 0x30, 0xb6, 0xb6, 0xcb,
+        0x0c, 0x5c, 0x38, 0xad, 0x70, 0x31, 0x66, 0xe1, ];
+
+    #[test]
+    fn pkcs8_rfc8410_examples() {
+        let v1: Keypair = Keypair::from_pkcs8_der(&RFC8410_V1).unwrap();
+        let v2: Keypair = Keypair::from_pkcs8_der(&RFC8410_V2).unwrap();
+
+        assert_eq!(v1.public, v2.public);
+        assert_eq!(v1.public.as_bytes(), &RFC8410_V2[84..]);
+        assert_eq!(SecretKey::from_pkcs8_der(&RFC8410_V2).unwrap().as_bytes(), v1.secret.as_bytes());
+        assert_eq!(v1.secret.to_pkcs8_der(), &RFC8410_V1[..]);
+    }
+
+    #[test]
+    fn pkcs8_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("pkcs8");
+        let v1: Vec<u8> = keypair.secret.to_pkcs8_der();
+        let v2: Vec<u8> = keypair.to_pkcs8_der();
+
+        assert_eq!(v2[4], 1);
+        assert_eq!(&v2[..48], &{ let mut v: Vec<u8> = v1.clone(); v[1] = 0x51; v[4] = 1; v }[..]);
+        assert_eq!(SecretKey::from_pkcs8_der(&v1).unwrap().as_bytes(), keypair.secret.as_bytes());
+        assert_eq!(Keypair::from_pkcs8_der(&v1).unwrap().public, keypair.public);
+        assert_eq!(Keypair::from_pkcs8_der(&v2).unwrap().to_raw_bytes()[..],
+                   keypair.to_raw_bytes()[..]);
+    }
+
+    #[test]
+    fn pkcs8_inconsistent_keypair_is_rejected() {
+        let keypair: Keypair = keypair_for_testing("pkcs8");
+        let other: Keypair = keypair_for_testing("other");
+        let der: Vec<u8> = keys_to_pkcs8(&keypair.secret, Some(&other.public));
+
+        assert_eq!(Keypair::from_pkcs8_der(&der).unwrap_err(), SignatureError::InconsistentKeypair);
+    }
+
+    #[test]
+    fn pkcs8_malformed_is_rejected() {
+        let mut v1_with_public_key: Vec<u8> = keypair_for_testing("pkcs8").to_pkcs8_der();
+        let mut trailing: Vec<u8> = RFC8410_V1.to_vec();
+        let mut not_ed25519: Vec<u8> = RFC8410_V1.to_vec();
+        let mut outer_trailing: Vec<u8> = RFC8410_V1.to_vec();
+        let mut long_form: Vec<u8> = vec![0x30, 0x81];
+        let mut leading_zero: Vec<u8> = vec![0x30, 0x82, 0x00];
+
+        v1_with_public_key[4] = 0;
+        trailing[1] += 2;
+        trailing.extend_from_slice(&[0x05, 0x00]);
+        not_ed25519[11] = 0x71;  // id-Ed448
+        outer_trailing.extend_from_slice(&[0x05, 0x00]);
+        long_form.extend_from_slice(&RFC8410_V1[1..]);
+        leading_zero.extend_from_slice(&RFC8410_V1[1..]);
+
+        assert_eq!(SecretKey::from_pkcs8_der(&v1_with_public_key).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&trailing).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&outer_trailing).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&long_form).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&leading_zero).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&RFC8410_V1[..47]).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+        assert_eq!(SecretKey::from_pkcs8_der(&not_ed25519).unwrap_err(), SignatureError::InvalidKeyLength);
+    }
+}
@@ -0,0 +1,85 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signatures stored together with a fingerprint of their message.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use subtle::slices_equal;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// A signature together with a digest of the message which it signs, for
+/// storing (e.g. in an audit log) in place of the message itself.
+///
+/// The digest allows a message to be matched against the proof quickly, and
+/// the signature proves that the holder of the keypair signed it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SignatureProof {
+    /// The 64-byte digest of the message.
+    pub message_hash: [u8; 64],
+    /// The signature on the message.
+    pub signature: Signature,
+}
+
+impl SignatureProof {
+    /// Sign a `message` with a `keypair`, and record its digest with `D`.
+    pub fn create<D>(keypair: &Keypair, message: &[u8]) -> SignatureProof
+            where D: Digest<OutputSize = U64> + Default {
+
+        let mut message_hash: [u8; 64] = [0u8; 64];
+
+        message_hash.copy_from_slice(D::digest(message).as_slice());
+
+        SignatureProof{ message_hash, signature: keypair.sign::<D>(message) }
+    }
+
+    /// Check that this proof is for the given `message`, and that its
+    /// signature on the message is valid under `public_key`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the message matches the stored digest and the signature
+    /// is valid, `Err(SignatureError::InvalidSignature)` if the message does
+    /// not match, and otherwise an error as for `PublicKey::verify()`.
+    pub fn verify_against_message<D>(&self, public_key: &PublicKey, message: &[u8])
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        if slices_equal(&self.message_hash, D::digest(message).as_slice()) != 1 {
+            return Err(SignatureError::InvalidSignature);
+        }
+        public_key.verify::<D>(message, &self.signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn signature_proof_verifies_against_message() {
+        let keypair: Keypair = keypair_for_testing("proof");
+        let proof: SignatureProof = SignatureProof::create::<Sha512>(&keypair, b"test message");
+
+        assert_eq!(&proof.message_hash[..], Sha512::digest(b"test message").as_slice());
+        assert_eq!(proof.verify_against_message::<Sha512>(&keypair.public, b"test message"),
+                   Ok(()));
+        assert_eq!(proof.verify_against_message::<Sha512>(&keypair.public, b"other message"),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(proof.verify_against_message::<Sha512>(&keypair_for_testing("other").public,
+                                                          b"test message"),
+                   Err(SignatureError::InvalidSignature));
+    }
+}
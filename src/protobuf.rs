@@ -0,0 +1,228 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Protocol Buffers encodings of signatures and public keys, for use in gRPC
+//! services.
+//!
+//! A `Signature` is encoded as the message `ed25519.Signature` in
+//! `proto/ed25519.proto`, whose only field is `bytes signature = 1`, holding
+//! its 64 bytes.  A `PublicKey` is encoded likewise, as
+//! `ed25519.PublicKey`, holding its 32 bytes.  Code generated from that file,
+//! in any language, interoperates with these implementations.
+//!
+//! Neither type has a meaningful `Default`, so rather than
+//! `prost::Message::decode()`, use `Signature::deserialize_from_proto()` and
+//! `PublicKey::deserialize_from_proto()`, which also reject encodings in
+//! which the field is missing.
+
+use std::vec::Vec;
+
+use prost::bytes::{Buf, BufMut};
+use prost::encoding;
+use prost::encoding::{DecodeContext, WireType};
+use prost::DecodeError;
+use prost::Message;
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{PublicKey, Signature};
+use errors::SignatureError;
+
+/// The tag of the only field in either message.
+const BYTES_TAG: u32 = 1;
+
+/// Decode the last occurrence of the `bytes` field with tag `BYTES_TAG` in a
+/// message, skipping any other fields.
+fn decode_bytes_field(mut buf: &[u8]) -> Result<Vec<u8>, SignatureError> {
+    let mut field: Option<Vec<u8>> = None;
+
+    while buf.has_remaining() {
+        let (tag, wire_type) = encoding::decode_key(&mut buf).or(Err(SignatureError::InvalidEncoding))?;
+
+        if tag == BYTES_TAG {
+            let mut bytes: Vec<u8> = Vec::new();
+
+            encoding::bytes::merge(wire_type, &mut bytes, &mut buf, DecodeContext::default())
+                .or(Err(SignatureError::InvalidEncoding))?;
+            field = Some(bytes);
+        } else {
+            encoding::skip_field(wire_type, tag, &mut buf, DecodeContext::default())
+                .or(Err(SignatureError::InvalidEncoding))?;
+        }
+    }
+    field.ok_or(SignatureError::InvalidEncoding)
+}
+
+/// Merge a `bytes` field from `buf`, for `prost::Message::merge_field()`.
+fn merge_bytes_field<B>(wire_type: WireType, buf: &mut B, ctx: DecodeContext)
+        -> Result<Vec<u8>, DecodeError> where B: Buf {
+
+    let mut bytes: Vec<u8> = Vec::new();
+
+    encoding::bytes::merge(wire_type, &mut bytes, buf, ctx)?;
+    Ok(bytes)
+}
+
+impl Message for Signature {
+    fn encode_raw<B>(&self, buf: &mut B) where B: BufMut {
+        encoding::bytes::encode(BYTES_TAG, &self.to_bytes().to_vec(), buf);
+    }
+
+    fn merge_field<B>(&mut self, tag: u32, wire_type: WireType, buf: &mut B, ctx: DecodeContext)
+            -> Result<(), DecodeError> where B: Buf {
+
+        if tag != BYTES_TAG {
+            return encoding::skip_field(wire_type, tag, buf, ctx);
+        }
+        let bytes: Vec<u8> = merge_bytes_field(wire_type, buf, ctx)?;
+
+        *self = Signature::from_bytes(&bytes)
+            .or(Err(DecodeError::new("ed25519 signature is not 64 bytes")))?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> usize {
+        encoding::bytes::encoded_len(BYTES_TAG, &self.to_bytes().to_vec())
+    }
+
+    fn clear(&mut self) {
+        *self = Signature{ r: CompressedEdwardsY([0u8; 32]), s: Scalar::zero() };
+    }
+}
+
+impl Signature {
+    /// Encode this `Signature` as an `ed25519.Signature` protobuf message.
+    pub fn serialize_to_proto(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Decode a `Signature` from an `ed25519.Signature` protobuf message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Signature`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the message is malformed, its
+    /// `signature` field is missing, or the field is not 64 bytes.
+    pub fn deserialize_from_proto(bytes: &[u8]) -> Result<Signature, SignatureError> {
+        Signature::from_bytes(&decode_bytes_field(bytes)?).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+impl Message for PublicKey {
+    fn encode_raw<B>(&self, buf: &mut B) where B: BufMut {
+        encoding::bytes::encode(BYTES_TAG, &self.to_bytes().to_vec(), buf);
+    }
+
+    fn merge_field<B>(&mut self, tag: u32, wire_type: WireType, buf: &mut B, ctx: DecodeContext)
+            -> Result<(), DecodeError> where B: Buf {
+
+        if tag != BYTES_TAG {
+            return encoding::skip_field(wire_type, tag, buf, ctx);
+        }
+        let bytes: Vec<u8> = merge_bytes_field(wire_type, buf, ctx)?;
+
+        *self = PublicKey::from_bytes(&bytes).map_err(DecodeError::new)?;
+        Ok(())
+    }
+
+    fn encoded_len(&self) -> usize {
+        encoding::bytes::encoded_len(BYTES_TAG, &self.to_bytes().to_vec())
+    }
+
+    fn clear(&mut self) {
+        *self = PublicKey(CompressedEdwardsY([0u8; 32]));
+    }
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` as an `ed25519.PublicKey` protobuf message.
+    pub fn serialize_to_proto(&self) -> Vec<u8> {
+        self.encode_to_vec()
+    }
+
+    /// Decode a `PublicKey` from an `ed25519.PublicKey` protobuf message.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `PublicKey`, or whose error value
+    /// is `SignatureError::InvalidEncoding` if the message is malformed, its
+    /// `public_key` field is missing, or the field is not a valid public key.
+    pub fn deserialize_from_proto(bytes: &[u8]) -> Result<PublicKey, SignatureError> {
+        PublicKey::from_bytes(&decode_bytes_field(bytes)?).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn signature_proto_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("protobuf");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let encoded: Vec<u8> = signature.serialize_to_proto();
+
+        assert_eq!(&encoded[..2], &[0x0a, 64]);
+        assert_eq!(&encoded[2..], &signature.to_bytes()[..]);
+        assert_eq!(encoded.len(), signature.encoded_len());
+        assert_eq!(Signature::deserialize_from_proto(&encoded), Ok(signature));
+
+        let mut merged: Signature = keypair.sign::<Sha512>(b"other message");
+
+        merged.merge(&encoded[..]).unwrap();
+        assert_eq!(merged, signature);
+    }
+
+    #[test]
+    fn public_key_proto_roundtrip() {
+        let public: PublicKey = keypair_for_testing("protobuf").public;
+        let encoded: Vec<u8> = public.serialize_to_proto();
+
+        assert_eq!(&encoded[..2], &[0x0a, 32]);
+        assert_eq!(&encoded[2..], public.as_bytes());
+        assert_eq!(PublicKey::deserialize_from_proto(&encoded), Ok(public));
+    }
+
+    #[test]
+    fn proto_unknown_fields_are_skipped() {
+        let public: PublicKey = keypair_for_testing("protobuf").public;
+        let mut encoded: Vec<u8> = vec![0x10, 0x2a];  // Field 2, varint 42.
+
+        encoded.extend_from_slice(&public.serialize_to_proto());
+        assert_eq!(PublicKey::deserialize_from_proto(&encoded), Ok(public));
+    }
+
+    #[test]
+    fn proto_malformed_is_rejected() {
+        let public: PublicKey = keypair_for_testing("protobuf").public;
+        let mut signature: Signature = keypair_for_testing("protobuf").sign::<Sha512>(b"test");
+
+        assert_eq!(Signature::deserialize_from_proto(&[]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(Signature::deserialize_from_proto(&public.serialize_to_proto()),
+                   Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::deserialize_from_proto(&[0x0a, 32, 0]),
+                   Err(SignatureError::InvalidEncoding));
+        assert!(signature.merge(&public.serialize_to_proto()[..]).is_err());
+    }
+
+    #[test]
+    fn proto_merge_reports_why_a_public_key_is_invalid() {
+        use std::string::ToString;
+
+        let mut public: PublicKey = keypair_for_testing("protobuf").public;
+        let mut identity: Vec<u8> = vec![0x0a, 32, 1];
+
+        identity.extend_from_slice(&[0u8; 31]);
+        assert!(public.merge(&identity[..]).unwrap_err().to_string().contains("small order"));
+        assert!(public.merge(&[0x0a, 1, 0][..]).unwrap_err().to_string().contains("Wrong length"));
+    }
+}
@@ -0,0 +1,143 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! A registry of public keys, indexed by short key identifiers.
+
+use std::collections::HashMap;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{PublicKey, Signature};
+use ed25519::KEY_ID_LENGTH;
+use errors::SignatureError;
+
+/// A collection of `PublicKey`s, each indexed by its `PublicKey::key_id()`,
+/// the first `KEY_ID_LENGTH` bytes of the SHA-256 digest of the compressed
+/// public key.
+///
+/// # Note
+///
+/// Key identifiers are short, so an attacker can find a public key with any
+/// given identifier with about 2^64 work.  They are meant to select which key
+/// to try, not to authenticate it: only register keys you already trust.
+#[derive(Debug, Default)]
+pub struct PublicKeyRegistry {
+    inner: HashMap<[u8; KEY_ID_LENGTH], PublicKey>,
+}
+
+impl PublicKeyRegistry {
+    /// Create a new, empty `PublicKeyRegistry`.
+    pub fn new() -> PublicKeyRegistry {
+        PublicKeyRegistry{ inner: HashMap::new() }
+    }
+
+    /// Compute the key identifier of a `public_key`, as by
+    /// `PublicKey::key_id()`.
+    pub fn key_id(public_key: &PublicKey) -> [u8; KEY_ID_LENGTH] {
+        public_key.key_id()
+    }
+
+    /// Add a `public_key` to this registry, replacing any other key with the
+    /// same identifier.
+    ///
+    /// # Returns
+    ///
+    /// The key identifier of the `public_key`.
+    pub fn register(&mut self, public_key: PublicKey) -> [u8; KEY_ID_LENGTH] {
+        let key_id: [u8; KEY_ID_LENGTH] = PublicKeyRegistry::key_id(&public_key);
+
+        self.inner.insert(key_id, public_key);
+        key_id
+    }
+
+    /// Look up the public key with the given `key_id`.
+    pub fn get(&self, key_id: &[u8; KEY_ID_LENGTH]) -> Option<&PublicKey> {
+        self.inner.get(key_id)
+    }
+
+    /// Verify a `signature` on a `message` with the public key whose
+    /// identifier is `key_id`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the signature is valid,
+    /// * `Err(SignatureError::KeyNotFound)` if no key with that identifier has
+    ///   been registered, or
+    /// * an error as for `PublicKey::verify()` if the signature is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rand;
+    /// extern crate sha2;
+    /// extern crate ed25519_dalek;
+    ///
+    /// # #[cfg(feature = "sha2")]
+    /// # fn main() {
+    /// # use rand::OsRng;
+    /// use sha2::Sha512;
+    /// use ed25519_dalek::{Keypair, PublicKeyRegistry, Signature};
+    ///
+    /// # let mut csprng: OsRng = OsRng::new().unwrap();
+    /// # let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+    /// let mut registry: PublicKeyRegistry = PublicKeyRegistry::new();
+    /// let key_id: [u8; 8] = registry.register(keypair.public);
+    ///
+    /// let signature: Signature = keypair.sign::<Sha512>(b"hello");
+    ///
+    /// assert!(registry.verify::<Sha512>(&key_id, b"hello", &signature).is_ok());
+    /// # }
+    /// # #[cfg(not(feature = "sha2"))]
+    /// # fn main() { }
+    /// ```
+    pub fn verify<D>(&self, key_id: &[u8; KEY_ID_LENGTH], message: &[u8], signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let public_key: &PublicKey = self.get(key_id).ok_or(SignatureError::KeyNotFound)?;
+
+        public_key.verify::<D>(message, signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn registry_verifies_with_the_right_key() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+        let mut registry: PublicKeyRegistry = PublicKeyRegistry::new();
+
+        let alice_id: [u8; KEY_ID_LENGTH] = registry.register(alice.public);
+        let bob_id: [u8; KEY_ID_LENGTH] = registry.register(bob.public);
+        let signature: Signature = alice.sign::<Sha512>(b"test message");
+
+        assert_eq!(registry.get(&alice_id), Some(&alice.public));
+        assert_eq!(registry.verify::<Sha512>(&alice_id, b"test message", &signature), Ok(()));
+        assert_eq!(registry.verify::<Sha512>(&bob_id, b"test message", &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn registry_unknown_key_id() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let registry: PublicKeyRegistry = PublicKeyRegistry::new();
+        let signature: Signature = alice.sign::<Sha512>(b"test message");
+
+        assert_eq!(registry.verify::<Sha512>(&PublicKeyRegistry::key_id(&alice.public),
+                                             b"test message", &signature),
+                   Err(SignatureError::KeyNotFound));
+    }
+}
@@ -0,0 +1,197 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Detection of replayed signatures.
+
+use std::collections::HashSet;
+use std::vec::Vec;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{PublicKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use errors::SignatureError;
+
+/// A filter which remembers every signature it has successfully verified, and
+/// refuses to accept any of them a second time.
+///
+/// # Note
+///
+/// Signatures are remembered in a canonical form, with `s` reduced modulo the
+/// group order and `r` re-encoded, so that an attacker cannot sneak a replay
+/// past the filter by submitting a malleated copy of a signature which it has
+/// already seen.
+///
+/// The filter grows without bound.  Callers which verify a large number of
+/// signatures over a long period of time should periodically replace it, and
+/// should combine it with some other mechanism (such as an expiry time within
+/// the signed message) to reject replays which span those periods.
+#[derive(Debug, Default)]
+pub struct ReplayFilter {
+    seen: HashSet<[u8; SIGNATURE_LENGTH]>,
+}
+
+impl ReplayFilter {
+    /// Create a new, empty `ReplayFilter`.
+    pub fn new() -> ReplayFilter {
+        ReplayFilter{ seen: HashSet::new() }
+    }
+
+    /// Verify a `signature` on a `message` with `public_key`, accepting any
+    /// given signature at most once.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the signature is valid and has not been seen before,
+    /// * `Err(SignatureError::InvalidSignature)` if the signature is invalid,
+    /// * `Err(SignatureError::ReplayedSignature)` if the signature is valid, but
+    ///   it has already been accepted by this filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// extern crate rand;
+    /// extern crate sha2;
+    /// extern crate ed25519_dalek;
+    ///
+    /// # fn main() {
+    /// use rand::OsRng;
+    /// use sha2::Sha512;
+    /// use ed25519_dalek::{Keypair, ReplayFilter, Signature, SignatureError};
+    ///
+    /// let mut csprng: OsRng = OsRng::new().unwrap();
+    /// let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+    /// let message: &[u8] = b"Transfer 10 coins to Bob";
+    /// let signature: Signature = keypair.sign::<Sha512>(message);
+    ///
+    /// let mut filter: ReplayFilter = ReplayFilter::new();
+    ///
+    /// assert_eq!(filter.verify_once::<Sha512>(&keypair.public, message, &signature),
+    ///            Ok(()));
+    /// assert_eq!(filter.verify_once::<Sha512>(&keypair.public, message, &signature),
+    ///            Err(SignatureError::ReplayedSignature));
+    /// # }
+    /// ```
+    pub fn verify_once<D>(&mut self,
+                          public_key: &PublicKey,
+                          message: &[u8],
+                          signature: &Signature) -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        public_key.verify::<D>(message, signature)?;
+
+        if !self.seen.insert(canonical_bytes(signature)) {
+            return Err(SignatureError::ReplayedSignature);
+        }
+        Ok(())
+    }
+}
+
+/// Remove exact duplicates from a list of public keys and signatures, i.e.
+/// pairs with the same public key and the same signature bytes, keeping the
+/// first occurrence of each and otherwise preserving their order.
+///
+/// No signatures are verified, and different encodings of the same signature
+/// are not considered duplicates; use a `ReplayFilter` for that.
+pub fn deduplicate_signatures(signatures: Vec<(PublicKey, Signature)>)
+        -> Vec<(PublicKey, Signature)> {
+
+    let mut seen: HashSet<([u8; PUBLIC_KEY_LENGTH], [u8; SIGNATURE_LENGTH])> = HashSet::new();
+
+    signatures.into_iter()
+        .filter(|(public_key, signature)| {
+            seen.insert((public_key.to_bytes(), signature.to_bytes()))
+        })
+        .collect()
+}
+
+/// Encode a (valid) `signature` canonically, such that all of the encodings of
+/// the same signature map to the same bytes.
+fn canonical_bytes(signature: &Signature) -> [u8; SIGNATURE_LENGTH] {
+    let mut bytes: [u8; SIGNATURE_LENGTH] = signature.to_bytes();
+    let mut wide_s: [u8; 64] = [0u8; 64];
+
+    // A valid signature's `r` always decompresses.
+    if let Some(r) = signature.r.decompress() {
+        bytes[..32].copy_from_slice(r.compress().as_bytes());
+    }
+    wide_s[..32].copy_from_slice(&bytes[32..]);
+    bytes[32..].copy_from_slice(Scalar::reduce(&wide_s).as_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use sha2::Sha512;
+    use curve25519_dalek::constants;
+    use super::*;
+    use ed25519::{Keypair, keypair_for_testing};
+
+    #[test]
+    fn malleated_replay_is_rejected() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+        let message: &[u8] = b"";
+        let signature: Signature = keypair.sign::<Sha512>(message);
+        let mut filter: ReplayFilter = ReplayFilter::new();
+
+        // Produce s' = s + l, which (with overwhelming probability) still fits
+        // within 253 bits, and is thus accepted by the verification equation.
+        let mut malleated: Signature = signature;
+        let mut carry: u16 = 0;
+        for i in 0..32 {
+            let sum: u16 = signature.s[i] as u16 + constants::l[i] as u16 + carry;
+            malleated.s[i] = sum as u8;
+            carry = sum >> 8;
+        }
+
+        assert!(keypair.public.verify::<Sha512>(message, &malleated).is_ok());
+        assert_eq!(filter.verify_once::<Sha512>(&keypair.public, message, &signature),
+                   Ok(()));
+        assert_eq!(filter.verify_once::<Sha512>(&keypair.public, message, &malleated),
+                   Err(SignatureError::ReplayedSignature));
+    }
+
+    #[test]
+    fn deduplicate_signatures_removes_exact_duplicates() {
+        let alice: Keypair = keypair_for_testing("alice");
+        let bob: Keypair = keypair_for_testing("bob");
+        let first: Signature = alice.sign::<Sha512>(b"first");
+        let second: Signature = alice.sign::<Sha512>(b"second");
+
+        assert!(first.is_identical_to(&first));
+        assert!(!first.is_identical_to(&second));
+
+        let deduplicated = deduplicate_signatures(vec![(alice.public, first),
+                                                       (alice.public, second),
+                                                       (alice.public, first),
+                                                       (bob.public, first)]);
+
+        assert_eq!(deduplicated, vec![(alice.public, first),
+                                      (alice.public, second),
+                                      (bob.public, first)]);
+    }
+
+    #[test]
+    fn invalid_signatures_are_not_remembered() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+        let signature: Signature = keypair.sign::<Sha512>(b"good");
+        let mut filter: ReplayFilter = ReplayFilter::new();
+
+        assert_eq!(filter.verify_once::<Sha512>(&keypair.public, b"bad", &signature),
+                   Err(SignatureError::InvalidSignature));
+        assert_eq!(filter.verify_once::<Sha512>(&keypair.public, b"good", &signature),
+                   Ok(()));
+    }
+}
@@ -0,0 +1,254 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Schnorr ring signatures over edwards25519, in the construction of Abe,
+//! Ohkubo, and Suzuki ("1-out-of-n Signatures from a Variety of Keys",
+//! ASIACRYPT 2002).
+//!
+//! A ring signature shows that the message was signed by the holder of one
+//! of the secret keys for a set (the "ring") of public keys, without
+//! revealing which one.  These are not ed25519 signatures, and are not
+//! linkable: two signatures by the same signer cannot be recognised as such.
+
+use std::vec::Vec;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::vartime;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{ExpandedSecretKey, Keypair, PublicKey};
+use errors::SignatureError;
+
+/// The domain separation string for the challenges of a ring signature.
+const RING_DOMAIN: &[u8] = b"ed25519-dalek ring signature";
+
+/// The domain separation string for the signer's deterministic nonces.
+const NONCE_DOMAIN: &[u8] = b"ed25519-dalek ring signature nonce";
+
+/// A ring signature, consisting of the first challenge and one response for
+/// each member of the ring.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RingSignature {
+    challenge: Scalar,
+    responses: Vec<Scalar>,
+}
+
+impl RingSignature {
+    /// Convert this ring signature to bytes: the 32-byte challenge followed by
+    /// each 32-byte response, in the order of the ring.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(32 * (1 + self.responses.len()));
+
+        bytes.extend_from_slice(self.challenge.as_bytes());
+        for response in self.responses.iter() {
+            bytes.extend_from_slice(response.as_bytes());
+        }
+        bytes
+    }
+
+    /// Construct a ring signature from the bytes produced by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `RingSignature`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the length of the bytes
+    /// is not a multiple of 32 of at least 64.
+    pub fn from_bytes(bytes: &[u8]) -> Result<RingSignature, SignatureError> {
+        if bytes.len() < 64 || !bytes.len().is_multiple_of(32) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        let mut scalars = bytes.chunks(32).map(|chunk| Scalar(*array_ref!(chunk, 0, 32)));
+        let challenge: Scalar = scalars.next().unwrap();
+
+        Ok(RingSignature{ challenge, responses: scalars.collect() })
+    }
+}
+
+/// Hash the ring and the message together, for use in every challenge.
+fn ring_prefix<D>(ring: &[PublicKey], message: &[u8]) -> [u8; 64]
+        where D: Digest<OutputSize = U64> + Default {
+
+    let mut h: D = D::default();
+    let mut prefix: [u8; 64] = [0u8; 64];
+
+    h.input(RING_DOMAIN);
+    h.input(&(ring.len() as u64).to_le_bytes());
+    for public_key in ring {
+        h.input(public_key.as_bytes());
+    }
+    h.input(message);
+    prefix.copy_from_slice(h.result().as_slice());
+    prefix
+}
+
+/// Compute the challenge for the next member of the ring from a commitment.
+fn challenge<D>(prefix: &[u8; 64], commitment: &CompressedEdwardsY) -> Scalar
+        where D: Digest<OutputSize = U64> + Default {
+
+    let mut h: D = D::default();
+    let mut hash: [u8; 64] = [0u8; 64];
+
+    h.input(prefix);
+    h.input(commitment.as_bytes());
+    hash.copy_from_slice(h.result().as_slice());
+    Scalar::reduce(&hash)
+}
+
+/// Derive the signer's `index`th nonce deterministically from its secret key,
+/// in the manner of ed25519.
+fn nonce<D>(expanded: &ExpandedSecretKey, index: usize, prefix: &[u8; 64]) -> Scalar
+        where D: Digest<OutputSize = U64> + Default {
+
+    let mut h: D = D::default();
+    let mut hash: [u8; 64] = [0u8; 64];
+
+    h.input(NONCE_DOMAIN);
+    h.input(&expanded.nonce);
+    h.input(&(index as u64).to_le_bytes());
+    h.input(prefix);
+    hash.copy_from_slice(h.result().as_slice());
+    Scalar::reduce(&hash)
+}
+
+/// Sign a `message` with `my_keypair`, as an anonymous member of the `ring`
+/// of public keys, using the hash function `D`.
+///
+/// Signing is deterministic: the same keypair, ring, and message always
+/// produce the same signature.  The order of the ring matters, and the
+/// verifier must use the same order.
+///
+/// # Panics
+///
+/// If `my_keypair.public` is not a member of the `ring`, or if any public key
+/// in the ring is not a valid point.
+pub fn ring_sign<D>(message: &[u8], my_keypair: &Keypair, ring: &[PublicKey]) -> RingSignature
+        where D: Digest<OutputSize = U64> + Default {
+
+    let n: usize = ring.len();
+    let me: usize = ring.iter().position(|public_key| public_key == &my_keypair.public)
+        .expect("the signer's public key must be a member of the ring");
+    let expanded: ExpandedSecretKey = my_keypair.secret.expand::<D>();
+    let prefix: [u8; 64] = ring_prefix::<D>(ring, message);
+    let mut challenges: Vec<Scalar> = vec![Scalar::zero(); n];
+    let mut responses: Vec<Scalar> = vec![Scalar::zero(); n];
+
+    let alpha: Scalar = nonce::<D>(&expanded, me, &prefix);
+    let commitment: ExtendedPoint = &alpha * &constants::ED25519_BASEPOINT_TABLE;
+
+    challenges[(me + 1) % n] = challenge::<D>(&prefix, &commitment.compress());
+
+    let mut i: usize = (me + 1) % n;
+    while i != me {
+        let a: ExtendedPoint = ring[i].0.decompress()
+            .expect("every public key in the ring must be a valid point");
+
+        responses[i] = nonce::<D>(&expanded, i, &prefix);
+        let commitment: ExtendedPoint = vartime::double_scalar_mult_basepoint(&challenges[i], &a,
+                                                                              &responses[i]);
+        challenges[(i + 1) % n] = challenge::<D>(&prefix, &commitment.compress());
+        i = (i + 1) % n;
+    }
+    responses[me] = &alpha - &(&challenges[me] * &expanded.key);
+
+    RingSignature{ challenge: challenges[0], responses }
+}
+
+/// Verify that a ring `signature` on a `message` was made by the holder of
+/// the secret key for one of the public keys in the `ring`.
+///
+/// # Returns
+///
+/// `Ok(())` if the signature is valid, and
+/// `Err(SignatureError::InvalidSignature)` otherwise, including if the ring
+/// is not the size of the signature, or contains an invalid public key.
+pub fn ring_verify<D>(message: &[u8], ring: &[PublicKey], signature: &RingSignature)
+        -> Result<(), SignatureError>
+        where D: Digest<OutputSize = U64> + Default {
+
+    if ring.is_empty() || ring.len() != signature.responses.len() {
+        return Err(SignatureError::InvalidSignature);
+    }
+    let prefix: [u8; 64] = ring_prefix::<D>(ring, message);
+    let mut c: Scalar = signature.challenge;
+
+    for (public_key, response) in ring.iter().zip(signature.responses.iter()) {
+        let a: ExtendedPoint = public_key.0.decompress().ok_or(SignatureError::InvalidSignature)?;
+        let commitment: ExtendedPoint = vartime::double_scalar_mult_basepoint(&c, &a, response);
+
+        c = challenge::<D>(&prefix, &commitment.compress());
+    }
+    if c == signature.challenge {
+        Ok(())
+    } else {
+        Err(SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    fn ring() -> Vec<Keypair> {
+        ["alice", "bob", "carol"].iter().map(|name| keypair_for_testing(name)).collect()
+    }
+
+    #[test]
+    fn ring_signature_verifies() {
+        let keypairs: Vec<Keypair> = ring();
+        let ring: Vec<PublicKey> = keypairs.iter().map(|keypair| keypair.public).collect();
+
+        for keypair in keypairs.iter() {
+            let signature: RingSignature = ring_sign::<Sha512>(b"test message", keypair, &ring);
+
+            assert_eq!(ring_verify::<Sha512>(b"test message", &ring, &signature), Ok(()));
+            assert_eq!(ring_verify::<Sha512>(b"other message", &ring, &signature),
+                       Err(SignatureError::InvalidSignature));
+            assert_eq!(ring_verify::<Sha512>(b"test message", &ring[..2], &signature),
+                       Err(SignatureError::InvalidSignature));
+            assert_eq!(RingSignature::from_bytes(&signature.to_bytes()), Ok(signature));
+        }
+    }
+
+    #[test]
+    fn ring_of_one_verifies() {
+        let keypair: Keypair = keypair_for_testing("alice");
+        let signature: RingSignature = ring_sign::<Sha512>(b"test message", &keypair,
+                                                           &[keypair.public]);
+
+        assert_eq!(ring_verify::<Sha512>(b"test message", &[keypair.public], &signature), Ok(()));
+    }
+
+    #[test]
+    fn ring_signature_is_bound_to_ring() {
+        let keypairs: Vec<Keypair> = ring();
+        let ring: Vec<PublicKey> = keypairs.iter().map(|keypair| keypair.public).collect();
+        let other: Vec<PublicKey> = vec![ring[0], ring[1], keypair_for_testing("dave").public];
+        let signature: RingSignature = ring_sign::<Sha512>(b"test message", &keypairs[0], &ring);
+
+        assert_eq!(ring_verify::<Sha512>(b"test message", &other, &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ring_sign_outside_ring_panics() {
+        let keypairs: Vec<Keypair> = ring();
+        let ring: Vec<PublicKey> = keypairs[1..].iter().map(|keypair| keypair.public).collect();
+
+        ring_sign::<Sha512>(b"test message", &keypairs[0], &ring);
+    }
+}
@@ -0,0 +1,93 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Implementations of the RustCrypto `signature` traits.
+//!
+//! A `DigestSigner` is handed the state of a hash function which has already
+//! absorbed the message, so these implementations are of Ed25519ph, with an
+//! empty context: the 64-byte output of the caller's digest is signed as the
+//! prehashed message, while SHA-512 is used internally, as specified in
+//! RFC8032.  With SHA-512 as the caller's digest, this is exactly Ed25519ph;
+//! with another 512-bit hash, such as BLAKE2b, it is Ed25519ph over that hash.
+//!
+//! `Signature` is a `PrehashSignature` whose `Digest` is SHA-512, so generic
+//! code which takes the digest from the signature type, with a bound such as
+//! `DigestSigner<S::Digest, S>`, gets exactly Ed25519ph.
+
+use signature::DigestSigner;
+use signature::DigestVerifier;
+use signature::Error;
+use signature::PrehashSignature;
+use signature::digest::Digest;
+
+use generic_array::typenum::U64;
+
+use sha2::Sha512;
+
+use sha2_0_10;
+
+use ed25519::{Keypair, PublicKey, Signature};
+
+impl PrehashSignature for Signature {
+    type Digest = sha2_0_10::Sha512;
+}
+
+impl<D> DigestSigner<D, Signature> for Keypair where D: Digest<OutputSize = U64> {
+    fn try_sign_digest(&self, digest: D) -> Result<Signature, Error> {
+        Ok(self.secret.expand::<Sha512>()
+           .sign_with_dom2::<Sha512>(Some((1, &[])), &[&digest.finalize()], &self.public))
+    }
+}
+
+impl<D> DigestVerifier<D, Signature> for PublicKey where D: Digest<OutputSize = U64> {
+    fn verify_digest(&self, digest: D, signature: &Signature) -> Result<(), Error> {
+        self.verify_with_dom2::<Sha512>(Some((1, &[])), &[&digest.finalize()], signature)
+            .or(Err(Error::new()))
+    }
+}
+
+impl<D> DigestVerifier<D, Signature> for Keypair where D: Digest<OutputSize = U64> {
+    fn verify_digest(&self, digest: D, signature: &Signature) -> Result<(), Error> {
+        self.public.verify_digest(digest, signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use hex::FromHex;
+    use sha2_0_10::Sha512 as PrehashSha512;
+    use ed25519::SecretKey;
+    use super::*;
+
+    fn sign_with_signature_digest<T, S>(signer: &T, message: &[u8]) -> S
+            where T: DigestSigner<S::Digest, S>, S: PrehashSignature {
+        signer.sign_digest(S::Digest::new_with_prefix(message))
+    }
+
+    // TEST abc from RFC8032 §7.3.
+    #[test]
+    fn digest_signer_rfc8032_ed25519ph_vector() {
+        let secret_bytes: Vec<u8> = FromHex::from_hex(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42").unwrap();
+        let expected: Vec<u8> = FromHex::from_hex(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41\
+             31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406").unwrap();
+        let secret: SecretKey = SecretKey::from_bytes(&secret_bytes).unwrap();
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+        let keypair: Keypair = Keypair{ secret, public };
+
+        let signature: Signature = keypair.sign_digest(PrehashSha512::new_with_prefix(b"abc"));
+
+        assert_eq!(&signature.to_bytes()[..], &expected[..]);
+        assert_eq!(sign_with_signature_digest::<Keypair, Signature>(&keypair, b"abc"), signature);
+        assert!(public.verify_digest(PrehashSha512::new_with_prefix(b"abc"), &signature).is_ok());
+        assert!(public.verify_digest(PrehashSha512::new_with_prefix(b"abd"), &signature).is_err());
+    }
+}
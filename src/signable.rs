@@ -0,0 +1,88 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing of structured values, such as transactions, which know how to
+//! encode themselves for signing.
+
+use std::vec::Vec;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// A value which can be signed, by way of a canonical byte encoding.
+///
+/// The encoding returned by `signing_bytes()` is what is actually signed, so
+/// it must be deterministic, and must be unambiguous: two different values
+/// which should not share a signature must never have the same encoding.
+/// It is usually wise to begin it with a domain separation string naming the
+/// type, so that a signature on one type of value cannot be mistaken for a
+/// signature on another.
+pub trait Signable {
+    /// The bytes to be signed for this value.
+    fn signing_bytes(&self) -> Vec<u8>;
+}
+
+/// Sign a `Signable` value with a `keypair`.
+pub fn sign_signable<D, T>(keypair: &Keypair, value: &T) -> Signature
+        where D: Digest<OutputSize = U64> + Default, T: Signable + ?Sized {
+    keypair.sign::<D>(&value.signing_bytes())
+}
+
+/// Verify a `signature` on a `Signable` value with a `public_key`.
+///
+/// # Returns
+///
+/// `Ok(())` if the signature is valid, and otherwise an error as for
+/// `PublicKey::verify()`.
+pub fn verify_signable<D, T>(public_key: &PublicKey, value: &T, signature: &Signature)
+        -> Result<(), SignatureError>
+        where D: Digest<OutputSize = U64> + Default, T: Signable + ?Sized {
+
+    public_key.verify::<D>(&value.signing_bytes(), signature)
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    struct Transfer {
+        to: [u8; 32],
+        amount: u64,
+    }
+
+    impl Signable for Transfer {
+        fn signing_bytes(&self) -> Vec<u8> {
+            let mut bytes: Vec<u8> = b"Transfer".to_vec();
+
+            bytes.extend_from_slice(&self.to);
+            bytes.extend_from_slice(&self.amount.to_le_bytes());
+            bytes
+        }
+    }
+
+    #[test]
+    fn sign_and_verify_signable() {
+        let keypair: Keypair = keypair_for_testing("signable");
+        let transfer: Transfer = Transfer{ to: [7u8; 32], amount: 100 };
+        let signature: Signature = sign_signable::<Sha512, _>(&keypair, &transfer);
+
+        assert_eq!(verify_signable::<Sha512, _>(&keypair.public, &transfer, &signature), Ok(()));
+        assert!(keypair.verify::<Sha512>(&transfer.signing_bytes(), &signature).is_ok());
+
+        let forged: Transfer = Transfer{ to: [7u8; 32], amount: 1000 };
+        assert_eq!(verify_signable::<Sha512, _>(&keypair.public, &forged, &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+}
@@ -0,0 +1,149 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Keys which sign and verify with SHA-512, without a type parameter.
+//!
+//! `Keypair::sign()` and `PublicKey::verify()` are generic over the hash
+//! function, so the hash is fixed at each call site.  RFC8032 defines Ed25519
+//! only with SHA-512, so a `SigningKey` and a `VerifyingKey` fix it once, and
+//! their methods are plain functions of the message.  The `Sign` and `Verify`
+//! traits are object-safe, so that code which is handed a signer or verifier
+//! at runtime can hold a `Box<Sign>` or `&Verify`.
+
+use core::fmt;
+use core::fmt::Debug;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// Something which signs messages, such as a `SigningKey`.
+pub trait Sign {
+    /// Sign a `message`.
+    fn sign(&self, message: &[u8]) -> Signature;
+}
+
+/// Something which verifies signatures on messages, such as a `VerifyingKey`.
+pub trait Verify {
+    /// Verify a `signature` on a `message`.
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError>;
+}
+
+/// A `Keypair` which signs with SHA-512.
+pub struct SigningKey(Keypair);
+
+/// A `PublicKey` which verifies with SHA-512.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VerifyingKey(PublicKey);
+
+impl Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SigningKey({:?})", self.0.public)
+    }
+}
+
+impl From<Keypair> for SigningKey {
+    fn from(keypair: Keypair) -> SigningKey {
+        SigningKey(keypair)
+    }
+}
+
+impl From<PublicKey> for VerifyingKey {
+    fn from(public_key: PublicKey) -> VerifyingKey {
+        VerifyingKey(public_key)
+    }
+}
+
+impl SigningKey {
+    /// Sign a `message` with this key, as by `Keypair::sign::<Sha512>()`.
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.0.sign::<Sha512>(message)
+    }
+
+    /// Verify a `signature` on a `message` under this key's public half.
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        self.0.verify::<Sha512>(message, signature)
+    }
+
+    /// The `VerifyingKey` for signatures made with this key.
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey(self.0.public)
+    }
+
+    /// The `Keypair` which this key signs with.
+    pub fn keypair(&self) -> &Keypair {
+        &self.0
+    }
+}
+
+impl VerifyingKey {
+    /// Verify a `signature` on a `message`, as by `PublicKey::verify::<Sha512>()`.
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        self.0.verify::<Sha512>(message, signature)
+    }
+
+    /// The `PublicKey` which this key verifies signatures under.
+    pub fn public_key(&self) -> PublicKey {
+        self.0
+    }
+}
+
+impl Sign for SigningKey {
+    fn sign(&self, message: &[u8]) -> Signature {
+        SigningKey::sign(self, message)
+    }
+}
+
+impl Verify for SigningKey {
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        SigningKey::verify(self, message, signature)
+    }
+}
+
+impl Verify for VerifyingKey {
+    fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), SignatureError> {
+        VerifyingKey::verify(self, message, signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::boxed::Box;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn signing_key_agrees_with_keypair() {
+        let keypair: Keypair = keypair_for_testing("signing key");
+        let expected: Signature = keypair.sign::<Sha512>(b"test message");
+        let public: PublicKey = keypair.public;
+        let signing_key: SigningKey = SigningKey::from(keypair);
+        let verifying_key: VerifyingKey = signing_key.verifying_key();
+
+        assert_eq!(signing_key.sign(b"test message"), expected);
+        assert_eq!(verifying_key, VerifyingKey::from(public));
+        assert_eq!(verifying_key.public_key(), public);
+        assert_eq!(verifying_key.verify(b"test message", &expected), Ok(()));
+        assert_eq!(verifying_key.verify(b"test massage", &expected), Err(SignatureError::InvalidSignature));
+        assert!(!format!("{:?}", signing_key).contains("SecretKey"));
+    }
+
+    #[test]
+    fn sign_and_verify_as_trait_objects() {
+        let signer: Box<dyn Sign> = Box::new(SigningKey::from(keypair_for_testing("signing key")));
+        let verifiers: [Box<dyn Verify>; 2] = [Box::new(SigningKey::from(keypair_for_testing("signing key"))),
+                                           Box::new(VerifyingKey::from(keypair_for_testing("signing key").public))];
+        let signature: Signature = signer.sign(b"test message");
+
+        for verifier in verifiers.iter() {
+            assert_eq!(verifier.verify(b"test message", &signature), Ok(()));
+        }
+    }
+}
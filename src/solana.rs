@@ -0,0 +1,124 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Import and export of public keys and signatures in the plain base58
+//! encoding used by Solana.
+//!
+//! Unlike base58check, as used by Bitcoin and Tezos, there is neither a
+//! version prefix nor a checksum: the string is the base58 encoding of the
+//! raw bytes.
+
+use std::string::String;
+use std::vec::Vec;
+
+use bs58;
+
+use ed25519::{PublicKey, Signature};
+use ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use errors::SignatureError;
+
+/// Decode a base58 string, which must contain exactly `length` bytes.
+fn decode(length: usize, encoded: &str) -> Result<Vec<u8>, SignatureError> {
+    let bytes: Vec<u8> = bs58::decode(encoded).into_vec()
+        .or(Err(SignatureError::InvalidEncoding))?;
+
+    if bytes.len() != length {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    Ok(bytes)
+}
+
+impl PublicKey {
+    /// Construct a `PublicKey` from a Solana base58 encoded address.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `PublicKey`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the string is not valid
+    /// base58, or does not decode to exactly 32 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "solana")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let public = PublicKey::from_solana_base58(
+    ///     "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z").unwrap();
+    ///
+    /// assert_eq!(public.to_solana_base58(), "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z");
+    /// # }
+    /// # #[cfg(not(feature = "solana"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_solana_base58(encoded: &str) -> Result<PublicKey, SignatureError> {
+        let bytes: Vec<u8> = decode(PUBLIC_KEY_LENGTH, encoded)?;
+
+        PublicKey::from_bytes(&bytes).or(Err(SignatureError::InvalidEncoding))
+    }
+
+    /// Encode this `PublicKey` as a Solana base58 address.
+    pub fn to_solana_base58(&self) -> String {
+        bs58::encode(self.as_bytes()).into_string()
+    }
+}
+
+impl Signature {
+    /// Construct a `Signature` from its Solana base58 encoding.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Signature`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the string is not valid
+    /// base58, or does not decode to exactly 64 bytes.
+    pub fn from_solana_base58(encoded: &str) -> Result<Signature, SignatureError> {
+        let bytes: Vec<u8> = decode(SIGNATURE_LENGTH, encoded)?;
+
+        Signature::from_bytes(&bytes).or(Err(SignatureError::InvalidEncoding))
+    }
+
+    /// Encode this `Signature` in base58, as Solana does.
+    pub fn to_solana_base58(&self) -> String {
+        bs58::encode(&self.to_bytes()[..]).into_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha2::Sha512;
+
+    // The public key and signature of TEST 1 from RFC8032 §7.1.
+    static PUBLIC_KEY: &str = "FVen3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z";
+    static SIGNATURE: &str = "5awYiUvGiDFA33EJjj4TXJG44a5afJc8QjWRpGgQiu6b23jCr7yndW2fmp9\
+                             ujwqJVe32J456wV3VF78Asb1obnTc";
+
+    #[test]
+    fn solana_vectors_roundtrip() {
+        let public: PublicKey = PublicKey::from_solana_base58(PUBLIC_KEY).unwrap();
+        let signature: Signature = Signature::from_solana_base58(SIGNATURE).unwrap();
+
+        assert!(public.verify::<Sha512>(b"", &signature).is_ok());
+        assert_eq!(public.to_solana_base58(), PUBLIC_KEY);
+        assert_eq!(signature.to_solana_base58(), SIGNATURE);
+    }
+
+    #[test]
+    fn solana_wrong_length_is_rejected() {
+        assert_eq!(PublicKey::from_solana_base58(SIGNATURE).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+        assert_eq!(Signature::from_solana_base58(PUBLIC_KEY).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+        // '0' is not in the base58 alphabet.
+        assert_eq!(PublicKey::from_solana_base58("0Ven3X669xLzsi6N2V91DoiyzHzg1uAgqiT8jZ9nS96Z")
+                   .unwrap_err(), SignatureError::InvalidEncoding);
+    }
+}
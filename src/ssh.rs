@@ -0,0 +1,261 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! The SSH wire formats of public keys and signatures, from RFC8709.
+//!
+//! In SSH, a public key is the blob `string "ssh-ed25519" || string key`, and
+//! a signature is `string "ssh-ed25519" || string signature`, where a
+//! `string` is a big-endian `uint32` length followed by that many bytes.
+//! OpenSSH writes a public key on one line, as in `~/.ssh/id_ed25519.pub`:
+//!
+//! ```text
+//! ssh-ed25519 <base64 of the key blob> <comment>
+//! ```
+//!
+//! OpenSSH's private key file format is not supported.
+
+use std::string::String;
+use std::vec::Vec;
+
+use base64;
+
+use ed25519::{PublicKey, Signature};
+use ed25519::{PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+use errors::SignatureError;
+
+/// The SSH name of the ed25519 key and signature algorithm.
+pub(crate) const SSH_ED25519: &[u8] = b"ssh-ed25519";
+
+/// Append an SSH `string`, i.e. a big-endian `uint32` length and the bytes.
+pub(crate) fn put_string(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+/// Split a big-endian `uint32` from the front of `input`.
+pub(crate) fn get_u32(input: &mut &[u8]) -> Option<u32> {
+    if input.len() < 4 {
+        return None;
+    }
+    let value: u32 = u32::from_be_bytes(*array_ref!(input, 0, 4));
+
+    *input = &input[4..];
+    Some(value)
+}
+
+/// Split an SSH `string` from the front of `input`.
+pub(crate) fn get_string<'a>(input: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let length: usize = get_u32(input)? as usize;
+
+    if input.len() < length {
+        return None;
+    }
+    let (string, rest) = input.split_at(length);
+
+    *input = rest;
+    Some(string)
+}
+
+/// Split the `string "ssh-ed25519" || string value` of an ed25519 key or
+/// signature blob, which must have nothing after it.
+///
+/// # Returns
+///
+/// The `value`, or `SignatureError::InvalidKeyLength` if the blob is for
+/// another algorithm, or `SignatureError::InvalidEncoding` if it is
+/// malformed or the `value` is not `length` bytes long.
+fn get_ed25519_blob(mut blob: &[u8], length: usize) -> Result<&[u8], SignatureError> {
+    if get_string(&mut blob).ok_or(SignatureError::InvalidEncoding)? != SSH_ED25519 {
+        return Err(SignatureError::InvalidKeyLength);
+    }
+    let value: &[u8] = get_string(&mut blob).ok_or(SignatureError::InvalidEncoding)?;
+
+    if value.len() != length || !blob.is_empty() {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    Ok(value)
+}
+
+impl PublicKey {
+    /// Encode this `PublicKey` as an SSH public key blob.
+    pub fn to_ssh_wire(&self) -> Vec<u8> {
+        let mut blob: Vec<u8> = Vec::with_capacity(19 + PUBLIC_KEY_LENGTH);
+
+        put_string(&mut blob, SSH_ED25519);
+        put_string(&mut blob, self.as_bytes());
+        blob
+    }
+
+    /// Decode a `PublicKey` from an SSH public key blob.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `PublicKey`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the blob is not of an ed25519
+    ///   key, or
+    /// * `SignatureError::InvalidEncoding` if it is malformed, or does not
+    ///   hold a valid public key.
+    pub fn from_ssh_wire(blob: &[u8]) -> Result<PublicKey, SignatureError> {
+        PublicKey::from_bytes(get_ed25519_blob(blob, PUBLIC_KEY_LENGTH)?)
+            .or(Err(SignatureError::InvalidEncoding))
+    }
+
+    /// Encode this `PublicKey` as a line of an OpenSSH public key file, with
+    /// the given `comment`, which is left off if empty.
+    pub fn to_openssh_public_key(&self, comment: &str) -> String {
+        let mut line: String = String::from("ssh-ed25519 ");
+
+        line.push_str(&base64::encode(&self.to_ssh_wire()));
+        if !comment.is_empty() {
+            line.push(' ');
+            line.push_str(comment);
+        }
+        line
+    }
+
+    /// Decode a `PublicKey` from a line of an OpenSSH public key file, such
+    /// as `~/.ssh/id_ed25519.pub`.  Any comment is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `PublicKey`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the line is not of an ed25519
+    ///   key, or
+    /// * `SignatureError::InvalidEncoding` if it is malformed, or its key
+    ///   type does not match the key blob.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "ssh")]
+    /// # fn main() {
+    /// use ed25519_dalek::PublicKey;
+    ///
+    /// let line: &str = "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIF/n0g1OuRjJpR8LUgWd6631dB5auiiamRJkd/0l5unr alice@example.com";
+    /// let public: PublicKey = PublicKey::from_openssh_public_key(line).unwrap();
+    ///
+    /// assert_eq!(public.to_openssh_public_key("alice@example.com"), line);
+    /// # }
+    /// # #[cfg(not(feature = "ssh"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_openssh_public_key(line: &str) -> Result<PublicKey, SignatureError> {
+        let mut fields = line.split_whitespace();
+
+        match fields.next() {
+            Some("ssh-ed25519") => (),
+            Some(_)             => return Err(SignatureError::InvalidKeyLength),
+            None                => return Err(SignatureError::InvalidEncoding),
+        }
+        let blob: Vec<u8> = fields.next().ok_or(SignatureError::InvalidEncoding)
+            .and_then(|encoded| base64::decode(encoded).or(Err(SignatureError::InvalidEncoding)))?;
+
+        PublicKey::from_ssh_wire(&blob).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+impl Signature {
+    /// Encode this `Signature` in the SSH `ssh-ed25519` signature format.
+    pub fn to_ssh_wire(&self) -> Vec<u8> {
+        let mut blob: Vec<u8> = Vec::with_capacity(19 + SIGNATURE_LENGTH);
+
+        put_string(&mut blob, SSH_ED25519);
+        put_string(&mut blob, &self.to_bytes());
+        blob
+    }
+
+    /// Decode a `Signature` from the SSH `ssh-ed25519` signature format.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Signature`, or whose error value is
+    ///
+    /// * `SignatureError::InvalidKeyLength` if the blob is not an ed25519
+    ///   signature, or
+    /// * `SignatureError::InvalidEncoding` if it is malformed.
+    pub fn from_ssh_wire(blob: &[u8]) -> Result<Signature, SignatureError> {
+        Signature::from_bytes(get_ed25519_blob(blob, SIGNATURE_LENGTH)?)
+            .or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Read;
+    use hex::FromHex;
+    use sha2::{Digest, Sha512};
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn openssh_public_key() {
+        // Written by `ssh-keygen -t ed25519 -C alice@example.com`.
+        let mut line: String = String::new();
+        let key_bytes: Vec<u8> = FromHex::from_hex("5fe7d20d4eb918c9a51f0b52059debadf5741e5aba289a99126477fd25e6e9eb").unwrap();
+
+        File::open("res/ed25519-openssh.pub").unwrap().read_to_string(&mut line).unwrap();
+
+        let public: PublicKey = PublicKey::from_openssh_public_key(&line).unwrap();
+
+        assert_eq!(public.as_bytes(), &key_bytes[..]);
+        assert_eq!(public.to_openssh_public_key("alice@example.com"), line.trim_end());
+        assert_eq!(PublicKey::from_openssh_public_key(&public.to_openssh_public_key("")), Ok(public));
+        assert_eq!(PublicKey::from_ssh_wire(&public.to_ssh_wire()), Ok(public));
+    }
+
+    #[test]
+    fn openssh_signature() {
+        // The signature from `ssh-keygen -Y sign -n file` of "test message"
+        // with the key in res/ed25519-openssh.pub, which signs the SSHSIG
+        // preimage below.
+        let blob: Vec<u8> = FromHex::from_hex(
+            "0000000b7373682d65643235353139000000406035175e9a59835725d6331acd\
+             1679857e85fe0b1e81d37a65d503d50f0da69bed8c4dd8cd00848de7d8432f2e\
+             da15b99a244fe6e1945da4b4a6764502080b05").unwrap();
+        let mut line: String = String::new();
+        let mut preimage: Vec<u8> = b"SSHSIG".to_vec();
+
+        File::open("res/ed25519-openssh.pub").unwrap().read_to_string(&mut line).unwrap();
+        put_string(&mut preimage, b"file");
+        put_string(&mut preimage, b"");
+        put_string(&mut preimage, b"sha512");
+        put_string(&mut preimage, &Sha512::digest(b"test message"));
+
+        let public: PublicKey = PublicKey::from_openssh_public_key(&line).unwrap();
+        let signature: Signature = Signature::from_ssh_wire(&blob).unwrap();
+
+        assert!(public.verify::<Sha512>(&preimage, &signature).is_ok());
+        assert_eq!(signature.to_ssh_wire(), blob);
+    }
+
+    #[test]
+    fn ssh_malformed_is_rejected() {
+        let public: PublicKey = keypair_for_testing("ssh").public;
+        let signature: Signature = keypair_for_testing("ssh").sign::<Sha512>(b"");
+        let line: String = public.to_openssh_public_key("comment");
+        let mut rsa: Vec<u8> = Vec::new();
+
+        put_string(&mut rsa, b"ssh-rsa");
+        put_string(&mut rsa, public.as_bytes());
+
+        assert_eq!(PublicKey::from_openssh_public_key(&line.replacen("ssh-ed25519", "ssh-rsa", 1)),
+                   Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_openssh_public_key("ssh-ed25519"), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_openssh_public_key(""), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_ssh_wire(&rsa), Err(SignatureError::InvalidKeyLength));
+        assert_eq!(PublicKey::from_ssh_wire(&public.to_ssh_wire()[..50]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(PublicKey::from_ssh_wire(&signature.to_ssh_wire()), Err(SignatureError::InvalidEncoding));
+        assert_eq!(Signature::from_ssh_wire(&[&signature.to_ssh_wire()[..], &[0]].concat()),
+                   Err(SignatureError::InvalidEncoding));
+    }
+}
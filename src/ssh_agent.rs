@@ -0,0 +1,245 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing with an ed25519 key held by an SSH agent.
+
+use std::io;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::vec::Vec;
+
+use sha2::Sha512;
+
+use ed25519::{PublicKey, Signature};
+use ed25519::SIGNATURE_LENGTH;
+use ssh;
+use ssh::{put_string, SSH_ED25519};
+
+/// The SSH agent protocol message numbers which we use.
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+/// The largest agent response which we are prepared to read, in bytes.
+const MAX_MESSAGE_LENGTH: usize = 256 * 1024;
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Split a big-endian `uint32` from the front of `input`.
+fn get_u32(input: &mut &[u8]) -> io::Result<u32> {
+    ssh::get_u32(input).ok_or(invalid_data("truncated SSH agent message"))
+}
+
+/// Split an SSH `string` from the front of `input`.
+fn get_string<'a>(input: &mut &'a [u8]) -> io::Result<&'a [u8]> {
+    ssh::get_string(input).ok_or(invalid_data("truncated SSH agent message"))
+}
+
+/// Send one `request` to the agent at `socket_path`, and read its response.
+fn transact(socket_path: &Path, request: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stream: UnixStream = UnixStream::connect(socket_path)?;
+    let mut length: [u8; 4] = [0u8; 4];
+
+    stream.write_all(&(request.len() as u32).to_be_bytes())?;
+    stream.write_all(request)?;
+    stream.read_exact(&mut length)?;
+
+    let length: usize = u32::from_be_bytes(length) as usize;
+
+    if length == 0 || length > MAX_MESSAGE_LENGTH {
+        return Err(invalid_data("bad SSH agent message length"));
+    }
+    let mut response: Vec<u8> = vec![0u8; length];
+
+    stream.read_exact(&mut response)?;
+    Ok(response)
+}
+
+/// An ed25519 key held by an SSH agent, which signs on our behalf.
+///
+/// Only the public key is known locally; the secret key never leaves the
+/// agent.  Each signature requires a round trip to the agent, which may ask
+/// its user for confirmation.
+#[derive(Debug)]
+pub struct SshAgentKeypair {
+    socket_path: PathBuf,
+    key_blob: Vec<u8>,
+    /// The public half of the key held by the agent.
+    pub public: PublicKey,
+}
+
+impl SshAgentKeypair {
+    /// Connect to the SSH agent listening on `socket_path` (usually the value
+    /// of the `SSH_AUTH_SOCK` environment variable), and find the ed25519 key
+    /// whose comment is `key_comment`.
+    ///
+    /// # Returns
+    ///
+    /// The `SshAgentKeypair`, or an `io::Error` whose kind is
+    ///
+    /// * `io::ErrorKind::NotFound` if the agent holds no such ed25519 key,
+    /// * `io::ErrorKind::InvalidData` if the agent's response is malformed, or
+    /// * any error from communicating with the agent.
+    pub fn connect(socket_path: &Path, key_comment: &str) -> io::Result<SshAgentKeypair> {
+        let response: Vec<u8> = transact(socket_path, &[SSH_AGENTC_REQUEST_IDENTITIES])?;
+        let mut body: &[u8] = &response[1..];
+
+        if response[0] != SSH_AGENT_IDENTITIES_ANSWER {
+            return Err(invalid_data("unexpected response to SSH agent identities request"));
+        }
+        for _ in 0..get_u32(&mut body)? {
+            let key_blob: &[u8] = get_string(&mut body)?;
+            let comment: &[u8] = get_string(&mut body)?;
+
+            if comment != key_comment.as_bytes() {
+                continue;
+            }
+            if let Ok(public) = PublicKey::from_ssh_wire(key_blob) {
+                return Ok(SshAgentKeypair{ socket_path: socket_path.to_path_buf(),
+                                           key_blob: key_blob.to_vec(),
+                                           public });
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::NotFound, "no such ed25519 key in SSH agent"))
+    }
+
+    /// Ask the agent to sign a `message` with this key.
+    ///
+    /// The signature returned by the agent is verified before it is returned,
+    /// so a misbehaving agent cannot return a bad signature.
+    ///
+    /// # Returns
+    ///
+    /// The `Signature`, or an `io::Error` whose kind is
+    ///
+    /// * `io::ErrorKind::PermissionDenied` if the agent refused to sign,
+    /// * `io::ErrorKind::InvalidData` if the agent's response is malformed or
+    ///   its signature does not verify, or
+    /// * any error from communicating with the agent.
+    pub fn sign(&self, message: &[u8]) -> io::Result<Signature> {
+        let mut request: Vec<u8> = Vec::with_capacity(13 + self.key_blob.len() + message.len());
+
+        request.push(SSH_AGENTC_SIGN_REQUEST);
+        put_string(&mut request, &self.key_blob);
+        put_string(&mut request, message);
+        request.extend_from_slice(&0u32.to_be_bytes());
+
+        let response: Vec<u8> = transact(&self.socket_path, &request)?;
+        let mut body: &[u8] = &response[1..];
+
+        match response[0] {
+            SSH_AGENT_SIGN_RESPONSE => (),
+            SSH_AGENT_FAILURE => return Err(io::Error::new(io::ErrorKind::PermissionDenied,
+                                                           "SSH agent refused to sign")),
+            _ => return Err(invalid_data("unexpected response to SSH agent sign request")),
+        }
+        let mut blob: &[u8] = get_string(&mut body)?;
+
+        if get_string(&mut blob)? != SSH_ED25519 {
+            return Err(invalid_data("SSH agent returned a signature of the wrong type"));
+        }
+        let signature_bytes: &[u8] = get_string(&mut blob)?;
+
+        if signature_bytes.len() != SIGNATURE_LENGTH {
+            return Err(invalid_data("SSH agent returned a signature of the wrong length"));
+        }
+        let signature: Signature = Signature::from_bytes(signature_bytes)
+            .or(Err(invalid_data("SSH agent returned a malformed signature")))?;
+
+        if self.public.verify::<Sha512>(message, &signature).is_err() {
+            return Err(invalid_data("SSH agent returned an invalid signature"));
+        }
+        Ok(signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+    use std::os::unix::net::UnixListener;
+    use std::thread;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    /// Run a minimal SSH agent holding `keypair`, which answers `requests`
+    /// requests and then exits.
+    fn fake_agent(name: &str, keypair: Keypair, requests: usize) -> PathBuf {
+        let socket_path: PathBuf = env::temp_dir().join(format!("ed25519-dalek-{}-{}",
+                                                                name, ::std::process::id()));
+        let _ = fs::remove_file(&socket_path);
+        let listener: UnixListener = UnixListener::bind(&socket_path).unwrap();
+        let mut key_blob: Vec<u8> = Vec::new();
+
+        put_string(&mut key_blob, SSH_ED25519);
+        put_string(&mut key_blob, keypair.public.as_bytes());
+
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream: UnixStream = stream.unwrap();
+                let mut length: [u8; 4] = [0u8; 4];
+
+                stream.read_exact(&mut length).unwrap();
+                let mut request: Vec<u8> = vec![0u8; u32::from_be_bytes(length) as usize];
+                stream.read_exact(&mut request).unwrap();
+
+                let mut response: Vec<u8> = Vec::new();
+                let mut body: &[u8] = &request[1..];
+
+                if request[0] == SSH_AGENTC_REQUEST_IDENTITIES {
+                    response.push(SSH_AGENT_IDENTITIES_ANSWER);
+                    response.extend_from_slice(&1u32.to_be_bytes());
+                    put_string(&mut response, &key_blob);
+                    put_string(&mut response, b"test@example.com");
+                } else {
+                    let _ = get_string(&mut body).unwrap();
+                    let data: &[u8] = get_string(&mut body).unwrap();
+                    let mut blob: Vec<u8> = Vec::new();
+
+                    put_string(&mut blob, SSH_ED25519);
+                    put_string(&mut blob, &keypair.sign::<Sha512>(data).to_bytes());
+                    response.push(SSH_AGENT_SIGN_RESPONSE);
+                    put_string(&mut response, &blob);
+                }
+                stream.write_all(&(response.len() as u32).to_be_bytes()).unwrap();
+                stream.write_all(&response).unwrap();
+            }
+        });
+        socket_path
+    }
+
+    #[test]
+    fn ssh_agent_signs() {
+        let keypair: Keypair = keypair_for_testing("ssh-agent");
+        let public: PublicKey = keypair.public;
+        let socket_path: PathBuf = fake_agent("signs", keypair, 2);
+
+        let agent: SshAgentKeypair = SshAgentKeypair::connect(&socket_path,
+                                                              "test@example.com").unwrap();
+        let signature: Signature = agent.sign(b"test message").unwrap();
+
+        assert_eq!(agent.public, public);
+        assert!(public.verify::<Sha512>(b"test message", &signature).is_ok());
+        let _ = fs::remove_file(&socket_path);
+    }
+
+    #[test]
+    fn ssh_agent_unknown_comment() {
+        let socket_path: PathBuf = fake_agent("unknown", keypair_for_testing("ssh-agent"), 1);
+
+        assert_eq!(SshAgentKeypair::connect(&socket_path, "nobody@example.com").unwrap_err().kind(),
+                   io::ErrorKind::NotFound);
+        let _ = fs::remove_file(&socket_path);
+    }
+}
@@ -0,0 +1,149 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing with an embedded, monotonically increasing counter.
+//!
+//! This needs 64-bit atomics, and so is only available on targets which have
+//! them, which excludes e.g. thumbv7m-none-eabi.
+
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// A `Keypair` which signs each message together with a counter, which is
+/// incremented atomically on every signature.
+///
+/// The message actually signed is the counter, as eight little-endian bytes,
+/// followed by the message.  A verifier which remembers the highest counter it
+/// has accepted from a signer, and rejects any signature whose counter is not
+/// greater, will never accept a replayed signature.
+///
+/// # Warning
+///
+/// The counter lives in memory.  If the signer restarts, it must be restored
+/// with `StatefulSigner::new()` to a value higher than any it has already
+/// used, or else its signatures will be rejected (or, worse, replayable).
+#[derive(Debug)]
+pub struct StatefulSigner {
+    keypair: Keypair,
+    counter: AtomicU64,
+}
+
+impl StatefulSigner {
+    /// Create a `StatefulSigner` whose first signature will use `counter`.
+    pub fn new(keypair: Keypair, counter: u64) -> StatefulSigner {
+        StatefulSigner{ keypair, counter: AtomicU64::new(counter) }
+    }
+
+    /// Get the counter which will be used for the next signature.
+    pub fn counter(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
+
+    /// Get the public key of this signer.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.keypair.public
+    }
+
+    /// Sign `counter || message`, and increment the counter.
+    ///
+    /// The counter is never incremented past `u64::MAX`, which is therefore
+    /// never used, so that it cannot wrap around to a counter already used.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the counter used, and the signature, or
+    /// whose error value is `SignatureError::CounterExhausted` if the counter
+    /// has reached `u64::MAX`.
+    pub fn sign<D>(&self, message: &[u8]) -> Result<(u64, Signature), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let counter: u64 = self.counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |counter| counter.checked_add(1))
+            .or(Err(SignatureError::CounterExhausted))?;
+        let counter_bytes: [u8; 8] = counter.to_le_bytes();
+        let signature: Signature = self.keypair.secret.expand::<D>()
+            .sign_with_dom2::<D>(None, &[&counter_bytes, message], &self.keypair.public);
+
+        Ok((counter, signature))
+    }
+
+    /// Verify a `signature` made by `StatefulSigner::sign()` with the given
+    /// `counter` on a `message`.
+    ///
+    /// This only checks the signature.  Rejecting counters which have already
+    /// been seen is up to the caller.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the signature is valid, and otherwise an error as for
+    /// `PublicKey::verify()`.
+    pub fn verify<D>(public_key: &PublicKey, counter: u64, message: &[u8], signature: &Signature)
+            -> Result<(), SignatureError>
+            where D: Digest<OutputSize = U64> + Default {
+
+        let counter_bytes: [u8; 8] = counter.to_le_bytes();
+
+        public_key.verify_with_dom2::<D>(None, &[&counter_bytes, message], signature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn stateful_signer_counts() {
+        let signer: StatefulSigner = StatefulSigner::new(keypair_for_testing("stateful"), 7);
+        let public_key: PublicKey = *signer.public_key();
+
+        let (first, first_sig) = signer.sign::<Sha512>(b"test message").unwrap();
+        let (second, second_sig) = signer.sign::<Sha512>(b"test message").unwrap();
+
+        assert_eq!((first, second, signer.counter()), (7, 8, 9));
+        assert!(first_sig != second_sig);
+        assert_eq!(StatefulSigner::verify::<Sha512>(&public_key, 7, b"test message", &first_sig),
+                   Ok(()));
+        assert_eq!(StatefulSigner::verify::<Sha512>(&public_key, 8, b"test message", &first_sig),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn stateful_signature_covers_counter_and_message() {
+        let keypair: Keypair = keypair_for_testing("stateful");
+        let public_key: PublicKey = keypair.public;
+        let signer: StatefulSigner = StatefulSigner::new(keypair, 1);
+        let (counter, signature) = signer.sign::<Sha512>(b"test message").unwrap();
+        let mut signed: [u8; 20] = [0u8; 20];
+
+        signed[0] = 1;
+        signed[8..].copy_from_slice(b"test message");
+
+        assert_eq!(counter, 1);
+        assert!(public_key.verify::<Sha512>(&signed, &signature).is_ok());
+    }
+
+    #[test]
+    fn stateful_signer_refuses_exhausted_counter() {
+        let signer: StatefulSigner = StatefulSigner::new(keypair_for_testing("stateful"), u64::MAX - 1);
+
+        assert_eq!(signer.sign::<Sha512>(b"test message").unwrap().0, u64::MAX - 1);
+        assert_eq!(signer.sign::<Sha512>(b"test message").unwrap_err(), SignatureError::CounterExhausted);
+        assert_eq!(signer.sign::<Sha512>(b"test message").unwrap_err(), SignatureError::CounterExhausted);
+        assert_eq!(signer.counter(), u64::MAX);
+    }
+}
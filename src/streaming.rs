@@ -0,0 +1,200 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Signing and verifying messages incrementally, without holding the whole
+//! message in memory.
+//!
+//! An ed25519 signature is `(R, s)`, where `R = r·B` for a nonce `r`, and
+//! `s = r + H(R || A || M)·a`.  `Keypair::sign()` derives the nonce
+//! deterministically, as `r = H(prefix || M)`, and so must read the message
+//! `M` twice: once for the nonce, and again for the challenge, which cannot
+//! be started until `R` is known.
+//!
+//! `SigningState` instead derives the nonce before seeing the message, as
+//! `r = H(prefix || z)`, for 64 bytes `z` fresh from a CSPRNG, so that the
+//! challenge can be hashed as the message arrives.  The `prefix` is the
+//! secret nonce half of the expanded secret key, so `r` stays unpredictable
+//! even if the CSPRNG is weak, provided it never repeats `z` for different
+//! messages.  The result is an ordinary ed25519 signature, which verifies
+//! with `PublicKey::verify()`, but, unlike one from `Keypair::sign()`, signing
+//! the same message twice gives different signatures.
+//!
+//! `VerifyingState` needs no such change: `R` is part of the signature, so
+//! the challenge can be hashed in a single pass.
+
+use std::io;
+use std::io::Write;
+
+use rand::Rng;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::constants;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{ExpandedSecretKey, Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// A signature being made on a message which is supplied in pieces.
+pub struct SigningState<D> {
+    key: Scalar,
+    nonce: Scalar,
+    r: CompressedEdwardsY,
+    h: D,
+}
+
+impl<D> SigningState<D> where D: Digest<OutputSize = U64> + Default {
+    /// Begin signing a message with the `keypair`, using the hash function
+    /// `D`, and drawing the nonce from the `csprng`.
+    pub fn new(keypair: &Keypair, csprng: &mut dyn Rng) -> SigningState<D> {
+        let expanded: ExpandedSecretKey = keypair.secret.expand::<D>();
+        let mut randomness: [u8; 64] = [0u8; 64];
+        let mut h: D = D::default();
+
+        csprng.fill_bytes(&mut randomness);
+        h.input(&expanded.nonce);
+        h.input(&randomness);
+
+        let digest = h.fixed_result();
+        let nonce: Scalar = Scalar::reduce(array_ref!(digest, 0, 64));
+        let r: CompressedEdwardsY = (&nonce * &constants::ED25519_BASEPOINT_TABLE).compress();
+        let mut h: D = D::default();
+
+        h.input(r.as_bytes());
+        h.input(keypair.public.as_bytes());
+
+        SigningState{ key: expanded.key, nonce, r, h }
+    }
+
+    /// Append `data` to the message being signed.
+    pub fn update(&mut self, data: &[u8]) {
+        self.h.input(data);
+    }
+
+    /// Finish signing, returning the signature on everything passed to
+    /// `update()`.
+    pub fn finalize(self) -> Signature {
+        let digest = self.h.fixed_result();
+        let k: Scalar = Scalar::reduce(array_ref!(digest, 0, 64));
+
+        Signature{ r: self.r, s: Scalar::multiply_add(&k, &self.key, &self.nonce) }
+    }
+}
+
+impl<D> Write for SigningState<D> where D: Digest<OutputSize = U64> + Default {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A signature being verified on a message which is supplied in pieces.
+pub struct VerifyingState<D> {
+    public_key: PublicKey,
+    signature: Signature,
+    h: D,
+}
+
+impl<D> VerifyingState<D> where D: Digest<OutputSize = U64> + Default {
+    /// Begin verifying the `signature` under the `public_key`, using the hash
+    /// function `D`.
+    pub fn new(public_key: &PublicKey, signature: &Signature) -> VerifyingState<D> {
+        let mut h: D = D::default();
+
+        h.input(signature.r.as_bytes());
+        h.input(public_key.as_bytes());
+
+        VerifyingState{ public_key: *public_key, signature: *signature, h }
+    }
+
+    /// Append `data` to the message being verified.
+    pub fn update(&mut self, data: &[u8]) {
+        self.h.input(data);
+    }
+
+    /// Finish verifying the signature on everything passed to `update()`.
+    ///
+    /// # Return
+    ///
+    /// As for `PublicKey::verify()`.
+    pub fn finalize(self) -> Result<(), SignatureError> {
+        self.public_key.verify_with_challenge_hash(self.h, &self.signature)
+    }
+}
+
+impl<D> Write for VerifyingState<D> where D: Digest<OutputSize = U64> + Default {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use sha2::Sha512;
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn streamed_signature_verifies() {
+        let keypair: Keypair = keypair_for_testing("streaming");
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let mut signer: SigningState<Sha512> = SigningState::new(&keypair, &mut csprng);
+
+        signer.update(b"test ");
+        signer.update(b"message");
+
+        let signature: Signature = signer.finalize();
+        let mut verifier: VerifyingState<Sha512> = VerifyingState::new(&keypair.public, &signature);
+
+        verifier.update(b"test mes");
+        verifier.update(b"sage");
+        assert_eq!(verifier.finalize(), Ok(()));
+        assert_eq!(keypair.public.verify::<Sha512>(b"test message", &signature), Ok(()));
+
+        let mut verifier: VerifyingState<Sha512> = VerifyingState::new(&keypair.public, &signature);
+
+        verifier.update(b"test massage");
+        assert_eq!(verifier.finalize(), Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn streamed_signature_nonces_differ() {
+        let keypair: Keypair = keypair_for_testing("streaming");
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let mut first: SigningState<Sha512> = SigningState::new(&keypair, &mut csprng);
+        let mut second: SigningState<Sha512> = SigningState::new(&keypair, &mut csprng);
+
+        first.update(b"test message");
+        second.update(b"test message");
+        assert!(first.finalize() != second.finalize());
+    }
+
+    #[test]
+    fn verifying_state_matches_sign() {
+        let keypair: Keypair = keypair_for_testing("streaming");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let mut verifier: VerifyingState<Sha512> = VerifyingState::new(&keypair.public, &signature);
+
+        io::copy(&mut &b"test message"[..], &mut verifier).unwrap();
+        assert_eq!(verifier.finalize(), Ok(()));
+    }
+}
@@ -0,0 +1,73 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! A quick, self-contained measurement of signing and verification throughput.
+
+use std::time::{Duration, Instant};
+
+use rand::OsRng;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, Signature};
+
+/// Sign messages for `duration_secs` seconds, then verify them for another
+/// `duration_secs` seconds, using a freshly generated keypair and SHA-512.
+///
+/// The results are only as reliable as the machine is quiet, and are
+/// meaningless unless the crate was compiled with optimisations.
+///
+/// # Returns
+///
+/// A tuple of the number of signatures made per second, and the number of
+/// signatures verified per second.
+///
+/// # Panics
+///
+/// If `duration_secs` is zero, or the operating system's random number
+/// generator is unavailable.
+pub fn stress_test_throughput(duration_secs: u64) -> (u64, u64) {
+    assert!(duration_secs > 0, "duration must be at least one second");
+
+    let mut csprng: OsRng = OsRng::new().unwrap();
+    let keypair: Keypair = Keypair::generate::<Sha512>(&mut csprng);
+    let duration: Duration = Duration::from_secs(duration_secs);
+    let mut message: [u8; 8] = [0u8; 8];
+    let mut signature: Signature = keypair.sign::<Sha512>(&message);
+    let mut signs: u64 = 0;
+    let mut verifies: u64 = 0;
+
+    let start: Instant = Instant::now();
+    while start.elapsed() < duration {
+        message = signs.to_le_bytes();
+        signature = keypair.sign::<Sha512>(&message);
+        signs += 1;
+    }
+
+    let start: Instant = Instant::now();
+    while start.elapsed() < duration {
+        assert!(keypair.verify::<Sha512>(&message, &signature).is_ok());
+        verifies += 1;
+    }
+
+    (signs / duration_secs, verifies / duration_secs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stress_test_throughput_reports_progress() {
+        let (signs, verifies) = stress_test_throughput(1);
+
+        assert!(signs > 0);
+        assert!(verifies > 0);
+    }
+}
@@ -0,0 +1,296 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Identifiers for the signature schemes of RFC8032, for use in protocol
+//! negotiation.
+
+#[cfg(feature = "serde")]
+use core::fmt;
+
+use std::vec::Vec;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+#[cfg(feature = "serde")]
+use serde::{Serializer, Deserializer};
+#[cfg(feature = "serde")]
+use serde::de::{EnumAccess, VariantAccess, Visitor};
+#[cfg(feature = "serde")]
+use serde::de::Error as SerdeError;
+
+use digest::Digest;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, Signature};
+use ed25519::MAX_CONTEXT_LENGTH;
+use errors::SignatureError;
+
+/// The object identifier of Ed25519, from RFC8410.
+const ED25519_OID: &str = "1.3.101.112";
+
+/// The object identifier of Ed25519ph, from draft-ietf-curdle-pkix-04.
+const ED25519PH_OID: &str = "1.3.101.114";
+
+/// The names of the variants of `SignatureSuite`, in order.
+#[cfg(feature = "serde")]
+const VARIANTS: &[&str] = &["Ed25519Sha512", "Ed25519phSha512", "Ed25519ctxSha512"];
+
+/// A signature scheme from RFC8032, together with its parameters.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SignatureSuite {
+    /// Pure Ed25519, with SHA-512.
+    Ed25519Sha512,
+    /// Ed25519ph, in which the message is first hashed with SHA-512, with an
+    /// empty context.
+    Ed25519phSha512,
+    /// Ed25519ctx, with SHA-512 and the given context, which must be at most
+    /// `MAX_CONTEXT_LENGTH` bytes long.
+    Ed25519ctxSha512(Vec<u8>),
+}
+
+impl SignatureSuite {
+    /// Get the object identifier for this `SignatureSuite`, as a dotted
+    /// decimal string.
+    ///
+    /// # Returns
+    ///
+    /// `Some` OID for `Ed25519Sha512` (from RFC8410) and `Ed25519phSha512`
+    /// (from the drafts which preceded it).  No OID has ever been assigned to
+    /// Ed25519ctx, and in any case an OID could not carry the context, so for
+    /// `Ed25519ctxSha512` this returns `None`.
+    pub fn oid(&self) -> Option<&'static str> {
+        match *self {
+            SignatureSuite::Ed25519Sha512       => Some(ED25519_OID),
+            SignatureSuite::Ed25519phSha512     => Some(ED25519PH_OID),
+            SignatureSuite::Ed25519ctxSha512(_) => None,
+        }
+    }
+
+    /// Find the `SignatureSuite` with the given dotted decimal object
+    /// identifier, if there is one.
+    pub fn from_oid(oid: &str) -> Option<SignatureSuite> {
+        match oid {
+            ED25519_OID   => Some(SignatureSuite::Ed25519Sha512),
+            ED25519PH_OID => Some(SignatureSuite::Ed25519phSha512),
+            _             => None,
+        }
+    }
+
+    /// Sign a `message` with the `keypair` under this `SignatureSuite`.
+    ///
+    /// # Return
+    ///
+    /// Returns the `Signature`, or `Err(SignatureError::InvalidInput)` if this
+    /// is `Ed25519ctxSha512` with a context longer than `MAX_CONTEXT_LENGTH`
+    /// bytes.
+    pub fn sign(&self, keypair: &Keypair, message: &[u8]) -> Result<Signature, SignatureError> {
+        let expanded = keypair.secret.expand::<Sha512>();
+
+        match *self {
+            SignatureSuite::Ed25519Sha512 =>
+                Ok(expanded.sign::<Sha512>(message, &keypair.public)),
+            SignatureSuite::Ed25519phSha512 =>
+                Ok(expanded.sign_with_dom2::<Sha512>(Some((1, &[])), &[&Sha512::digest(message)],
+                                                     &keypair.public)),
+            SignatureSuite::Ed25519ctxSha512(ref context) => {
+                if context.len() > MAX_CONTEXT_LENGTH {
+                    return Err(SignatureError::InvalidInput);
+                }
+                Ok(expanded.sign_with_dom2::<Sha512>(Some((0, context)), &[message],
+                                                     &keypair.public))
+            },
+        }
+    }
+
+    /// Verify a `signature` on a `message` with the `public_key` under this
+    /// `SignatureSuite`.
+    ///
+    /// # Return
+    ///
+    /// Returns `Ok(())` if the signature was successfully verified,
+    /// `Err(SignatureError::InvalidInput)` if this is `Ed25519ctxSha512` with
+    /// a context longer than `MAX_CONTEXT_LENGTH` bytes, and otherwise an
+    /// error as for `PublicKey::verify()`.
+    pub fn verify(&self, public_key: &PublicKey, message: &[u8], signature: &Signature)
+            -> Result<(), SignatureError> {
+
+        match *self {
+            SignatureSuite::Ed25519Sha512 =>
+                public_key.verify::<Sha512>(message, signature),
+            SignatureSuite::Ed25519phSha512 =>
+                public_key.verify_with_dom2::<Sha512>(Some((1, &[])), &[&Sha512::digest(message)],
+                                                      signature),
+            SignatureSuite::Ed25519ctxSha512(ref context) => {
+                if context.len() > MAX_CONTEXT_LENGTH {
+                    return Err(SignatureError::InvalidInput);
+                }
+                public_key.verify_with_dom2::<Sha512>(Some((0, context)), &[message], signature)
+            },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for SignatureSuite {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        match *self {
+            SignatureSuite::Ed25519Sha512 =>
+                serializer.serialize_unit_variant("SignatureSuite", 0, VARIANTS[0]),
+            SignatureSuite::Ed25519phSha512 =>
+                serializer.serialize_unit_variant("SignatureSuite", 1, VARIANTS[1]),
+            SignatureSuite::Ed25519ctxSha512(ref context) =>
+                serializer.serialize_newtype_variant("SignatureSuite", 2, VARIANTS[2], context),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'d> Deserialize<'d> for SignatureSuite {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'d> {
+        struct Variant(u8);
+        struct VariantVisitor;
+        struct SignatureSuiteVisitor;
+
+        impl<'d> Visitor<'d> for VariantVisitor {
+            type Value = Variant;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("An ed25519 signature suite name or index.")
+            }
+
+            fn visit_u64<E>(self, index: u64) -> Result<Variant, E> where E: SerdeError {
+                if index < VARIANTS.len() as u64 {
+                    Ok(Variant(index as u8))
+                } else {
+                    Err(SerdeError::invalid_value(::serde::de::Unexpected::Unsigned(index), &self))
+                }
+            }
+
+            fn visit_str<E>(self, name: &str) -> Result<Variant, E> where E: SerdeError {
+                match VARIANTS.iter().position(|&variant| variant == name) {
+                    Some(index) => Ok(Variant(index as u8)),
+                    None        => Err(SerdeError::unknown_variant(name, VARIANTS)),
+                }
+            }
+        }
+
+        impl<'d> Deserialize<'d> for Variant {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'d> {
+                deserializer.deserialize_identifier(VariantVisitor)
+            }
+        }
+
+        impl<'d> Visitor<'d> for SignatureSuiteVisitor {
+            type Value = SignatureSuite;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("An ed25519 signature suite, as specified in RFC8032.")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<SignatureSuite, A::Error> where A: EnumAccess<'d> {
+                let (variant, access) = data.variant::<Variant>()?;
+
+                match variant.0 {
+                    0 => access.unit_variant().map(|_| SignatureSuite::Ed25519Sha512),
+                    1 => access.unit_variant().map(|_| SignatureSuite::Ed25519phSha512),
+                    _ => access.newtype_variant::<Vec<u8>>().map(SignatureSuite::Ed25519ctxSha512),
+                }
+            }
+        }
+        deserializer.deserialize_enum("SignatureSuite", VARIANTS, SignatureSuiteVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hex::FromHex;
+    use ed25519::SecretKey;
+    use super::*;
+
+    fn keypair_from_hex(secret: &str) -> Keypair {
+        let secret_bytes: Vec<u8> = FromHex::from_hex(secret).unwrap();
+        let secret: SecretKey = SecretKey::from_bytes(&secret_bytes).unwrap();
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Keypair{ secret, public }
+    }
+
+    // TEST abc from RFC8032 §7.3.
+    #[test]
+    fn ed25519ph_rfc8032_vector() {
+        let keypair: Keypair = keypair_from_hex(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42");
+        let expected: Vec<u8> = FromHex::from_hex(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41\
+             31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406").unwrap();
+        let suite: SignatureSuite = SignatureSuite::Ed25519phSha512;
+        let signature: Signature = suite.sign(&keypair, b"abc").unwrap();
+
+        assert_eq!(&signature.to_bytes()[..], &expected[..]);
+        assert_eq!(suite.verify(&keypair.public, b"abc", &signature), Ok(()));
+        assert_eq!(SignatureSuite::Ed25519Sha512.verify(&keypair.public, b"abc", &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    // The first "foo" test from RFC8032 §7.2.
+    #[test]
+    fn ed25519ctx_rfc8032_vector() {
+        let keypair: Keypair = keypair_from_hex(
+            "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6");
+        let message: Vec<u8> = FromHex::from_hex("f726936d19c800494e3fdaff20b276a8").unwrap();
+        let expected: Vec<u8> = FromHex::from_hex(
+            "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a\
+             8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d").unwrap();
+        let suite: SignatureSuite = SignatureSuite::Ed25519ctxSha512(b"foo".to_vec());
+        let signature: Signature = suite.sign(&keypair, &message).unwrap();
+
+        assert_eq!(&signature.to_bytes()[..], &expected[..]);
+        assert_eq!(suite.verify(&keypair.public, &message, &signature), Ok(()));
+        assert_eq!(SignatureSuite::Ed25519ctxSha512(b"bar".to_vec())
+                       .verify(&keypair.public, &message, &signature),
+                   Err(SignatureError::InvalidSignature));
+    }
+
+    #[test]
+    fn ed25519ctx_long_context_is_rejected() {
+        let keypair: Keypair = keypair_from_hex(
+            "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6");
+        let suite: SignatureSuite = SignatureSuite::Ed25519ctxSha512(vec![0u8; MAX_CONTEXT_LENGTH + 1]);
+        let signature: Signature = SignatureSuite::Ed25519Sha512.sign(&keypair, b"foo").unwrap();
+
+        assert_eq!(suite.sign(&keypair, b"foo"), Err(SignatureError::InvalidInput));
+        assert_eq!(suite.verify(&keypair.public, b"foo", &signature),
+                   Err(SignatureError::InvalidInput));
+    }
+
+    #[test]
+    fn oid_roundtrip() {
+        for suite in [SignatureSuite::Ed25519Sha512, SignatureSuite::Ed25519phSha512].iter() {
+            assert_eq!(SignatureSuite::from_oid(suite.oid().unwrap()).as_ref(), Some(suite));
+        }
+        assert_eq!(SignatureSuite::Ed25519ctxSha512(b"foo".to_vec()).oid(), None);
+        assert_eq!(SignatureSuite::from_oid("1.3.101.113"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_deserialize_suite() {
+        use bincode::{serialize, deserialize, Infinite};
+
+        for suite in [SignatureSuite::Ed25519Sha512,
+                      SignatureSuite::Ed25519phSha512,
+                      SignatureSuite::Ed25519ctxSha512(b"foo".to_vec())].iter() {
+            let encoded: Vec<u8> = serialize(suite, Infinite).unwrap();
+            let decoded: SignatureSuite = deserialize(&encoded).unwrap();
+
+            assert_eq!(&decoded, suite);
+        }
+    }
+}
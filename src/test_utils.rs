@@ -0,0 +1,136 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Generation of test vectors, for testing other implementations against
+//! this one.
+//!
+//! This is a developer tool, not a cryptographic primitive.
+
+use std::string::String;
+use std::vec::Vec;
+
+use rand::Rng;
+
+use hex::ToHex;
+
+use serde::{Serialize, Serializer};
+use serde::ser::SerializeStruct;
+
+use serde_json;
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use ed25519::{Keypair, Signature};
+
+/// The longest message which `Keypair::generate_test_vectors()` generates,
+/// in bytes.
+const MAX_MESSAGE_LENGTH: u32 = 256;
+
+/// A test vector with each of its fields encoded as lowercase hexadecimal.
+///
+/// It serialises as a struct with the four fields below, so that, as JSON,
+/// it is an object such as
+///
+/// ```text
+/// {"secret_key_hex":"…","public_key_hex":"…","message_hex":"…","signature_hex":"…"}
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestVector {
+    /// The 32-byte secret key.
+    pub secret_key_hex: String,
+    /// The 32-byte public key.
+    pub public_key_hex: String,
+    /// The message, which may be empty.
+    pub message_hex: String,
+    /// The 64-byte signature on the message.
+    pub signature_hex: String,
+}
+
+impl Serialize for TestVector {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        let mut state = serializer.serialize_struct("TestVector", 4)?;
+
+        state.serialize_field("secret_key_hex", &self.secret_key_hex)?;
+        state.serialize_field("public_key_hex", &self.public_key_hex)?;
+        state.serialize_field("message_hex", &self.message_hex)?;
+        state.serialize_field("signature_hex", &self.signature_hex)?;
+        state.end()
+    }
+}
+
+/// Encode a set of test vectors as a JSON array.
+pub fn test_vectors_to_json(vectors: &[TestVector]) -> Result<String, serde_json::Error> {
+    serde_json::to_string(vectors)
+}
+
+impl Keypair {
+    /// Generate `count` test vectors, each with a fresh keypair from the
+    /// `csprng` and a random message of up to 256 bytes, signed using the
+    /// hash function `D`.
+    pub fn generate_test_vectors<D>(count: usize, csprng: &mut dyn Rng) -> Vec<TestVector>
+            where D: Digest<OutputSize = U64> + Default {
+
+        (0..count).map(|_| {
+            let keypair: Keypair = Keypair::generate::<D>(csprng);
+            let length: u32 = csprng.next_u32() % (MAX_MESSAGE_LENGTH + 1);
+            let mut message: Vec<u8> = vec![0u8; length as usize];
+
+            csprng.fill_bytes(&mut message);
+            let signature: Signature = keypair.sign::<D>(&message);
+
+            TestVector{
+                secret_key_hex: keypair.secret.as_bytes().to_hex(),
+                public_key_hex: keypair.public.as_bytes().to_hex(),
+                message_hex: message.to_hex(),
+                signature_hex: signature.to_bytes().to_vec().to_hex(),
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use hex::FromHex;
+    use serde_json::Value;
+    use sha2::Sha512;
+    use ed25519::PublicKey;
+    use super::*;
+
+    #[test]
+    fn generated_test_vectors_verify() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let vectors: Vec<TestVector> = Keypair::generate_test_vectors::<Sha512>(8, &mut csprng);
+
+        assert_eq!(vectors.len(), 8);
+        for vector in vectors.iter() {
+            let public_bytes: Vec<u8> = FromHex::from_hex(&vector.public_key_hex).unwrap();
+            let message: Vec<u8> = FromHex::from_hex(&vector.message_hex).unwrap();
+            let signature_bytes: Vec<u8> = FromHex::from_hex(&vector.signature_hex).unwrap();
+            let public: PublicKey = PublicKey::from_bytes(&public_bytes).unwrap();
+            let signature: Signature = Signature::from_bytes(&signature_bytes).unwrap();
+
+            assert_eq!(vector.secret_key_hex.len(), 64);
+            assert!(public.verify::<Sha512>(&message, &signature).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_vectors_to_json_is_an_array_of_objects() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let vectors: Vec<TestVector> = Keypair::generate_test_vectors::<Sha512>(2, &mut csprng);
+        let json: Value = serde_json::from_str(&test_vectors_to_json(&vectors).unwrap()).unwrap();
+
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[1]["signature_hex"].as_str().unwrap(), vectors[1].signature_hex);
+        assert_eq!(json[0].as_object().unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,314 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! The ed25519 test vectors from RFC8032 §7.1, for testing other
+//! implementations and code built upon this one.
+//!
+//! RFC8032 §7.1 gives five test vectors for Ed25519 with SHA-512.  The
+//! vectors for Ed25519ctx and Ed25519ph, in §7.2 and §7.3, sign differently,
+//! and are not included here.
+
+use ed25519::{PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH, SIGNATURE_LENGTH};
+
+/// A test vector: a keypair, a message, and the signature upon it.
+#[derive(Clone, Copy, Debug)]
+pub struct TestVector {
+    /// The secret key.
+    pub secret_key: [u8; SECRET_KEY_LENGTH],
+    /// The public key corresponding to `secret_key`.
+    pub public_key: [u8; PUBLIC_KEY_LENGTH],
+    /// The message.
+    pub message: &'static [u8],
+    /// The signature upon `message` made with `secret_key`, using SHA-512.
+    pub signature: [u8; SIGNATURE_LENGTH],
+}
+
+/// All of the test vectors, in the order in which they appear in RFC8032.
+pub const VECTORS: [TestVector; 5] = [VECTOR_1, VECTOR_2, VECTOR_3, VECTOR_4, VECTOR_5];
+
+/// TEST 1 from RFC8032 §7.1.
+pub const VECTOR_1: TestVector = TestVector{
+    secret_key: [
+        157,  97, 177, 157, 239, 253,  90,  96,
+        186, 132,  74, 244, 146, 236,  44, 196,
+         68,  73, 197, 105, 123,  50, 105,  25,
+        112,  59, 172,   3,  28, 174, 127,  96, ],
+    public_key: [
+        215,  90, 152,   1, 130, 177,  10, 183,
+        213,  75, 254, 211, 201, 100,   7,  58,
+         14, 225, 114, 243, 218, 166,  35,  37,
+        175,   2,  26, 104, 247,   7,  81,  26, ],
+    message: &[],
+    signature: [
+        229,  86,  67, 000, 195,  96, 172, 114,
+        144, 134, 226, 204, 128, 110, 130, 138,
+        132, 135, 127,  30, 184, 229, 217, 116,
+        216, 115, 224, 101,  34,  73,   1,  85,
+         95, 184, 130,  21, 144, 163,  59, 172,
+        198,  30,  57, 112,  28, 249, 180, 107,
+        210,  91, 245, 240,  89,  91, 190,  36,
+        101,  81,  65,  67, 142, 122,  16,  11, ],
+};
+
+/// TEST 2 from RFC8032 §7.1.
+pub const VECTOR_2: TestVector = TestVector{
+    secret_key: [
+         76, 205,   8, 155,  40, 255, 150, 218,
+        157, 182, 195,  70, 236,  17,  78,  15,
+         91, 138,  49, 159,  53, 171, 166,  36,
+        218, 140, 246, 237,  79, 184, 166, 251, ],
+    public_key: [
+         61,  64,  23, 195, 232,  67, 137,  90,
+        146, 183,  10, 167,  77,  27, 126, 188,
+        156, 152,  44, 207,  46, 196, 150, 140,
+        192, 205,  85, 241,  42, 244, 102,  12, ],
+    message: &[
+        114, ],
+    signature: [
+        146, 160,   9, 169, 240, 212, 202, 184,
+        114,  14, 130,  11,  95, 100,  37,  64,
+        162, 178, 123,  84,  22,  80,  63, 143,
+        179, 118,  34,  35, 235, 219, 105, 218,
+          8,  90, 193, 228,  62,  21, 153, 110,
+         69, 143,  54,  19, 208, 241,  29, 140,
+         56, 123,  46, 174, 180,  48,  42, 238,
+        176,  13,  41,  22,  18, 187,  12, 000, ],
+};
+
+/// TEST 3 from RFC8032 §7.1.
+pub const VECTOR_3: TestVector = TestVector{
+    secret_key: [
+        197, 170, 141, 244,  63, 159, 131, 123,
+        237, 183,  68,  47,  49, 220, 183, 177,
+        102, 211, 133,  53,   7, 111,   9,  75,
+        133, 206,  58,  46,  11,  68,  88, 247, ],
+    public_key: [
+        252,  81, 205, 142,  98,  24, 161, 163,
+        141, 164, 126, 208,   2,  48, 240,  88,
+          8,  22, 237,  19, 186,  51,   3, 172,
+         93, 235, 145,  21,  72, 144, 128,  37, ],
+    message: &[
+        175, 130, ],
+    signature: [
+         98, 145, 214,  87, 222, 236,  36,   2,
+         72,  39, 230, 156,  58, 190,   1, 163,
+         12, 229,  72, 162, 132, 116,  58,  68,
+         94,  54, 128, 215, 219,  90, 195, 172,
+         24, 255, 155,  83, 141,  22, 242, 144,
+        174, 103, 247,  96, 152,  77, 198,  89,
+         74, 124,  21, 233, 113, 110, 210, 141,
+        192,  39, 190, 206, 234,  30, 196,  10, ],
+};
+
+/// TEST 1024 from RFC8032 §7.1.
+pub const VECTOR_4: TestVector = TestVector{
+    secret_key: [
+        245, 229, 118, 124, 241,  83,  49, 149,
+         23,  99,  15,  34, 104, 118, 184, 108,
+        129,  96, 204,  88,  59, 192,  19, 116,
+         76, 107, 242,  85, 245, 204,  14, 229, ],
+    public_key: [
+         39, 129,  23, 252,  20,  76, 114,  52,
+         15, 103, 208, 242,  49, 110, 131, 134,
+        206, 255, 191,  43,  36,  40, 201, 197,
+         31, 239, 124,  89, 127,  29,  66, 110, ],
+    message: &[
+          8, 184, 178, 183,  51,  66,  66,  67,
+        118,  15, 228,  38, 164, 181,  73,   8,
+         99,  33,  16, 166, 108,  47, 101, 145,
+        234, 189,  51,  69, 227, 228, 235, 152,
+        250, 110,  38,  75, 240, 158, 254,  18,
+        238,  80, 248, 245,  78, 159, 119, 177,
+        227,  85, 246, 197,   5,  68, 226,  63,
+        177,  67,  61, 223, 115, 190, 132, 216,
+        121, 222, 124, 000,  70, 220,  73, 150,
+        217, 231, 115, 244, 188, 158, 254,  87,
+         56, 130, 154, 219,  38, 200,  27,  55,
+        201,  58,  27,  39,  11,  32,  50, 157,
+        101, 134, 117, 252, 110, 165,  52, 224,
+        129,  10,  68,  50, 130, 107, 245, 140,
+        148,  30, 251, 101, 213, 122,  51, 139,
+        189,  46,  38, 100,  15, 137, 255, 188,
+         26, 133, 142, 252, 184,  85,  14, 227,
+        165, 225, 153, 139, 209, 119, 233,  58,
+        115,  99, 195,  68, 254, 107,  25, 158,
+        229, 208,  46, 130, 213,  34, 196, 254,
+        186,  21,  69,  47, 128,  40, 138, 130,
+         26,  87, 145,  22, 236, 109, 173,  43,
+         59,  49,  13, 169,   3,  64,  26, 166,
+         33, 000, 171,  93,  26,  54,  85,  62,
+          6,  32,  59,  51, 137,  12, 201, 184,
+         50, 247, 158, 248,   5,  96, 204, 185,
+        163, 156, 231, 103, 150, 126, 214,  40,
+        198, 173,  87,  60, 177,  22, 219, 239,
+        239, 215,  84, 153, 218, 150, 189, 104,
+        168, 169, 123, 146, 138, 139, 188,  16,
+         59, 102,  33, 252, 222,  43, 236, 161,
+         35,  29,  32, 107, 230, 205, 158, 199,
+        175, 246, 246, 201,  79, 205, 114,   4,
+        237,  52,  85, 198, 140, 131, 244, 164,
+         29, 164, 175,  43, 116, 239,  92,  83,
+        241, 216, 172, 112, 189, 203, 126, 209,
+        133, 206, 129, 189, 132,  53, 157,  68,
+         37,  77, 149,  98, 158, 152,  85, 169,
+         74, 124,  25,  88, 209, 248, 173, 165,
+        208,  83,  46, 216, 165, 170,  63, 178,
+        209, 123, 167,  14, 182,  36, 142,  89,
+         78,  26,  34, 151, 172, 187, 179, 157,
+         80,  47,  26, 140, 110, 182, 241, 206,
+         34, 179, 222,  26,  31,  64, 204,  36,
+         85,  65,  25, 168,  49, 169, 170, 214,
+          7, 156, 173, 136,  66,  93, 230, 189,
+        225, 169,  24, 126, 187,  96, 146, 207,
+        103, 191,  43,  19, 253, 101, 242, 112,
+        136, 215, 139, 126, 136,  60, 135,  89,
+        210, 196, 245, 198,  90, 219, 117,  83,
+        135, 138, 213, 117, 249, 250, 216, 120,
+        232,  10,  12, 155, 166,  59, 203, 204,
+         39,  50, 230, 148, 133, 187, 201, 201,
+         11, 251, 214,  36, 129, 217,   8, 155,
+        236, 207, 128, 207, 226, 223,  22, 162,
+        207, 101, 189, 146, 221,  89, 123,   7,
+          7, 224, 145, 122, 244, 139, 187, 117,
+        254, 212,  19, 210,  56, 245,  85,  90,
+        122,  86, 157, 128, 195,  65,  74, 141,
+          8,  89, 220, 101, 164,  97,  40, 186,
+        178, 122, 248, 122, 113,  49,  79,  49,
+        140, 120,  43,  35, 235, 254, 128, 139,
+        130, 176, 206,  38,  64,  29,  46,  34,
+        240,  77, 131, 209,  37,  93, 197,  26,
+        221, 211, 183,  90,  43,  26, 224, 120,
+         69,   4, 223,  84,  58, 248, 150, 155,
+        227, 234, 112, 130, 255, 127, 201, 136,
+        140,  20,  77, 162, 175,  88,  66, 158,
+        201,  96,  49, 219, 202, 211, 218, 217,
+        175,  13, 203, 170, 175,  38, 140, 184,
+        252, 255, 234, 217,  79,  60, 124, 164,
+        149, 224,  86, 169, 180, 122, 205, 183,
+         81, 251, 115, 230, 102, 198, 198,  85,
+        173, 232,  41, 114, 151, 208, 122, 209,
+        186,  94,  67, 241, 188, 163,  35,   1,
+        101,  19,  57, 226,  41,   4, 204, 140,
+         66, 245, 140,  48, 192,  74, 175, 219,
+          3, 141, 218,   8,  71, 221, 152, 141,
+        205, 166, 243, 191, 209,  92,  75,  76,
+         69,  37, 000,  74, 160, 110, 239, 248,
+        202,  97, 120,  58, 172, 236,  87, 251,
+         61,  31, 146, 176, 254,  47, 209, 168,
+         95, 103,  36,  81, 123, 101, 230,  20,
+        173, 104,   8, 214, 246, 238,  52, 223,
+        247,  49,  15, 220, 130, 174, 191, 217,
+          4, 176,  30,  29, 197,  75,  41,  39,
+          9,  75,  45, 182, 141, 111, 144,  59,
+        104,  64,  26, 222, 191,  90, 126,   8,
+        215, 143, 244, 239,  93,  99, 101,  58,
+        101,   4,  12, 249, 191, 212, 172, 167,
+        152,  74, 116, 211, 113,  69, 152, 103,
+        128, 252,  11,  22, 172,  69,  22,  73,
+        222,  97, 136, 167, 219, 223,  25,  31,
+        100, 181, 252,  94,  42, 180, 123,  87,
+        247, 247,  39, 108, 212,  25, 193, 122,
+         60, 168, 225, 185,  57, 174,  73, 228,
+        136, 172, 186, 107, 150,  86,  16, 181,
+         72,   1,   9, 200, 177, 123, 128, 225,
+        183, 183,  80, 223, 199,  89, 141,  93,
+         80,  17, 253,  45, 204,  86, 000, 163,
+         46, 245, 181,  42,  30, 204, 130,  14,
+         48, 138, 163,  66, 114,  26, 172,   9,
+         67, 191, 102, 134, 182,  75,  37, 121,
+         55, 101,   4, 204, 196, 147, 217, 126,
+        106, 237,  63, 176, 249, 205, 113, 164,
+         61, 212, 151, 240,  31,  23, 192, 226,
+        203,  55, 151, 170,  42,  47,  37, 102,
+         86,  22, 142, 108,  73, 106, 252,  95,
+        185,  50,  70, 246, 177,  17,  99, 152,
+        163,  70, 241, 166,  65, 243, 176,  65,
+        233, 137, 247, 145,  79, 144, 204,  44,
+        127, 255,  53, 120, 118, 229,   6, 181,
+         13,  51,  75, 167, 124,  34,  91, 195,
+          7, 186,  83, 113,  82, 243, 241,  97,
+         14,  78, 175, 229, 149, 246, 217, 217,
+         13,  17, 250, 169,  51, 161,  94, 241,
+         54, 149,  70, 134, 138, 127,  58,  69,
+        169, 103, 104, 212,  15, 217, 208,  52,
+         18, 192, 145, 198,  49,  92, 244, 253,
+        231, 203, 104,  96, 105,  55,  56,  13,
+        178, 234, 170, 112, 123,  76,  65, 133,
+        195,  46, 221, 205, 211,   6, 112,  94,
+         77, 193, 255, 200, 114, 238, 238,  71,
+         90, 100, 223, 172, 134, 171, 164,  28,
+          6,  24, 152,  63, 135,  65, 197, 239,
+        104, 211, 161,   1, 232, 163, 184, 202,
+        198,  12, 144,  92,  21, 252, 145,   8,
+         64, 185,  76, 000, 160, 185, 208, ],
+    signature: [
+         10, 171,  76, 144,   5,   1, 179, 226,
+         77, 124, 223,  70,  99,  50, 106,  58,
+        135, 223,  94,  72,  67, 178, 203, 219,
+        103, 203, 246, 228,  96, 254, 195,  80,
+        170,  83, 113, 177,  80, 143, 159,  69,
+         40, 236, 234,  35, 196,  54, 217,  75,
+         94, 143, 205,  79, 104,  30,  48, 166,
+        172, 000, 169, 112,  74,  24, 138,   3, ],
+};
+
+/// TEST SHA(abc) from RFC8032 §7.1.
+pub const VECTOR_5: TestVector = TestVector{
+    secret_key: [
+        131,  63, 230,  36,   9,  35, 123, 157,
+         98, 236, 119,  88, 117,  32, 145,  30,
+        154, 117, 156, 236,  29,  25, 117,  91,
+        125, 169,   1, 185, 109, 202,  61,  66, ],
+    public_key: [
+        236,  23,  43, 147, 173,  94,  86,  59,
+        244, 147,  44, 112, 225,  36,  80,  52,
+        195,  84, 103, 239,  46, 253,  77, 100,
+        235, 248,  25, 104,  52, 103, 226, 191, ],
+    message: &[
+        221, 175,  53, 161, 147,  97, 122, 186,
+        204,  65, 115,  73, 174,  32,  65,  49,
+         18, 230, 250,  78, 137, 169, 126, 162,
+         10, 158, 238, 230,  75,  85, 211, 154,
+         33, 146, 153,  42,  39,  79, 193, 168,
+         54, 186,  60,  35, 163, 254, 235, 189,
+         69,  77,  68,  35, 100,  60, 232,  14,
+         42, 154, 201,  79, 165,  76, 164, 159, ],
+    signature: [
+        220,  42,  68,  89, 231,  54, 150,  51,
+        165,  43,  27, 242, 119, 131, 154, 000,
+         32,  16,   9, 163, 239, 191,  62, 203,
+        105, 190, 162,  24, 108,  38, 181, 137,
+          9,  53,  31, 201, 172, 144, 179, 236,
+        253, 251, 199, 198, 100,  49, 224,  48,
+         61, 202,  23, 156,  19, 138, 193, 122,
+        217, 190, 241,  23, 115,  49, 167,   4, ],
+};
+
+#[cfg(test)]
+mod test {
+    use sha2::Sha512;
+    use ed25519::{Keypair, PublicKey, SecretKey, Signature};
+    use super::*;
+
+    #[test]
+    fn rfc8032_vectors_pass() {
+        for vector in VECTORS.iter() {
+            let secret: SecretKey = SecretKey::from_bytes(&vector.secret_key).unwrap();
+            let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+            let keypair: Keypair = Keypair{ secret, public };
+            let signature: Signature = keypair.sign::<Sha512>(vector.message);
+
+            assert_eq!(public.to_bytes(), vector.public_key);
+            assert_eq!(&signature.to_bytes()[..], &vector.signature[..]);
+            assert!(public.verify::<Sha512>(vector.message,
+                                            &Signature::from_bytes(&vector.signature).unwrap()).is_ok());
+        }
+        assert_eq!(VECTOR_4.message.len(), 1023);
+    }
+}
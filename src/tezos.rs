@@ -0,0 +1,171 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Import and export of keys in the base58check encoding used by Tezos.
+
+use std::string::String;
+use std::vec::Vec;
+
+use bs58;
+
+use digest::Digest;
+use digest::FixedOutput;
+
+use sha2::Sha256;
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use ed25519::SECRET_KEY_LENGTH;
+use errors::SignatureError;
+
+/// The prefix which encodes to `edsk` for a 32-byte secret key seed.
+const EDSK_PREFIX: [u8; 4] = [13, 15, 58, 7];
+
+/// The prefix which encodes to `edpk` for a 32-byte public key.
+const EDPK_PREFIX: [u8; 4] = [13, 15, 37, 217];
+
+/// The length of a base58check checksum, in bytes.
+const CHECKSUM_LENGTH: usize = 4;
+
+/// Compute the base58check checksum, the first four bytes of
+/// `SHA-256(SHA-256(payload))`.
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LENGTH] {
+    let mut h: Sha256 = Sha256::default();
+    let mut inner: [u8; 32] = [0u8; 32];
+    let mut checksum: [u8; CHECKSUM_LENGTH] = [0u8; CHECKSUM_LENGTH];
+
+    h.input(payload);
+    inner.copy_from_slice(h.fixed_result().as_slice());
+
+    h = Sha256::default();
+    h.input(&inner);
+    checksum.copy_from_slice(&h.fixed_result().as_slice()[..CHECKSUM_LENGTH]);
+    checksum
+}
+
+/// Encode `bytes` with the given `prefix` as base58check.
+fn encode(prefix: &[u8; 4], bytes: &[u8]) -> String {
+    let mut payload: Vec<u8> = Vec::with_capacity(prefix.len() + bytes.len() + CHECKSUM_LENGTH);
+
+    payload.extend_from_slice(prefix);
+    payload.extend_from_slice(bytes);
+
+    let check = checksum(&payload);
+    payload.extend_from_slice(&check);
+
+    bs58::encode(payload).into_string()
+}
+
+/// Decode a base58check string, which must carry the given `prefix` and
+/// contain exactly `length` bytes of data.
+fn decode(prefix: &[u8; 4], length: usize, encoded: &str) -> Result<Vec<u8>, SignatureError> {
+    let payload: Vec<u8> = bs58::decode(encoded).into_vec()
+        .or(Err(SignatureError::InvalidEncoding))?;
+
+    if payload.len() != prefix.len() + length + CHECKSUM_LENGTH {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    let (body, check) = payload.split_at(payload.len() - CHECKSUM_LENGTH);
+
+    if body[..prefix.len()] != prefix[..] || &checksum(body)[..] != check {
+        return Err(SignatureError::InvalidEncoding);
+    }
+    Ok(body[prefix.len()..].to_vec())
+}
+
+impl Keypair {
+    /// Construct a `Keypair` from a Tezos `edsk...` encoded secret key.
+    ///
+    /// Tezos encodes the 32-byte secret key seed using base58check, with a
+    /// prefix which causes the resulting 54-character string to begin with
+    /// `edsk`.  The public key is derived from the secret key using SHA-512.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Keypair`, or whose error
+    /// value is `SignatureError::InvalidEncoding` if the string is not valid
+    /// base58, has the wrong prefix or length, or has a bad checksum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "tezos")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// let keypair = Keypair::from_tezos_base58(
+    ///     "edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh").unwrap();
+    /// let (_edsk, edpk) = keypair.to_tezos_base58();
+    ///
+    /// assert_eq!(edpk, "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav");
+    /// # }
+    /// # #[cfg(not(feature = "tezos"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_tezos_base58(edsk: &str) -> Result<Keypair, SignatureError> {
+        let bytes: Vec<u8> = decode(&EDSK_PREFIX, SECRET_KEY_LENGTH, edsk)?;
+        let secret: SecretKey = SecretKey::from_bytes(&bytes)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&secret);
+
+        Ok(Keypair{ secret, public })
+    }
+
+    /// Encode this `Keypair` in the Tezos base58check format.
+    ///
+    /// # Returns
+    ///
+    /// A tuple of the `edsk...` encoded secret key and the `edpk...` encoded
+    /// public key.
+    pub fn to_tezos_base58(&self) -> (String, String) {
+        let edsk: String = encode(&EDSK_PREFIX, self.secret.as_bytes());
+        let edpk: String = encode(&EDPK_PREFIX, self.public.as_bytes());
+
+        (edsk, edpk)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The bootstrap accounts from the Tezos sandbox documentation.
+    static VECTORS: [(&str, &str); 2] = [
+        ("edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsh",
+         "edpkuBknW28nW72KG6RoHtYW7p12T6GKc7nAbwYX5m8Wd9sDVC9yav"),
+        ("edsk39qAm1fiMjgmPkw1EgQYkMzkJezLNewd7PLNHTkr6w9XA2zdfo",
+         "edpktzNbDAUjUk697W7gYg2CRuBQjyPxbEg8dLccYYwKSKvkPvjtV9"),
+    ];
+
+    #[test]
+    fn tezos_vectors_roundtrip() {
+        for &(edsk, edpk) in VECTORS.iter() {
+            let keypair: Keypair = Keypair::from_tezos_base58(edsk).unwrap();
+            let (encoded_edsk, encoded_edpk) = keypair.to_tezos_base58();
+
+            assert_eq!(encoded_edsk, edsk);
+            assert_eq!(encoded_edpk, edpk);
+        }
+    }
+
+    #[test]
+    fn tezos_bad_checksum_is_rejected() {
+        // The last character of the first vector, changed from 'h' to 'i'.
+        assert_eq!(Keypair::from_tezos_base58(
+                   "edsk3gUfUPyBSfrS9CCgmCiQsTCHGkviBDusMxDJstFtojtc1zcpsi").unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+
+    #[test]
+    fn tezos_public_key_is_not_a_secret_key() {
+        assert_eq!(Keypair::from_tezos_base58(VECTORS[0].1).unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+}
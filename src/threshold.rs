@@ -0,0 +1,231 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Splitting of secret keys into `n` shares, any `t` of which suffice to
+//! recover the key.
+
+use std::vec::Vec;
+
+use rand::Rng;
+
+use backup::gf_mul;
+use ed25519::SecretKey;
+use ed25519::SECRET_KEY_LENGTH;
+use errors::SignatureError;
+
+/// The length of a `KeyShare`, in bytes: its index, its threshold, and one
+/// byte for each byte of the secret key.
+pub const KEY_SHARE_LENGTH: usize = SECRET_KEY_LENGTH + 2;
+
+/// The inverse of `a` in GF(2⁸), which is `a²⁵⁴`, or zero if `a` is zero.
+fn gf_inv(a: u8) -> u8 {
+    let mut result: u8 = 1;
+    let mut power: u8 = a;
+
+    for bit in 0..8 {
+        if (254 >> bit) & 1 == 1 {
+            result = gf_mul(result, power);
+        }
+        power = gf_mul(power, power);
+    }
+    result
+}
+
+/// One of the shares of a secret key, from `SecretKey::split()`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct KeyShare {
+    index: u8,
+    threshold: u8,
+    value: [u8; SECRET_KEY_LENGTH],
+}
+
+impl KeyShare {
+    /// The index of this share, from 1 to the number of shares.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    /// The number of shares which are needed to recover the secret key.
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Convert this share to bytes: its index, its threshold, and its value.
+    pub fn to_bytes(&self) -> [u8; KEY_SHARE_LENGTH] {
+        let mut bytes: [u8; KEY_SHARE_LENGTH] = [0u8; KEY_SHARE_LENGTH];
+
+        bytes[0] = self.index;
+        bytes[1] = self.threshold;
+        bytes[2..].copy_from_slice(&self.value);
+        bytes
+    }
+
+    /// Construct a share from the bytes produced by `to_bytes()`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `KeyShare`, or whose error value is
+    /// `SignatureError::InvalidEncoding` if the `bytes` are not
+    /// `KEY_SHARE_LENGTH` long, or the index or threshold is zero.
+    pub fn from_bytes(bytes: &[u8]) -> Result<KeyShare, SignatureError> {
+        if bytes.len() != KEY_SHARE_LENGTH || bytes[0] == 0 || bytes[1] == 0 {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        Ok(KeyShare{ index: bytes[0], threshold: bytes[1], value: *array_ref!(bytes, 2, SECRET_KEY_LENGTH) })
+    }
+}
+
+impl SecretKey {
+    /// Split this secret key into `n` shares, any `threshold` of which
+    /// recover it with `SecretKey::combine()`, using randomness from the
+    /// `csprng`.
+    ///
+    /// # Construction
+    ///
+    /// This is Shamir's scheme over GF(2⁸), as in the 2-of-3 backups of
+    /// `Keypair::split_secret_2of3()`, but with any threshold `t` and number
+    /// of shares `n` such that `1 ≤ t ≤ n ≤ 255`.  For each byte `k` of the
+    /// key a random polynomial `f` of degree `t - 1` with `f(0) = k` is
+    /// chosen, and share `x`, for `x` in `1..n + 1`, holds `f(x)`.  Any `t`
+    /// shares determine each polynomial, and so the key, by Lagrange
+    /// interpolation, while any `t - 1` of them reveal nothing about it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `n` shares, in order of their
+    /// indices, or whose error value is `SignatureError::InvalidInput` unless
+    /// `1 ≤ threshold ≤ n`.
+    pub fn split(&self, threshold: u8, n: u8, csprng: &mut dyn Rng) -> Result<Vec<KeyShare>, SignatureError> {
+        if threshold == 0 || threshold > n {
+            return Err(SignatureError::InvalidInput);
+        }
+        let mut coefficients: Vec<[u8; SECRET_KEY_LENGTH]> = vec![[0u8; SECRET_KEY_LENGTH]; threshold as usize - 1];
+
+        for coefficient in coefficients.iter_mut() {
+            csprng.fill_bytes(coefficient);
+        }
+        Ok((1..n as u16 + 1).map(|x| {
+            let mut value: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+
+            for k in 0..SECRET_KEY_LENGTH {
+                value[k] = coefficients.iter().rev()
+                    .fold(0u8, |y, coefficient| gf_mul(y, x as u8) ^ coefficient[k]);
+                value[k] = gf_mul(value[k], x as u8) ^ self.0[k];
+            }
+            KeyShare{ index: x as u8, threshold, value }
+        }).collect())
+    }
+
+    /// Recover a secret key from at least `threshold` of the `shares`
+    /// produced by `SecretKey::split()`, in any order.
+    ///
+    /// Shares carry no integrity protection: a corrupted share, or one from a
+    /// different split, yields the wrong key, so check the recovered key
+    /// against the known public key.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `SecretKey`, or whose error value is
+    /// `SignatureError::InvalidInput` if there are fewer shares than their
+    /// threshold, if their thresholds differ, or if two shares have the same
+    /// index.
+    pub fn combine(shares: &[KeyShare]) -> Result<SecretKey, SignatureError> {
+        let threshold: u8 = shares.first().ok_or(SignatureError::InvalidInput)?.threshold;
+
+        if shares.len() < threshold as usize || shares.iter().any(|share| share.threshold != threshold) {
+            return Err(SignatureError::InvalidInput);
+        }
+        for (i, share) in shares.iter().enumerate() {
+            if share.index == 0 || shares[..i].iter().any(|other| other.index == share.index) {
+                return Err(SignatureError::InvalidInput);
+            }
+        }
+        let mut secret: SecretKey = SecretKey([0u8; SECRET_KEY_LENGTH]);
+
+        for share in shares {
+            // The Lagrange basis polynomial for this share, at zero.
+            let basis: u8 = shares.iter().filter(|other| other.index != share.index)
+                .fold(1u8, |basis, other| gf_mul(basis, gf_mul(other.index, gf_inv(other.index ^ share.index))));
+
+            for k in 0..SECRET_KEY_LENGTH {
+                secret.0[k] ^= gf_mul(share.value[k], basis);
+            }
+        }
+        Ok(secret)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::OsRng;
+    use sha2::Sha512;
+    use ed25519::{Keypair, PublicKey, Signature, keypair_for_testing};
+    use super::*;
+
+    #[test]
+    fn gf_inverses_are_inverses() {
+        for a in 1..256 {
+            assert_eq!(gf_mul(a as u8, gf_inv(a as u8)), 1);
+        }
+    }
+
+    #[test]
+    fn any_three_of_five_shares_recover_the_secret() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let keypair: Keypair = keypair_for_testing("threshold");
+        let shares: Vec<KeyShare> = keypair.secret.split(3, 5, &mut csprng).unwrap();
+
+        assert_eq!(shares.len(), 5);
+        for i in 0..5 {
+            for j in i + 1..5 {
+                for k in j + 1..5 {
+                    let subset: [KeyShare; 3] = [shares[k], shares[i], shares[j]];
+                    let secret: SecretKey = SecretKey::combine(&subset).unwrap();
+                    let signer: Keypair = Keypair{ public: PublicKey::from_secret::<Sha512>(&secret),
+                                                   secret };
+                    let signature: Signature = signer.sign::<Sha512>(b"test message");
+
+                    assert_eq!(signer.public, keypair.public);
+                    assert_eq!(keypair.public.verify::<Sha512>(b"test message", &signature), Ok(()));
+                }
+            }
+        }
+        assert_eq!(SecretKey::combine(&shares).unwrap().to_bytes(), keypair.secret.to_bytes());
+        assert_eq!(SecretKey::combine(&shares[..2]).unwrap_err(), SignatureError::InvalidInput);
+    }
+
+    #[test]
+    fn key_shares_roundtrip_through_bytes() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let shares: Vec<KeyShare> = keypair_for_testing("threshold").secret.split(2, 3, &mut csprng).unwrap();
+
+        for share in shares.iter() {
+            assert_eq!(KeyShare::from_bytes(&share.to_bytes()), Ok(*share));
+        }
+        assert_eq!(shares[2].index(), 3);
+        assert_eq!(shares[2].threshold(), 2);
+        assert_eq!(KeyShare::from_bytes(&shares[0].to_bytes()[..33]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(KeyShare::from_bytes(&[0u8; KEY_SHARE_LENGTH]), Err(SignatureError::InvalidEncoding));
+    }
+
+    #[test]
+    fn bad_parameters_and_shares_are_rejected() {
+        let mut csprng: OsRng = OsRng::new().unwrap();
+        let secret: SecretKey = keypair_for_testing("threshold").secret;
+        let shares: Vec<KeyShare> = secret.split(2, 3, &mut csprng).unwrap();
+        let others: Vec<KeyShare> = secret.split(3, 3, &mut csprng).unwrap();
+
+        assert_eq!(secret.split(0, 3, &mut csprng), Err(SignatureError::InvalidInput));
+        assert_eq!(secret.split(4, 3, &mut csprng), Err(SignatureError::InvalidInput));
+        assert_eq!(secret.split(255, 255, &mut csprng).map(|shares| shares.len()), Ok(255));
+        assert_eq!(SecretKey::combine(&[shares[0], shares[0]]).unwrap_err(), SignatureError::InvalidInput);
+        assert_eq!(SecretKey::combine(&[shares[0], others[1], others[2]]).unwrap_err(), SignatureError::InvalidInput);
+        assert_eq!(SecretKey::combine(&[]).unwrap_err(), SignatureError::InvalidInput);
+    }
+}
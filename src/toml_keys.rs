@@ -0,0 +1,135 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Encoding of keypairs in TOML configuration files.
+
+use std::string::String;
+use std::vec::Vec;
+
+use hex::{FromHex, ToHex};
+
+use sha2::Sha512;
+
+use toml;
+
+use ed25519::{Keypair, PublicKey, SecretKey};
+use errors::SignatureError;
+
+/// Decode the hexadecimal string `key` of the TOML `table`.
+fn hex_key(table: &toml::Table, key: &str) -> Result<Vec<u8>, SignatureError> {
+    let encoded: &str = table.get(key).and_then(toml::Value::as_str)
+        .ok_or(SignatureError::InvalidEncoding)?;
+
+    FromHex::from_hex(encoded).or(Err(SignatureError::InvalidEncoding))
+}
+
+impl Keypair {
+    /// Encode this `Keypair` as TOML.
+    ///
+    /// The output is two TOML keys, each a string of lowercase hexadecimal:
+    ///
+    /// ```text
+    /// secret_key = "<64 hex digits>"
+    /// public_key = "<64 hex digits>"
+    /// ```
+    ///
+    /// # Warning
+    ///
+    /// The output contains the secret key, in the clear.
+    pub fn to_toml(&self) -> String {
+        format!("secret_key = \"{}\"\npublic_key = \"{}\"\n",
+                self.secret.as_bytes().to_hex(), self.public.as_bytes().to_hex())
+    }
+
+    /// Decode a `Keypair` from TOML, in the format of `Keypair::to_toml()`.
+    ///
+    /// Other keys in the same table are ignored, so a keypair may share a
+    /// configuration file with other settings.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the decoded `Keypair`, or whose error
+    /// value is
+    ///
+    /// * `SignatureError::InvalidEncoding` if the input is not TOML whose
+    ///   `secret_key` and `public_key` are 32 bytes of hex, or
+    /// * `SignatureError::InconsistentKeypair` if the `public_key` is not the
+    ///   one derived from the `secret_key`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate ed25519_dalek;
+    /// # #[cfg(feature = "toml")]
+    /// # fn main() {
+    /// use ed25519_dalek::Keypair;
+    ///
+    /// let toml: &str = "secret_key = \"9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60\"\n\
+    ///                   public_key = \"d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a\"\n";
+    /// let keypair: Keypair = Keypair::from_toml(toml).unwrap();
+    ///
+    /// assert_eq!(keypair.to_toml(), toml);
+    /// # }
+    /// # #[cfg(not(feature = "toml"))]
+    /// # fn main() { }
+    /// ```
+    pub fn from_toml(toml: &str) -> Result<Keypair, SignatureError> {
+        let table: toml::Table = toml.parse().or(Err(SignatureError::InvalidEncoding))?;
+        let secret: SecretKey = SecretKey::from_bytes(&hex_key(&table, "secret_key")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let public: PublicKey = PublicKey::from_bytes(&hex_key(&table, "public_key")?)
+            .or(Err(SignatureError::InvalidEncoding))?;
+        let keypair: Keypair = Keypair{ secret, public };
+
+        keypair.verify_own_public_key::<Sha512>()?;
+        Ok(keypair)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn toml_roundtrip() {
+        let keypair: Keypair = keypair_for_testing("toml");
+        let decoded: Keypair = Keypair::from_toml(&keypair.to_toml()).unwrap();
+
+        assert_eq!(decoded.to_raw_bytes()[..], keypair.to_raw_bytes()[..]);
+
+        let config: String = format!("[server]\nport = 8080\n\n[signing]\n{}", keypair.to_toml());
+        let table: toml::Table = config.parse().unwrap();
+        let signing: String = toml::to_string(&table["signing"]).unwrap();
+
+        assert_eq!(Keypair::from_toml(&signing).unwrap().public, keypair.public);
+    }
+
+    #[test]
+    fn toml_inconsistent_keypair_is_rejected() {
+        let keypair: Keypair = keypair_for_testing("toml");
+        let other: Keypair = keypair_for_testing("other");
+        let toml: String = format!("secret_key = \"{}\"\npublic_key = \"{}\"\n",
+                                   keypair.secret.as_bytes().to_hex(),
+                                   other.public.as_bytes().to_hex());
+
+        assert_eq!(Keypair::from_toml(&toml).unwrap_err(), SignatureError::InconsistentKeypair);
+    }
+
+    #[test]
+    fn toml_malformed_is_rejected() {
+        let keypair: Keypair = keypair_for_testing("toml");
+        let missing: String = format!("secret_key = \"{}\"\n", keypair.secret.as_bytes().to_hex());
+
+        assert_eq!(Keypair::from_toml(&missing).unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_toml("secret_key = ").unwrap_err(), SignatureError::InvalidEncoding);
+        assert_eq!(Keypair::from_toml("secret_key = 7\npublic_key = 7").unwrap_err(),
+                   SignatureError::InvalidEncoding);
+    }
+}
@@ -0,0 +1,88 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Keypairs which may only sign from a fixed set of vote payloads.
+
+use std::collections::HashSet;
+
+use sha2::{Digest, Sha256, Sha512};
+
+use ed25519::{Keypair, PublicKey, Signature};
+use errors::SignatureError;
+
+/// A `Keypair` which will only sign messages whose SHA-256 digest is in its
+/// set of allowed payloads.
+///
+/// The keypair is not reachable through a `VoteKeypair`, so code which holds
+/// only the `VoteKeypair` cannot sign anything else with it.  This limits
+/// what a bug can make the key sign; it does not protect the secret key from
+/// an attacker who can read this process's memory.
+#[derive(Debug)]
+pub struct VoteKeypair {
+    keypair: Keypair,
+    allowed_payloads: HashSet<[u8; 32]>,
+}
+
+impl VoteKeypair {
+    /// Restrict the `keypair` to signing payloads whose SHA-256 digests are
+    /// in `allowed_payloads`.
+    pub fn new(keypair: Keypair, allowed_payloads: HashSet<[u8; 32]>) -> VoteKeypair {
+        VoteKeypair{ keypair, allowed_payloads }
+    }
+
+    /// The public key for verifying this keypair's votes.
+    pub fn public_key(&self) -> PublicKey {
+        self.keypair.public
+    }
+
+    /// Sign a `message` with SHA-512, if its SHA-256 digest is one of the
+    /// allowed payloads.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `Signature`, or whose error value
+    /// is `SignatureError::PolicyDenied` if the message is not allowed.
+    pub fn sign(&self, message: &[u8]) -> Result<Signature, SignatureError> {
+        let mut digest: [u8; 32] = [0u8; 32];
+
+        digest.copy_from_slice(&Sha256::digest(message));
+        if !self.allowed_payloads.contains(&digest) {
+            return Err(SignatureError::PolicyDenied);
+        }
+        Ok(self.keypair.sign::<Sha512>(message))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    fn payload_digest(payload: &[u8]) -> [u8; 32] {
+        let mut digest: [u8; 32] = [0u8; 32];
+
+        digest.copy_from_slice(&Sha256::digest(payload));
+        digest
+    }
+
+    #[test]
+    fn vote_keypair_signs_only_allowed_payloads() {
+        let mut allowed: HashSet<[u8; 32]> = HashSet::new();
+
+        allowed.insert(payload_digest(b"yes"));
+        allowed.insert(payload_digest(b"no"));
+
+        let voter: VoteKeypair = VoteKeypair::new(keypair_for_testing("voter"), allowed);
+        let signature: Signature = voter.sign(b"yes").unwrap();
+
+        assert_eq!(voter.public_key().verify::<Sha512>(b"yes", &signature), Ok(()));
+        assert!(voter.sign(b"no").is_ok());
+        assert_eq!(voter.sign(b"maybe"), Err(SignatureError::PolicyDenied));
+    }
+}
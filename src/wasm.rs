@@ -0,0 +1,206 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Bindings for JavaScript, with `wasm-bindgen`.
+//!
+//! The keys and signatures of this crate are wrapped in `WasmPublicKey`,
+//! `WasmSecretKey`, `WasmKeypair`, and `WasmSignature`, which convert to and
+//! from `Uint8Array`s, and which sign and verify with SHA-512.  Errors are
+//! thrown as strings.  Randomness is the caller's business: a new keypair is
+//! made from a 32-byte seed, such as one from `crypto.getRandomValues()`.
+//!
+//! ```js
+//! import { WasmKeypair, WasmPublicKey } from "ed25519-dalek";
+//!
+//! const keypair = WasmKeypair.fromSeed(crypto.getRandomValues(new Uint8Array(32)));
+//! const message = new TextEncoder().encode("hello");
+//! const signature = keypair.sign(message);
+//! const publicKey = WasmPublicKey.fromBytes(keypair.publicKey().toBytes());
+//!
+//! console.assert(publicKey.verify(message, signature));
+//! ```
+//!
+//! The tests of this module run in a JavaScript engine, with
+//! `wasm-pack test --node -- --features wasm`.
+
+use alloc::string::ToString;
+
+use wasm_bindgen::prelude::*;
+
+use js_sys::Uint8Array;
+
+use sha2::Sha512;
+
+use ed25519::{Keypair, PublicKey, SecretKey, Signature};
+use ed25519::SECRET_KEY_LENGTH;
+
+/// An ed25519 public key, for JavaScript.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct WasmPublicKey(PublicKey);
+
+/// An ed25519 secret key, for JavaScript.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct WasmSecretKey(SecretKey);
+
+/// An ed25519 keypair, for JavaScript.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct WasmKeypair(Keypair);
+
+/// An ed25519 signature, for JavaScript.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct WasmSignature(Signature);
+
+#[wasm_bindgen]
+impl WasmPublicKey {
+    /// Construct a public key from its 32 bytes.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmPublicKey, JsValue> {
+        PublicKey::from_bytes(bytes).map(WasmPublicKey).map_err(JsValue::from_str)
+    }
+
+    /// The 32 bytes of this public key.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.0.as_bytes()[..])
+    }
+
+    /// Whether the `signature` on the `message` is valid under this key.
+    pub fn verify(&self, message: &[u8], signature: &WasmSignature) -> bool {
+        self.0.verify::<Sha512>(message, &signature.0).is_ok()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmSecretKey {
+    /// Construct a secret key from its 32 bytes.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmSecretKey, JsValue> {
+        SecretKey::from_bytes(bytes).map(WasmSecretKey).map_err(JsValue::from_str)
+    }
+
+    /// The 32 bytes of this secret key.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.0.as_bytes()[..])
+    }
+
+    /// The public key of this secret key.
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> WasmPublicKey {
+        WasmPublicKey(PublicKey::from_secret::<Sha512>(&self.0))
+    }
+
+    /// Sign a `message` with this secret key.
+    ///
+    /// This derives the public key on every call, so a `WasmKeypair` is
+    /// faster for signing more than once.
+    pub fn sign(&self, message: &[u8]) -> WasmSignature {
+        let public: PublicKey = PublicKey::from_secret::<Sha512>(&self.0);
+
+        WasmSignature(self.0.expand::<Sha512>().sign::<Sha512>(message, &public))
+    }
+}
+
+#[wasm_bindgen]
+impl WasmKeypair {
+    /// Construct a keypair from a 32-byte `seed`, which must be uniformly
+    /// random and kept secret.
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: &Uint8Array) -> Result<WasmKeypair, JsValue> {
+        let mut bytes: [u8; SECRET_KEY_LENGTH] = [0u8; SECRET_KEY_LENGTH];
+
+        if seed.length() as usize != SECRET_KEY_LENGTH {
+            return Err(JsValue::from_str("A seed must be 32 bytes long"));
+        }
+        seed.copy_to(&mut bytes);
+
+        Ok(WasmKeypair(Keypair::from_seed::<Sha512>(&bytes)))
+    }
+
+    /// Construct a keypair from its 64 bytes, the secret key followed by the
+    /// public key, which are checked against each other.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmKeypair, JsValue> {
+        let keypair: Keypair = Keypair::from_raw_bytes(bytes).map_err(JsValue::from_str)?;
+
+        keypair.verify_own_public_key::<Sha512>().map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(WasmKeypair(keypair))
+    }
+
+    /// The 64 bytes of this keypair, the secret key followed by the public
+    /// key.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.0.to_raw_bytes()[..])
+    }
+
+    /// The public half of this keypair.
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> WasmPublicKey {
+        WasmPublicKey(self.0.public)
+    }
+
+    /// The secret half of this keypair.
+    #[wasm_bindgen(js_name = secretKey)]
+    pub fn secret_key(&self) -> WasmSecretKey {
+        WasmSecretKey(SecretKey::from_seed(self.0.secret.as_bytes()))
+    }
+
+    /// Sign a `message` with this keypair.
+    pub fn sign(&self, message: &[u8]) -> WasmSignature {
+        WasmSignature(self.0.sign::<Sha512>(message))
+    }
+
+    /// Whether the `signature` on the `message` is valid under this keypair's
+    /// public key.
+    pub fn verify(&self, message: &[u8], signature: &WasmSignature) -> bool {
+        self.0.verify::<Sha512>(message, &signature.0).is_ok()
+    }
+}
+
+#[wasm_bindgen]
+impl WasmSignature {
+    /// Construct a signature from its 64 bytes, `R || s`.
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmSignature, JsValue> {
+        Signature::from_bytes(bytes).map(WasmSignature).map_err(JsValue::from_str)
+    }
+
+    /// The 64 bytes of this signature, `R || s`.
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Uint8Array {
+        Uint8Array::from(&self.0.to_bytes()[..])
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod test {
+    use std::vec::Vec;
+    use wasm_bindgen_test::wasm_bindgen_test;
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn keypair_from_seed_signs_and_verifies() {
+        let keypair: WasmKeypair = WasmKeypair::from_seed(&Uint8Array::from(&[7u8; 32][..])).unwrap();
+        let public: WasmPublicKey = WasmPublicKey::from_bytes(&keypair.public_key().to_bytes().to_vec()).unwrap();
+        let signature: WasmSignature = keypair.sign(b"test message");
+        let bytes: Vec<u8> = signature.to_bytes().to_vec();
+
+        assert!(public.verify(b"test message", &WasmSignature::from_bytes(&bytes).unwrap()));
+        assert!(!public.verify(b"test massage", &signature));
+        assert!(keypair.secret_key().public_key().verify(b"test message", &keypair.secret_key().sign(b"test message")));
+        assert!(WasmKeypair::from_bytes(&keypair.to_bytes().to_vec()).is_ok());
+        assert!(WasmKeypair::from_seed(&Uint8Array::new_with_length(31)).is_err());
+        assert!(WasmSignature::from_bytes(&bytes[..63]).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Byte layouts for public keys on the wire, for protocols which frame them
+//! in different ways.
+//!
+//! Code which sends or receives public keys can take a `&WireFormat`, and so
+//! be told the layout by its caller:
+//!
+//! * `RawFormat` is the 32 bytes of the key, alone;
+//! * `LengthPrefixedFormat(n)` is the length of the key, 32, as an `n`-byte
+//!   big-endian integer, followed by the key; and
+//! * `TypeTaggedFormat(tag)` is the byte `tag`, followed by the key.
+
+use std::vec::Vec;
+
+use ed25519::PublicKey;
+use ed25519::PUBLIC_KEY_LENGTH;
+use errors::SignatureError;
+
+/// A byte layout for public keys.
+pub trait WireFormat {
+    /// Encode the `public_key` in this layout.
+    fn serialize(&self, public_key: &PublicKey) -> Vec<u8>;
+
+    /// Decode a public key in this layout from exactly the `bytes`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` whose okay value is the `PublicKey`, or whose error value is
+    /// `SignatureError::InvalidEncoding` if the `bytes` are not a public key
+    /// in this layout.
+    fn deserialize(&self, bytes: &[u8]) -> Result<PublicKey, SignatureError>;
+}
+
+/// The 32 bytes of a public key, with no framing.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RawFormat;
+
+impl WireFormat for RawFormat {
+    fn serialize(&self, public_key: &PublicKey) -> Vec<u8> {
+        public_key.as_bytes().to_vec()
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<PublicKey, SignatureError> {
+        PublicKey::from_bytes(bytes).or(Err(SignatureError::InvalidEncoding))
+    }
+}
+
+/// A public key, preceded by its length as a big-endian integer of the given
+/// width in bytes, which must be 1 or 2.
+///
+/// # Panics
+///
+/// `serialize()` and `deserialize()` panic if the width is not 1 or 2.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LengthPrefixedFormat(pub u8);
+
+impl LengthPrefixedFormat {
+    /// The encoding of the length of a public key, in this width.
+    fn prefix(&self) -> &'static [u8] {
+        match self.0 {
+            1 => &[PUBLIC_KEY_LENGTH as u8],
+            2 => &[0, PUBLIC_KEY_LENGTH as u8],
+            width => panic!("length prefix must be 1 or 2 bytes, not {}", width),
+        }
+    }
+}
+
+impl WireFormat for LengthPrefixedFormat {
+    fn serialize(&self, public_key: &PublicKey) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.prefix().to_vec();
+
+        bytes.extend_from_slice(public_key.as_bytes());
+        bytes
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<PublicKey, SignatureError> {
+        let prefix: &[u8] = self.prefix();
+
+        if !bytes.starts_with(prefix) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        RawFormat.deserialize(&bytes[prefix.len()..])
+    }
+}
+
+/// A public key, preceded by a one-byte type or version tag.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TypeTaggedFormat(pub u8);
+
+impl WireFormat for TypeTaggedFormat {
+    fn serialize(&self, public_key: &PublicKey) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![self.0];
+
+        bytes.extend_from_slice(public_key.as_bytes());
+        bytes
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<PublicKey, SignatureError> {
+        if bytes.first() != Some(&self.0) {
+            return Err(SignatureError::InvalidEncoding);
+        }
+        RawFormat.deserialize(&bytes[1..])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ed25519::keypair_for_testing;
+    use super::*;
+
+    #[test]
+    fn wire_formats_roundtrip() {
+        let public: PublicKey = keypair_for_testing("wire").public;
+        let formats: [&dyn WireFormat; 4] = [&RawFormat, &LengthPrefixedFormat(1),
+                                         &LengthPrefixedFormat(2), &TypeTaggedFormat(0xed)];
+
+        for format in formats.iter() {
+            assert_eq!(format.deserialize(&format.serialize(&public)), Ok(public));
+        }
+        assert_eq!(&LengthPrefixedFormat(1).serialize(&public)[..1], &[32]);
+        assert_eq!(&LengthPrefixedFormat(2).serialize(&public)[..2], &[0, 32]);
+        assert_eq!(&TypeTaggedFormat(0xed).serialize(&public)[..], &[&[0xed], &public.as_bytes()[..]].concat()[..]);
+    }
+
+    #[test]
+    fn wire_formats_reject_malformed_input() {
+        let public: PublicKey = keypair_for_testing("wire").public;
+        let tagged: Vec<u8> = TypeTaggedFormat(1).serialize(&public);
+
+        assert_eq!(RawFormat.deserialize(&tagged), Err(SignatureError::InvalidEncoding));
+        assert_eq!(TypeTaggedFormat(2).deserialize(&tagged), Err(SignatureError::InvalidEncoding));
+        assert_eq!(TypeTaggedFormat(1).deserialize(&tagged[..32]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(TypeTaggedFormat(1).deserialize(&[]), Err(SignatureError::InvalidEncoding));
+        assert_eq!(LengthPrefixedFormat(2).deserialize(&LengthPrefixedFormat(1).serialize(&public)),
+                   Err(SignatureError::InvalidEncoding));
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_prefix_must_be_one_or_two_bytes() {
+        LengthPrefixedFormat(4).serialize(&keypair_for_testing("wire").public);
+    }
+}
@@ -0,0 +1,132 @@
+// -*- mode: rust; -*-
+//
+// This file is part of ed25519-dalek.
+// Copyright (c) 2017 Isis Lovecruft
+// See LICENSE for licensing information.
+//
+// Authors:
+// - Isis Agora Lovecruft <isis@patternsinthevoid.net>
+
+//! Verification under the rules of ZIP-215, which fixes exactly which
+//! signatures Zcash consensus accepts, so that every node agrees.
+//!
+//! A signature `(R, s)` on a message `M` under a public key `A` is valid
+//! under ZIP-215 when:
+//!
+//! * `s` is less than ℓ,
+//! * `R` and `A` decode to points on the curve, where non-canonical
+//!   encodings (a y-coordinate of at least p, or a set sign bit with an
+//!   x-coordinate of zero) are accepted, and points of small order are
+//!   allowed, and
+//! * the cofactored equation `[8][s]B = [8]R + [8][k]A` holds, where `k` is
+//!   the hash of the encodings of `R` and `A`, exactly as given, followed by
+//!   `M`.
+//!
+//! This is more permissive than both `PublicKey::verify()` and
+//! `PublicKey::verify_strict()`, and has its own implementation, so changes
+//! to either cannot affect it.
+
+use digest::Digest;
+
+use generic_array::typenum::U64;
+
+use curve25519_dalek::edwards::ExtendedPoint;
+use curve25519_dalek::edwards::IsIdentity;
+use curve25519_dalek::edwards::vartime;
+use curve25519_dalek::scalar::Scalar;
+
+use ed25519::{PublicKey, Signature};
+
+impl PublicKey {
+    /// Verify a signature on a message with this public key, following
+    /// ZIP-215 exactly.
+    ///
+    /// # Return
+    ///
+    /// Returns `true` if the signature is valid under ZIP-215, and `false`
+    /// otherwise.
+    pub fn verify_zip215<D>(&self, message: &[u8], signature: &Signature) -> bool
+            where D: Digest<OutputSize = U64> + Default {
+
+        if !signature.has_canonical_s() {
+            return false;
+        }
+        let (a, r): (ExtendedPoint, ExtendedPoint) = match (self.0.decompress(), signature.r.decompress()) {
+            (Some(a), Some(r)) => (a, r),
+            _                  => return false,
+        };
+        let mut h: D = D::default();
+
+        h.input(signature.r.as_bytes());
+        h.input(self.as_bytes());
+        h.input(message);
+
+        let digest = h.fixed_result();
+        let k: Scalar = Scalar::reduce(array_ref!(digest, 0, 64));
+
+        // [s]B - [k]A - R, which must be killed by the cofactor.
+        let difference: ExtendedPoint = &vartime::double_scalar_mult_basepoint(&k, &-(&a), &signature.s) - &r;
+
+        difference.mult_by_cofactor().is_identity()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec::Vec;
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    use hex::FromHex;
+    use sha2::Sha512;
+    use ed25519::{Keypair, keypair_for_testing};
+    use super::*;
+
+    // Every encoding of a point of small order, canonical or not.  The ZIP-215
+    // test vectors are the signatures `(R, 0)` on "Zcash" under `A`, for each
+    // pair of these as `A` and `R`, and all of them are valid.
+    static SMALL_ORDER_ENCODINGS: [&str; 14] = [
+        "0100000000000000000000000000000000000000000000000000000000000000",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+        "0000000000000000000000000000000000000000000000000000000000000080",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+        "0100000000000000000000000000000000000000000000000000000000000080",
+        "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", ];
+
+    fn point(encoding: &str) -> CompressedEdwardsY {
+        let bytes: Vec<u8> = FromHex::from_hex(encoding).unwrap();
+
+        CompressedEdwardsY(*array_ref!(bytes, 0, 32))
+    }
+
+    #[test]
+    fn zip215_test_vectors() {
+        for a in SMALL_ORDER_ENCODINGS.iter() {
+            for r in SMALL_ORDER_ENCODINGS.iter() {
+                let public: PublicKey = PublicKey(point(a));
+                let signature: Signature = Signature{ r: point(r), s: Scalar::zero() };
+
+                assert!(public.verify_zip215::<Sha512>(b"Zcash", &signature), "A = {}, R = {}", a, r);
+            }
+        }
+    }
+
+    #[test]
+    fn zip215_agrees_with_verify_on_honest_signatures() {
+        let keypair: Keypair = keypair_for_testing("zip215");
+        let signature: Signature = keypair.sign::<Sha512>(b"test message");
+        let mut high_s: Signature = signature;
+
+        high_s.s[31] |= 0x10;
+        assert!(keypair.public.verify_zip215::<Sha512>(b"test message", &signature));
+        assert!(!keypair.public.verify_zip215::<Sha512>(b"test massage", &signature));
+        assert!(!keypair_for_testing("other").public.verify_zip215::<Sha512>(b"test message", &signature));
+        assert!(!keypair.public.verify_zip215::<Sha512>(b"test message", &high_s));
+    }
+}